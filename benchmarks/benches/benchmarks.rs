@@ -22,6 +22,7 @@ fn create_test_sound(num_samples: usize) -> StaticSoundData {
 		settings: StaticSoundSettings::new().loop_behavior(LoopBehavior {
 			start_position: 0.0,
 		}),
+		markers: Arc::new([]),
 	}
 }
 
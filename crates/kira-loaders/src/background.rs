@@ -0,0 +1,198 @@
+use std::{
+	fs::File,
+	future::Future,
+	io::{Read, Seek},
+	path::Path,
+	pin::Pin,
+	sync::{
+		atomic::{AtomicBool, AtomicU64, Ordering},
+		Arc, Mutex,
+	},
+	task::{Context, Poll, Waker},
+};
+
+use kira::sound::static_sound::StaticSoundData;
+use symphonia::core::io::MediaSource;
+
+use crate::{decode, source::SeekableSource, Error, FormatHint, LoadSettings};
+
+const TOTAL_FRAMES_UNKNOWN: u64 = u64::MAX;
+
+/// How much of a sound has been decoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LoadingProgress {
+	/// The number of frames that have been decoded so far.
+	pub decoded_frames: u64,
+	/// The number of frames the sound is expected to have,
+	/// if the file provides that information.
+	pub estimated_total_frames: Option<u64>,
+}
+
+impl LoadingProgress {
+	/// Returns the portion of the sound that has been decoded
+	/// (from `0.0` to `1.0`), if the total length of the sound
+	/// is known.
+	pub fn fraction(&self) -> Option<f64> {
+		self.estimated_total_frames.map(|total| {
+			if total == 0 {
+				1.0
+			} else {
+				(self.decoded_frames as f64 / total as f64).min(1.0)
+			}
+		})
+	}
+}
+
+struct LoadingShared {
+	decoded_frames: AtomicU64,
+	estimated_total_frames: AtomicU64,
+	cancelled: AtomicBool,
+	result: Mutex<Option<Result<StaticSoundData, Error>>>,
+	waker: Mutex<Option<Waker>>,
+}
+
+/// Checks on a sound that's being loaded on a background thread.
+///
+/// The loaded sound can be retrieved by polling
+/// [`LoadingHandle::try_take`], or by `.await`ing the handle.
+///
+/// When a [`LoadingHandle`] is dropped, loading will be cancelled.
+pub struct LoadingHandle {
+	shared: Arc<LoadingShared>,
+	taken: bool,
+}
+
+impl LoadingHandle {
+	/// Decodes a sound on a new thread. `open` is called on
+	/// that thread to get the audio data to decode.
+	fn spawn(
+		open: impl FnOnce() -> Result<Box<dyn MediaSource>, Error> + Send + 'static,
+		hint: Option<FormatHint>,
+		settings: LoadSettings,
+	) -> Self {
+		let shared = Arc::new(LoadingShared {
+			decoded_frames: AtomicU64::new(0),
+			estimated_total_frames: AtomicU64::new(TOTAL_FRAMES_UNKNOWN),
+			cancelled: AtomicBool::new(false),
+			result: Mutex::new(None),
+			waker: Mutex::new(None),
+		});
+		let thread_shared = shared.clone();
+		std::thread::spawn(move || {
+			let result = open().and_then(|media_source| {
+				decode(media_source, hint, settings, |progress| {
+					thread_shared
+						.decoded_frames
+						.store(progress.decoded_frames, Ordering::SeqCst);
+					thread_shared.estimated_total_frames.store(
+						progress
+							.estimated_total_frames
+							.unwrap_or(TOTAL_FRAMES_UNKNOWN),
+						Ordering::SeqCst,
+					);
+					!thread_shared.cancelled.load(Ordering::SeqCst)
+				})
+			});
+			*thread_shared.result.lock().unwrap() = Some(result);
+			if let Some(waker) = thread_shared.waker.lock().unwrap().take() {
+				waker.wake();
+			}
+		});
+		Self {
+			shared,
+			taken: false,
+		}
+	}
+
+	/// Returns how much of the sound has been decoded.
+	pub fn progress(&self) -> LoadingProgress {
+		LoadingProgress {
+			decoded_frames: self.shared.decoded_frames.load(Ordering::SeqCst),
+			estimated_total_frames: match self.shared.estimated_total_frames.load(Ordering::SeqCst)
+			{
+				TOTAL_FRAMES_UNKNOWN => None,
+				total => Some(total),
+			},
+		}
+	}
+
+	/// Returns `true` if loading has finished, successfully or not.
+	pub fn finished(&self) -> bool {
+		self.taken || self.shared.result.lock().unwrap().is_some()
+	}
+
+	/// Stops decoding the sound. The result of loading will be
+	/// [`Error::Cancelled`] unless the sound already finished loading.
+	pub fn cancel(&mut self) {
+		self.shared.cancelled.store(true, Ordering::SeqCst);
+	}
+
+	/// Returns the result of loading if it has finished, or `None`
+	/// if the sound is still loading.
+	///
+	/// # Panics
+	///
+	/// Panics if the result has already been taken.
+	pub fn try_take(&mut self) -> Option<Result<StaticSoundData, Error>> {
+		assert!(!self.taken, "The loading result was already taken");
+		let result = self.shared.result.lock().unwrap().take();
+		if result.is_some() {
+			self.taken = true;
+		}
+		result
+	}
+}
+
+impl Future for LoadingHandle {
+	type Output = Result<StaticSoundData, Error>;
+
+	fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+		let handle = self.get_mut();
+		// store the waker before checking the result so a result that
+		// arrives in between is never missed
+		*handle.shared.waker.lock().unwrap() = Some(cx.waker().clone());
+		match handle.try_take() {
+			Some(result) => Poll::Ready(result),
+			None => Poll::Pending,
+		}
+	}
+}
+
+impl Drop for LoadingHandle {
+	fn drop(&mut self) {
+		self.cancel();
+	}
+}
+
+/// Loads an audio file into a [`StaticSoundData`] on a background
+/// thread.
+///
+/// If the file can't be opened, the error is returned immediately.
+/// Any errors that occur while decoding are reported by the
+/// [`LoadingHandle`].
+pub fn load_in_background(
+	path: impl AsRef<Path>,
+	settings: impl Into<LoadSettings>,
+) -> Result<LoadingHandle, Error> {
+	let path = path.as_ref();
+	let file = File::open(path)?;
+	Ok(LoadingHandle::spawn(
+		move || Ok(Box::new(file)),
+		Some(FormatHint::from_path(path)),
+		settings.into(),
+	))
+}
+
+/// Loads audio data from any seekable source into a
+/// [`StaticSoundData`] on a background thread.
+pub fn load_from_reader_in_background(
+	reader: impl Read + Seek + Send + 'static,
+	hint: impl Into<Option<FormatHint>>,
+	settings: impl Into<LoadSettings>,
+) -> LoadingHandle {
+	LoadingHandle::spawn(
+		move || Ok(Box::new(SeekableSource::new(reader)?)),
+		hint.into(),
+		settings.into(),
+	)
+}
@@ -27,6 +27,41 @@ manager.play(kira_loaders::load(
 # Result::<(), Box<dyn std::error::Error>>::Ok(())
 ```
 
+### Loading a sound from memory
+
+```no_run
+use kira_loaders::{FormatHint, LoadSettings};
+
+static SOUND: &[u8] = &[/* include_bytes!("sound.ogg") */];
+let sound_data = kira_loaders::load_from_bytes(
+	SOUND,
+	FormatHint::new().extension("ogg"),
+	LoadSettings::new(),
+)?;
+# Result::<(), Box<dyn std::error::Error>>::Ok(())
+```
+
+### Loading a sound on a background thread
+
+Decoding a long sound can take a while. [`load_in_background`] decodes
+the sound on another thread and returns a [`LoadingHandle`], which can
+report progress, be cancelled, and be polled (or `.await`ed) for the
+loaded sound.
+
+```no_run
+use kira::sound::static_sound::StaticSoundSettings;
+
+let mut loading = kira_loaders::load_in_background("music.ogg", StaticSoundSettings::new())?;
+// every frame:
+if let Some(fraction) = loading.progress().fraction() {
+	println!("{:.0}% loaded", fraction * 100.0);
+}
+if let Some(result) = loading.try_take() {
+	let sound_data = result?;
+}
+# Result::<(), Box<dyn std::error::Error>>::Ok(())
+```
+
 ### Streaming a sound from disk
 
 ```no_run
@@ -46,6 +81,53 @@ manager.play(kira_loaders::stream(
 # Result::<(), Box<dyn std::error::Error>>::Ok(())
 ```
 
+### Streaming a sound from memory
+
+Sounds can also be streamed from data in memory or from any
+[`Read`](std::io::Read) + [`Seek`](std::io::Seek) source. Since there's
+no file name to detect the format from, you can provide a
+[`FormatHint`].
+
+```no_run
+use kira::manager::{backend::MockBackend, AudioManager, AudioManagerSettings};
+use kira_loaders::{FormatHint, StreamingSoundData, StreamingSoundSettings};
+
+let mut manager = AudioManager::new(
+	MockBackend::new(48_000),
+	AudioManagerSettings::default(),
+)
+.unwrap();
+let bytes = std::fs::read("sound.ogg")?;
+manager.play(StreamingSoundData::from_bytes(
+	bytes,
+	FormatHint::new().extension("ogg"),
+	StreamingSoundSettings::default(),
+)?)?;
+# Result::<(), Box<dyn std::error::Error>>::Ok(())
+```
+
+### Matching the backend's sample rate
+
+Sounds that have a different sample rate than the audio backend are
+resampled while they play. To convert them once when they're loaded
+instead, set [`LoadSettings::sample_rate`].
+
+```no_run
+use kira::manager::{backend::MockBackend, AudioManager, AudioManagerSettings};
+use kira_loaders::LoadSettings;
+
+let mut manager = AudioManager::new(
+	MockBackend::new(48_000),
+	AudioManagerSettings::default(),
+)
+.unwrap();
+manager.play(kira_loaders::load(
+	"sound.ogg",
+	LoadSettings::new().sample_rate(manager.sample_rate()),
+)?)?;
+# Result::<(), Box<dyn std::error::Error>>::Ok(())
+```
+
 ## Static vs. streaming sounds
 
 `kira-loaders` can load entire sounds into memory, but it can also
@@ -57,37 +139,142 @@ which is almost the same as [`StaticSoundSettings`]. Similarly,
 [`StreamingSoundHandle`]s are very similar to
 [`StaticSoundHandle`](kira::sound::static_sound::StaticSoundHandle)s.
 
+Streaming sounds are decoded by a [`DecoderPool`], a small group of
+threads shared by all streaming sounds.
+
 Streaming sounds have some disadvantages compared to static sounds:
 
 - Streaming sounds require more CPU power.
 - There may be a longer delay between when you call
   [`AudioManager::play`](kira::manager::AudioManager::play) and
-  when the sound actually starts playing.
+  when the sound actually starts playing, unless the beginning of the
+  sound is [preloaded](StreamingSoundData::preload).
 - Seeking the sound may also have a longer delay.
 - If the file cannot be read from the disk fast enough, there will be hiccups in
   the sound playback. (This will not affect other sounds, though.)
-- Backwards playback is not supported.
+- Playing backwards is less efficient, since audio has to be decoded
+  in chunks and then reversed.
 - [`StreamingSoundData`] cannot be cloned.
+
+## Markers
+
+Cue points stored in WAV files are loaded as
+[`Marker`](kira::sound::Marker)s on both static and streaming sounds,
+using the cue point's label as the marker name. When playback crosses a
+marker, the `AudioManager` emits an `Event::MarkerCrossed`.
+
+## Loudness
+
+Audio files often come from different sources and are mastered at
+different levels. [`Loudness::measure`] measures the integrated loudness
+(following EBU R128) and true peak of audio, and setting
+[`LoadSettings::target_loudness`] or
+[`StreamingSoundSettings::target_loudness`] amplifies sounds to a
+consistent loudness when they're loaded.
+
+```no_run
+use kira_loaders::{LoadSettings, Loudness};
+
+let sound_data = kira_loaders::load("sound.ogg", LoadSettings::new())?;
+let loudness = Loudness::of_static_sound(&sound_data);
+println!("{} LUFS, {} dBTP", loudness.integrated, loudness.true_peak);
+let normalized = kira_loaders::load("sound.ogg", LoadSettings::new().target_loudness(-16.0))?;
+# Result::<(), Box<dyn std::error::Error>>::Ok(())
+```
+
+## Playlists
+
+A [`PlaylistSoundData`] plays a sequence of streaming sounds back to
+back, optionally crossfading between them. More sounds can be added
+with the [`PlaylistHandle`] while the playlist is playing.
+
+```no_run
+use kira::manager::{backend::MockBackend, AudioManager, AudioManagerSettings};
+use kira_loaders::{PlaylistSettings, PlaylistSoundData, StreamingSoundSettings};
+
+let mut manager =
+	AudioManager::new(MockBackend::new(48_000), AudioManagerSettings::default()).unwrap();
+let mut playlist = manager.play(PlaylistSoundData::new(
+	[kira_loaders::stream("track1.ogg", StreamingSoundSettings::new())?],
+	PlaylistSettings::new().crossfade_duration(2.0),
+))?;
+playlist.enqueue(kira_loaders::stream("track2.ogg", StreamingSoundSettings::new())?)?;
+# Result::<(), Box<dyn std::error::Error>>::Ok(())
+```
+
+## Stem groups
+
+A [`StemGroupData`] plays several static or streaming sounds with a
+shared playhead, so they stay perfectly aligned when the group is
+paused, seeked, or looped. Each stem keeps its own volume and track.
+
+```no_run
+use kira::manager::{backend::MockBackend, AudioManager, AudioManagerSettings};
+use kira_loaders::{Stem, StemGroupData, StemGroupSettings, StreamingSoundSettings};
+
+let mut manager =
+	AudioManager::new(MockBackend::new(48_000), AudioManagerSettings::default()).unwrap();
+let mut group = manager.play(StemGroupData::new(
+	[
+		Stem::from(kira_loaders::stream("drums.ogg", StreamingSoundSettings::new())?),
+		Stem::from(kira_loaders::stream("strings.ogg", StreamingSoundSettings::new().volume(0.0))?),
+	],
+	StemGroupSettings::new(),
+))?;
+// bring in the strings
+group.set_stem_volume(1, 1.0)?;
+# Result::<(), Box<dyn std::error::Error>>::Ok(())
+```
 */
 
 #![warn(missing_docs)]
 #![allow(clippy::tabs_in_doc_comments)]
 
+mod background;
+mod load_settings;
+mod loudness;
+mod markers;
+mod playlist;
+mod source;
+mod stem_group;
 mod streaming;
 
+#[cfg(test)]
+mod test;
+
+pub use background::*;
 use kira::{
-	dsp::Frame,
-	sound::static_sound::{StaticSoundData, StaticSoundSettings},
+	dsp::{self, Frame},
+	sound::static_sound::StaticSoundData,
 };
+pub use load_settings::*;
+pub use loudness::*;
+pub use playlist::*;
+pub use source::FormatHint;
+use source::SeekableSource;
+pub use stem_group::*;
 pub use streaming::*;
 use symphonia::core::{
 	audio::{AudioBuffer, AudioBufferRef, Signal},
 	conv::{FromSample, IntoSample},
-	io::MediaSourceStream,
+	io::{MediaSource, MediaSourceStream},
 	sample::Sample,
 };
 
-use std::{fmt::Display, fs::File, path::Path, sync::Arc};
+use std::{
+	fmt::Display,
+	fs::File,
+	io::{Cursor, Read, Seek},
+	path::Path,
+	sync::Arc,
+};
+
+/// The most frames that are allocated ahead of time based on the
+/// length a file reports (10 minutes at 48kHz).
+const MAX_PREALLOCATED_FRAMES: u64 = 48_000 * 60 * 10;
+/// The number of frames processed between checks for cancellation
+/// when resampling or normalizing a sound.
+pub(crate) const FRAMES_PER_CHUNK: usize = 16_384;
 
 /// Errors that can occur when loading or streaming an audio file.
 #[derive(Debug)]
@@ -104,6 +291,8 @@ pub enum Error {
 	IoError(std::io::Error),
 	/// An error occurred when parsing the file.
 	SymphoniaError(symphonia::core::errors::Error),
+	/// Loading was cancelled with [`LoadingHandle::cancel`].
+	Cancelled,
 }
 
 impl Display for Error {
@@ -118,6 +307,7 @@ impl Display for Error {
 			}
 			Error::IoError(error) => error.fmt(f),
 			Error::SymphoniaError(error) => error.fmt(f),
+			Error::Cancelled => f.write_str("Loading was cancelled"),
 		}
 	}
 }
@@ -145,34 +335,97 @@ impl From<symphonia::core::errors::Error> for Error {
 }
 
 /// Loads an audio file into a [`StaticSoundData`].
+///
+/// This accepts either [`LoadSettings`] or
+/// [`StaticSoundSettings`](kira::sound::static_sound::StaticSoundSettings).
 pub fn load(
 	path: impl AsRef<Path>,
-	settings: StaticSoundSettings,
+	settings: impl Into<LoadSettings>,
+) -> Result<StaticSoundData, Error> {
+	let path = path.as_ref();
+	load_from_media_source(
+		Box::new(File::open(path)?),
+		FormatHint::from_path(path),
+		settings,
+	)
+}
+
+/// Loads audio data stored in memory (for example, with
+/// [`include_bytes`]) into a [`StaticSoundData`].
+pub fn load_from_bytes(
+	bytes: impl AsRef<[u8]> + Send + 'static,
+	hint: impl Into<Option<FormatHint>>,
+	settings: impl Into<LoadSettings>,
+) -> Result<StaticSoundData, Error> {
+	load_from_media_source(Box::new(Cursor::new(bytes)), hint, settings)
+}
+
+/// Loads audio data from any seekable source, like a file inside
+/// an archive, into a [`StaticSoundData`].
+pub fn load_from_reader(
+	reader: impl Read + Seek + Send + 'static,
+	hint: impl Into<Option<FormatHint>>,
+	settings: impl Into<LoadSettings>,
+) -> Result<StaticSoundData, Error> {
+	load_from_media_source(Box::new(SeekableSource::new(reader)?), hint, settings)
+}
+
+/// Loads audio data from a symphonia [`MediaSource`] into a
+/// [`StaticSoundData`].
+pub fn load_from_media_source(
+	media_source: Box<dyn MediaSource>,
+	hint: impl Into<Option<FormatHint>>,
+	settings: impl Into<LoadSettings>,
 ) -> Result<StaticSoundData, Error> {
+	decode(media_source, hint.into(), settings.into(), |_| true)
+}
+
+/// Decodes a whole sound, calling `on_progress` after each packet
+/// is decoded. If `on_progress` returns `false`, decoding stops and
+/// [`Error::Cancelled`] is returned.
+pub(crate) fn decode(
+	mut media_source: Box<dyn MediaSource>,
+	hint: Option<FormatHint>,
+	settings: LoadSettings,
+	mut on_progress: impl FnMut(LoadingProgress) -> bool,
+) -> Result<StaticSoundData, Error> {
+	let cue_points = markers::read_wav_cue_points(media_source.as_mut())?;
 	let codecs = symphonia::default::get_codecs();
 	let probe = symphonia::default::get_probe();
-	let file = File::open(path)?;
-	let mss = MediaSourceStream::new(Box::new(file), Default::default());
+	let hint = hint.unwrap_or_default().to_symphonia_hint();
+	let mss = MediaSourceStream::new(media_source, Default::default());
 	let mut format_reader = probe
-		.format(
-			&Default::default(),
-			mss,
-			&Default::default(),
-			&Default::default(),
-		)?
+		.format(&hint, mss, &Default::default(), &Default::default())?
 		.format;
 	let codec_params = &format_reader
 		.default_track()
 		.ok_or(Error::NoDefaultTrack)?
 		.codec_params;
 	let sample_rate = codec_params.sample_rate.ok_or(Error::UnknownSampleRate)?;
+	let mono = codec_params
+		.channels
+		.is_some_and(|channels| channels.count() == 1);
+	let estimated_total_frames = codec_params.n_frames;
 	let mut decoder = codecs.make(codec_params, &Default::default())?;
 	let mut frames = vec![];
+	// the header could claim any length, so don't trust it with
+	// too much memory up front
+	if let Some(estimated_total_frames) = estimated_total_frames {
+		frames.reserve(estimated_total_frames.min(MAX_PREALLOCATED_FRAMES) as usize);
+	}
+	let mut progress = LoadingProgress {
+		decoded_frames: 0,
+		estimated_total_frames,
+	};
 	loop {
 		match format_reader.next_packet() {
 			Ok(packet) => {
 				let buffer = decoder.decode(&packet)?;
 				load_frames_from_buffer_ref(&mut frames, &buffer)?;
+				progress.decoded_frames = frames.len() as u64;
+				if !on_progress(progress) {
+					return Err(Error::Cancelled);
+				}
 			}
 			Err(error) => match error {
 				symphonia::core::errors::Error::IoError(error) => {
@@ -185,11 +438,42 @@ pub fn load(
 			},
 		}
 	}
-	Ok(StaticSoundData {
+	let mut sound = StaticSoundData {
 		sample_rate,
 		frames: Arc::new(frames),
-		settings,
-	})
+		settings: settings.sound,
+		markers: cue_points
+			.into_iter()
+			.map(|cue_point| cue_point.into_marker(sample_rate))
+			.collect(),
+	};
+	// resampling and normalizing can take a while for long sounds,
+	// so keep checking whether loading was cancelled
+	if let Some(sample_rate) = settings.sample_rate {
+		if sample_rate != sound.sample_rate {
+			let frames = {
+				let mut resampled_frames =
+					dsp::resampled_frames(&sound.frames, sound.sample_rate, sample_rate);
+				let mut frames = Vec::with_capacity(resampled_frames.len());
+				while resampled_frames.len() > 0 {
+					frames.extend(resampled_frames.by_ref().take(FRAMES_PER_CHUNK));
+					if !on_progress(progress) {
+						return Err(Error::Cancelled);
+					}
+				}
+				frames
+			};
+			sound = StaticSoundData {
+				sample_rate,
+				frames: Arc::new(frames),
+				..sound
+			};
+		}
+	}
+	if let Some(target_loudness) = settings.target_loudness {
+		sound = loudness::normalize(sound, target_loudness, mono, || on_progress(progress))?;
+	}
+	Ok(sound)
 }
 
 /// Creates a [`StreamingSoundData`] for an audio file.
@@ -197,10 +481,10 @@ pub fn stream(
 	path: impl AsRef<Path>,
 	settings: StreamingSoundSettings,
 ) -> Result<StreamingSoundData, Error> {
-	StreamingSoundData::new(path, settings)
+	StreamingSoundData::from_file(path, settings)
 }
 
-fn load_frames_from_buffer_ref(
+pub(crate) fn load_frames_from_buffer_ref(
 	frames: &mut Vec<Frame>,
 	buffer: &AudioBufferRef,
 ) -> Result<(), Error> {
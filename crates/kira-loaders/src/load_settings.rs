@@ -0,0 +1,70 @@
+use kira::sound::static_sound::StaticSoundSettings;
+
+/// Settings for loading a sound into memory.
+///
+/// Any [`StaticSoundSettings`] can be used wherever [`LoadSettings`]
+/// are expected.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub struct LoadSettings {
+	/// Settings for the loaded sound.
+	pub sound: StaticSoundSettings,
+	/// If set, the sound will be amplified so its integrated
+	/// loudness matches this level (in LUFS).
+	pub target_loudness: Option<f64>,
+	/// If set, the sound will be converted to this sample rate
+	/// (in Hz) when it's loaded.
+	///
+	/// Use [`AudioManager::sample_rate`](kira::manager::AudioManager::sample_rate)
+	/// to avoid resampling the sound while it plays.
+	pub sample_rate: Option<u32>,
+}
+
+impl LoadSettings {
+	/// Creates a new [`LoadSettings`] with the default settings.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Sets the settings for the loaded sound.
+	pub fn sound(self, sound: StaticSoundSettings) -> Self {
+		Self { sound, ..self }
+	}
+
+	/// Sets the loudness (in LUFS) the sound should be
+	/// normalized to.
+	pub fn target_loudness(self, target_loudness: impl Into<Option<f64>>) -> Self {
+		Self {
+			target_loudness: target_loudness.into(),
+			..self
+		}
+	}
+
+	/// Sets the sample rate (in Hz) the sound should be
+	/// converted to.
+	pub fn sample_rate(self, sample_rate: impl Into<Option<u32>>) -> Self {
+		Self {
+			sample_rate: sample_rate.into(),
+			..self
+		}
+	}
+}
+
+impl Default for LoadSettings {
+	fn default() -> Self {
+		Self {
+			sound: StaticSoundSettings::default(),
+			target_loudness: None,
+			sample_rate: None,
+		}
+	}
+}
+
+impl From<StaticSoundSettings> for LoadSettings {
+	fn from(sound: StaticSoundSettings) -> Self {
+		Self {
+			sound,
+			..Default::default()
+		}
+	}
+}
@@ -0,0 +1,377 @@
+//! Loudness measurement following ITU-R BS.1770 and EBU R128.
+
+#[cfg(test)]
+mod test;
+
+use std::f64::consts::PI;
+
+use kira::{dsp::Frame, sound::static_sound::StaticSoundData};
+
+use crate::{Error, FRAMES_PER_CHUNK};
+
+/// The length of a gating block (in seconds).
+const BLOCK_DURATION: f64 = 0.4;
+/// The number of steps each gating block is divided into,
+/// which gives 75% overlap between blocks.
+const BLOCK_STEPS: usize = 4;
+/// Blocks quieter than this (in LUFS) are ignored.
+const ABSOLUTE_GATE: f64 = -70.0;
+/// Blocks more than this many LU below the ungated loudness
+/// are ignored.
+const RELATIVE_GATE: f64 = -10.0;
+/// The amount of oversampling used to find the true peak.
+const TRUE_PEAK_OVERSAMPLING: usize = 4;
+/// The number of input samples on each side of the interpolated
+/// sample used by the true peak interpolation filter.
+const TRUE_PEAK_FILTER_HALF_LENGTH: usize = 12;
+
+/// The measured loudness of a sound.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Loudness {
+	/// The integrated (gated, whole-sound) loudness (in LUFS).
+	///
+	/// This is [`f64::NEG_INFINITY`] for sounds that are silent
+	/// or shorter than 400ms.
+	pub integrated: f64,
+	/// The highest level of the reconstructed waveform (in dBTP),
+	/// including peaks between samples.
+	pub true_peak: f64,
+}
+
+impl Loudness {
+	/// Measures the loudness of a sequence of stereo frames.
+	///
+	/// Use [`Loudness::measure_mono`] for sounds that were loaded
+	/// from mono audio, which have the same signal in both channels.
+	pub fn measure(frames: &[Frame], sample_rate: u32) -> Self {
+		let mut meter = LoudnessMeter::new(sample_rate, false);
+		meter.add_frames(frames);
+		meter.finish()
+	}
+
+	/// Measures the loudness of a sequence of frames that hold mono
+	/// audio. Only the left channel is measured.
+	pub fn measure_mono(frames: &[Frame], sample_rate: u32) -> Self {
+		let mut meter = LoudnessMeter::new(sample_rate, true);
+		meter.add_frames(frames);
+		meter.finish()
+	}
+
+	/// Measures the loudness of a [`StaticSoundData`] as stereo audio.
+	pub fn of_static_sound(data: &StaticSoundData) -> Self {
+		Self::measure(&data.frames, data.sample_rate)
+	}
+
+	/// Returns the amplitude that a sound with this loudness should
+	/// be multiplied by to reach the target loudness (in LUFS).
+	///
+	/// Silent sounds aren't amplified.
+	pub fn gain_to(&self, target_loudness: f64) -> f64 {
+		if self.integrated.is_finite() {
+			10.0f64.powf((target_loudness - self.integrated) / 20.0)
+		} else {
+			1.0
+		}
+	}
+}
+
+/// Returns `sound` with its frames amplified so its integrated
+/// loudness matches `target_loudness` (in LUFS).
+///
+/// `keep_going` is called after each chunk of frames is processed.
+/// If it returns `false`, [`Error::Cancelled`] is returned.
+pub(crate) fn normalize(
+	sound: StaticSoundData,
+	target_loudness: f64,
+	mono: bool,
+	mut keep_going: impl FnMut() -> bool,
+) -> Result<StaticSoundData, Error> {
+	let mut meter = LoudnessMeter::new(sound.sample_rate, mono);
+	for chunk in sound.frames.chunks(FRAMES_PER_CHUNK) {
+		meter.add_frames(chunk);
+		if !keep_going() {
+			return Err(Error::Cancelled);
+		}
+	}
+	let gain = meter.finish().gain_to(target_loudness) as f32;
+	let mut frames = Vec::with_capacity(sound.frames.len());
+	for chunk in sound.frames.chunks(FRAMES_PER_CHUNK) {
+		frames.extend(chunk.iter().map(|frame| *frame * gain));
+		if !keep_going() {
+			return Err(Error::Cancelled);
+		}
+	}
+	Ok(StaticSoundData {
+		frames: frames.into(),
+		..sound
+	})
+}
+
+/// Measures loudness incrementally, so audio can be measured
+/// as it's decoded without keeping all of it in memory.
+pub(crate) struct LoudnessMeter {
+	/// Whether only the left channel should be measured.
+	mono: bool,
+	/// The number of frames in each step of a gating block.
+	step_length: usize,
+	filters: [KWeighting; 2],
+	/// The weighted energy of the step in progress.
+	step_energy: f64,
+	step_frames: usize,
+	/// The energies of the most recent steps, which make up
+	/// the most recent gating block.
+	recent_step_energies: [f64; BLOCK_STEPS],
+	num_steps: usize,
+	/// The mean square of each block that passed the absolute gate.
+	block_mean_squares: Vec<f64>,
+	true_peak_meters: [TruePeakMeter; 2],
+}
+
+impl LoudnessMeter {
+	pub fn new(sample_rate: u32, mono: bool) -> Self {
+		Self {
+			mono,
+			step_length: (BLOCK_DURATION * sample_rate as f64 / BLOCK_STEPS as f64).round()
+				as usize,
+			filters: [KWeighting::new(sample_rate), KWeighting::new(sample_rate)],
+			step_energy: 0.0,
+			step_frames: 0,
+			recent_step_energies: [0.0; BLOCK_STEPS],
+			num_steps: 0,
+			block_mean_squares: vec![],
+			true_peak_meters: [TruePeakMeter::new(), TruePeakMeter::new()],
+		}
+	}
+
+	pub fn add_frames(&mut self, frames: &[Frame]) {
+		for frame in frames {
+			self.add_frame(*frame);
+		}
+	}
+
+	pub fn add_frame(&mut self, frame: Frame) {
+		let num_channels = if self.mono { 1 } else { 2 };
+		let samples = [frame.left as f64, frame.right as f64];
+		for ((sample, filter), true_peak_meter) in samples
+			.iter()
+			.zip(&mut self.filters)
+			.zip(&mut self.true_peak_meters)
+			.take(num_channels)
+		{
+			self.step_energy += filter.process(*sample).powi(2);
+			true_peak_meter.add_sample(*sample);
+		}
+		if self.step_length == 0 {
+			return;
+		}
+		self.step_frames += 1;
+		if self.step_frames == self.step_length {
+			self.finish_step();
+		}
+	}
+
+	/// Records the energy of the step that just finished, and
+	/// the gating block that ends with it.
+	fn finish_step(&mut self) {
+		self.recent_step_energies[self.num_steps % BLOCK_STEPS] = self.step_energy;
+		self.num_steps += 1;
+		self.step_energy = 0.0;
+		self.step_frames = 0;
+		if self.num_steps < BLOCK_STEPS {
+			return;
+		}
+		let mean_square =
+			self.recent_step_energies.iter().sum::<f64>() / (self.step_length * BLOCK_STEPS) as f64;
+		if block_loudness(mean_square) > ABSOLUTE_GATE {
+			self.block_mean_squares.push(mean_square);
+		}
+	}
+
+	pub fn finish(mut self) -> Loudness {
+		let true_peak = self
+			.true_peak_meters
+			.iter_mut()
+			.map(TruePeakMeter::finish)
+			.fold(0.0, f64::max);
+		Loudness {
+			integrated: self.integrated_loudness(),
+			true_peak: 20.0 * true_peak.log10(),
+		}
+	}
+
+	fn integrated_loudness(&self) -> f64 {
+		if self.block_mean_squares.is_empty() {
+			return f64::NEG_INFINITY;
+		}
+		let relative_threshold = block_loudness(
+			self.block_mean_squares.iter().sum::<f64>() / self.block_mean_squares.len() as f64,
+		) + RELATIVE_GATE;
+		let (sum, count) = self
+			.block_mean_squares
+			.iter()
+			.filter(|mean_square| block_loudness(**mean_square) > relative_threshold)
+			.fold((0.0, 0), |(sum, count), mean_square| {
+				(sum + mean_square, count + 1)
+			});
+		if count == 0 {
+			return f64::NEG_INFINITY;
+		}
+		block_loudness(sum / count as f64)
+	}
+}
+
+/// A second-order IIR filter.
+struct Biquad {
+	b0: f64,
+	b1: f64,
+	b2: f64,
+	a1: f64,
+	a2: f64,
+	z1: f64,
+	z2: f64,
+}
+
+impl Biquad {
+	fn process(&mut self, input: f64) -> f64 {
+		let output = self.b0 * input + self.z1;
+		self.z1 = self.b1 * input - self.a1 * output + self.z2;
+		self.z2 = self.b2 * input - self.a2 * output;
+		output
+	}
+}
+
+/// The "K" frequency weighting from BS.1770, which roughly models
+/// how sensitive human hearing is to different frequencies.
+// The filter coefficients are derived for any sample rate using the
+// same approach as libebur128.
+struct KWeighting {
+	shelf: Biquad,
+	high_pass: Biquad,
+}
+
+impl KWeighting {
+	fn new(sample_rate: u32) -> Self {
+		let sample_rate = sample_rate as f64;
+		// stage 1: a high shelf that models the acoustic effect of the head
+		let f0 = 1681.974450955533;
+		let gain = 3.999843853973347;
+		let q = 0.7071752369554196;
+		let k = (PI * f0 / sample_rate).tan();
+		let vh = 10.0f64.powf(gain / 20.0);
+		let vb = vh.powf(0.4996667741545416);
+		let a0 = 1.0 + k / q + k * k;
+		let shelf = Biquad {
+			b0: (vh + vb * k / q + k * k) / a0,
+			b1: 2.0 * (k * k - vh) / a0,
+			b2: (vh - vb * k / q + k * k) / a0,
+			a1: 2.0 * (k * k - 1.0) / a0,
+			a2: (1.0 - k / q + k * k) / a0,
+			z1: 0.0,
+			z2: 0.0,
+		};
+		// stage 2: a high pass that removes very low frequencies
+		let f0 = 38.13547087602444;
+		let q = 0.5003270373238773;
+		let k = (PI * f0 / sample_rate).tan();
+		let a0 = 1.0 + k / q + k * k;
+		let high_pass = Biquad {
+			b0: 1.0,
+			b1: -2.0,
+			b2: 1.0,
+			a1: 2.0 * (k * k - 1.0) / a0,
+			a2: (1.0 - k / q + k * k) / a0,
+			z1: 0.0,
+			z2: 0.0,
+		};
+		Self { shelf, high_pass }
+	}
+
+	fn process(&mut self, input: f64) -> f64 {
+		self.high_pass.process(self.shelf.process(input))
+	}
+}
+
+fn block_loudness(mean_square: f64) -> f64 {
+	-0.691 + 10.0 * mean_square.log10()
+}
+
+/// Returns the coefficients of a windowed-sinc filter that
+/// interpolates a sample `phase` of the way between two samples.
+fn interpolation_filter(phase: f64) -> Vec<f64> {
+	let half_length = TRUE_PEAK_FILTER_HALF_LENGTH as isize;
+	(-half_length + 1..=half_length)
+		.map(|offset| {
+			let x = offset as f64 - phase;
+			let sinc = if x == 0.0 {
+				1.0
+			} else {
+				(PI * x).sin() / (PI * x)
+			};
+			// blackman window
+			let t = (x + half_length as f64) / (2 * half_length) as f64;
+			let window = 0.42 - 0.5 * (2.0 * PI * t).cos() + 0.08 * (4.0 * PI * t).cos();
+			sinc * window
+		})
+		.collect()
+}
+
+/// Finds the peak of one channel of audio, including peaks
+/// between samples, by oversampling it.
+struct TruePeakMeter {
+	filters: Vec<Vec<f64>>,
+	/// The most recent samples, oldest first.
+	history: [f64; 2 * TRUE_PEAK_FILTER_HALF_LENGTH],
+	num_samples: usize,
+	peak: f64,
+}
+
+impl TruePeakMeter {
+	fn new() -> Self {
+		Self {
+			filters: (1..TRUE_PEAK_OVERSAMPLING)
+				.map(|i| interpolation_filter(i as f64 / TRUE_PEAK_OVERSAMPLING as f64))
+				.collect(),
+			history: [0.0; 2 * TRUE_PEAK_FILTER_HALF_LENGTH],
+			num_samples: 0,
+			peak: 0.0,
+		}
+	}
+
+	fn add_sample(&mut self, sample: f64) {
+		self.peak = self.peak.max(sample.abs());
+		self.push_to_history(sample);
+	}
+
+	/// Adds a sample to the history and measures the interpolated
+	/// samples that can be computed now.
+	fn push_to_history(&mut self, sample: f64) {
+		self.history.rotate_left(1);
+		self.history[self.history.len() - 1] = sample;
+		self.num_samples += 1;
+		// the filter is centered between the two middle samples of
+		// the history, so the interpolated samples trail behind the
+		// newest sample. the audio is treated as silent before it
+		// starts, but the points between that silence and the first
+		// sample aren't measured.
+		if self.num_samples <= TRUE_PEAK_FILTER_HALF_LENGTH {
+			return;
+		}
+		for filter in &self.filters {
+			let interpolated = filter
+				.iter()
+				.zip(&self.history)
+				.map(|(coefficient, sample)| coefficient * sample)
+				.sum::<f64>();
+			self.peak = self.peak.max(interpolated.abs());
+		}
+	}
+
+	fn finish(&mut self) -> f64 {
+		// the audio is treated as silent after it ends, so the
+		// last few interpolated samples can be measured
+		for _ in 0..TRUE_PEAK_FILTER_HALF_LENGTH {
+			self.push_to_history(0.0);
+		}
+		self.peak
+	}
+}
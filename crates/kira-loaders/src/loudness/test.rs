@@ -0,0 +1,74 @@
+use std::f64::consts::PI;
+
+use kira::dsp::Frame;
+
+use super::{Loudness, LoudnessMeter};
+
+const SAMPLE_RATE: u32 = 48_000;
+
+fn sine(frequency: f64, amplitude: f64, duration: f64) -> Vec<Frame> {
+	(0..(duration * SAMPLE_RATE as f64) as usize)
+		.map(|i| {
+			let sample = amplitude * (2.0 * PI * frequency * i as f64 / SAMPLE_RATE as f64).sin();
+			Frame::from_mono(sample as f32)
+		})
+		.collect()
+}
+
+#[test]
+fn measures_sine_loudness() {
+	// a stereo 1kHz sine wave has about the same loudness (in LUFS)
+	// as its peak level (in dBFS)
+	let loudness = Loudness::measure(&sine(1000.0, 0.5, 2.0), SAMPLE_RATE);
+	assert!((loudness.integrated - -6.02).abs() < 0.1);
+	assert!((loudness.true_peak - -6.02).abs() < 0.1);
+}
+
+#[test]
+fn measures_inter_sample_peaks() {
+	// a sine wave at a quarter of the sample rate, offset by 45 degrees,
+	// never has a sample at its actual peak
+	let frames = (0..4800)
+		.map(|i| {
+			let sample = (PI / 2.0 * i as f64 + PI / 4.0).sin();
+			Frame::from_mono(sample as f32)
+		})
+		.collect::<Vec<_>>();
+	let loudness = Loudness::measure(&frames, SAMPLE_RATE);
+	assert!(loudness.true_peak > -0.5);
+}
+
+#[test]
+fn silence_has_no_loudness() {
+	let loudness = Loudness::measure(&[Frame::ZERO; 48_000], SAMPLE_RATE);
+	assert_eq!(loudness.integrated, f64::NEG_INFINITY);
+	assert_eq!(loudness.gain_to(-16.0), 1.0);
+}
+
+#[test]
+fn gain_reaches_target_loudness() {
+	let frames = sine(440.0, 0.1, 2.0);
+	let gain = Loudness::measure(&frames, SAMPLE_RATE).gain_to(-16.0) as f32;
+	let normalized = frames.iter().map(|frame| *frame * gain).collect::<Vec<_>>();
+	assert!((Loudness::measure(&normalized, SAMPLE_RATE).integrated - -16.0).abs() < 0.01);
+}
+
+#[test]
+fn mono_audio_is_measured_as_one_channel() {
+	let frames = sine(1000.0, 0.5, 2.0);
+	let stereo = Loudness::measure(&frames, SAMPLE_RATE);
+	let mono = Loudness::measure_mono(&frames, SAMPLE_RATE);
+	// playing the same signal on two channels adds about 3 LU
+	assert!((stereo.integrated - mono.integrated - 3.01).abs() < 0.01);
+	assert_eq!(stereo.true_peak, mono.true_peak);
+}
+
+#[test]
+fn measuring_in_chunks_gives_the_same_result() {
+	let frames = sine(440.0, 0.3, 1.5);
+	let mut meter = LoudnessMeter::new(SAMPLE_RATE, false);
+	for chunk in frames.chunks(1000) {
+		meter.add_frames(chunk);
+	}
+	assert_eq!(meter.finish(), Loudness::measure(&frames, SAMPLE_RATE));
+}
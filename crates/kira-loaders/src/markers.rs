@@ -0,0 +1,141 @@
+use std::{
+	collections::HashMap,
+	io::{self, SeekFrom},
+};
+
+use kira::sound::Marker;
+use symphonia::core::io::MediaSource;
+
+use crate::Error;
+
+/// Chunks bigger than this are assumed to be corrupt rather
+/// than read into memory.
+const MAX_CHUNK_SIZE: u32 = 1 << 20;
+
+/// A cue point read from a WAV file.
+///
+/// Cue points are stored in frames, so they can't be converted
+/// to [`Marker`]s until the sample rate is known.
+pub(crate) struct CuePoint {
+	id: u32,
+	frame: u64,
+	name: Option<String>,
+}
+
+impl CuePoint {
+	pub fn into_marker(self, sample_rate: u32) -> Marker {
+		Marker {
+			id: self.id,
+			position: self.frame as f64 / sample_rate as f64,
+			name: self.name,
+		}
+	}
+}
+
+/// Reads the cue points from the `cue ` chunk of a WAV file,
+/// along with their labels from the `LIST` chunk.
+///
+/// Symphonia doesn't read these chunks, so we read them ourselves
+/// before handing the source over. Sources that aren't WAV files
+/// have no cue points. The source is rewound afterwards.
+pub(crate) fn read_wav_cue_points(source: &mut dyn MediaSource) -> Result<Vec<CuePoint>, Error> {
+	if !source.is_seekable() {
+		return Ok(vec![]);
+	}
+	// cue points are nice to have, so a malformed file
+	// shouldn't keep the audio from loading
+	let cue_points = read_chunks(source).unwrap_or_default();
+	source.seek(SeekFrom::Start(0))?;
+	Ok(cue_points)
+}
+
+fn read_chunks(reader: &mut dyn MediaSource) -> io::Result<Vec<CuePoint>> {
+	let mut header = [0; 12];
+	reader.read_exact(&mut header)?;
+	if &header[0..4] != b"RIFF" || &header[8..12] != b"WAVE" {
+		return Ok(vec![]);
+	}
+	// the RIFF chunk contains all the other chunks
+	let riff_end = 8 + read_u32(&header[4..8]) as u64;
+	let mut position = header.len() as u64;
+	let mut cue_points = vec![];
+	let mut labels = HashMap::new();
+	while position < riff_end {
+		let mut chunk_header = [0; 8];
+		match reader.read_exact(&mut chunk_header) {
+			Ok(()) => {}
+			Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => break,
+			Err(error) => return Err(error),
+		}
+		let size = read_u32(&chunk_header[4..8]);
+		// chunks are padded to an even number of bytes
+		let padded_size = size as u64 + (size % 2) as u64;
+		position += chunk_header.len() as u64 + padded_size;
+		match &chunk_header[0..4] {
+			b"cue " | b"LIST" if size <= MAX_CHUNK_SIZE => {
+				let mut chunk = vec![0; size as usize];
+				reader.read_exact(&mut chunk)?;
+				if size % 2 == 1 {
+					reader.seek(SeekFrom::Current(1))?;
+				}
+				if &chunk_header[0..4] == b"cue " {
+					cue_points = parse_cue_chunk(&chunk);
+				} else if chunk.starts_with(b"adtl") {
+					parse_adtl_chunk(&chunk[4..], &mut labels);
+				}
+			}
+			_ => {
+				reader.seek(SeekFrom::Current(padded_size as i64))?;
+			}
+		}
+	}
+	for cue_point in &mut cue_points {
+		cue_point.name = labels.remove(&cue_point.id);
+	}
+	cue_points.sort_by_key(|cue_point| cue_point.frame);
+	Ok(cue_points)
+}
+
+/// Parses the list of cue points in a `cue ` chunk.
+fn parse_cue_chunk(chunk: &[u8]) -> Vec<CuePoint> {
+	const CUE_POINT_SIZE: usize = 24;
+	if chunk.len() < 4 {
+		return vec![];
+	}
+	let num_cue_points = read_u32(&chunk[0..4]) as usize;
+	chunk[4..]
+		.chunks_exact(CUE_POINT_SIZE)
+		.take(num_cue_points)
+		.map(|cue_point| CuePoint {
+			id: read_u32(&cue_point[0..4]),
+			frame: read_u32(&cue_point[20..24]) as u64,
+			name: None,
+		})
+		.collect()
+}
+
+/// Collects the `labl` sub-chunks of an associated data list,
+/// which give names to cue points.
+fn parse_adtl_chunk(mut chunk: &[u8], labels: &mut HashMap<u32, String>) {
+	while chunk.len() >= 8 {
+		let size = read_u32(&chunk[4..8]) as usize;
+		let body = match chunk.get(8..8 + size) {
+			Some(body) => body,
+			None => break,
+		};
+		if &chunk[0..4] == b"labl" && body.len() >= 4 {
+			let text = &body[4..];
+			// the text is null-terminated
+			let text = text.split(|byte| *byte == 0).next().unwrap_or(text);
+			labels.insert(
+				read_u32(&body[0..4]),
+				String::from_utf8_lossy(text).into_owned(),
+			);
+		}
+		chunk = chunk.get(8 + size + size % 2..).unwrap_or_default();
+	}
+}
+
+fn read_u32(bytes: &[u8]) -> u32 {
+	u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
@@ -0,0 +1,29 @@
+mod data;
+mod handle;
+mod settings;
+mod sound;
+
+#[cfg(test)]
+mod test;
+
+pub use data::*;
+pub use handle::*;
+pub use settings::*;
+
+use crate::streaming::sound::StreamingSound;
+
+/// A unique identifier for an item in a playlist.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PlaylistItemId(pub(crate) u64);
+
+pub(crate) struct Item {
+	id: PlaylistItemId,
+	sound: StreamingSound,
+}
+
+pub(crate) enum PlaylistCommand {
+	Enqueue(Box<Item>),
+	Skip,
+	Clear,
+	Stop,
+}
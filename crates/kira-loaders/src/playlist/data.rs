@@ -0,0 +1,104 @@
+use std::{collections::VecDeque, sync::Arc};
+
+use kira::sound::{SoundData, SoundId};
+use ringbuf::RingBuffer;
+
+use crate::{Error, StreamingSoundData};
+
+use super::{
+	sound::{PlaylistShared, PlaylistSound},
+	Item, PlaylistHandle, PlaylistItemId, PlaylistSettings,
+};
+
+/// The number of commands that can be sent to the playlist
+/// in addition to queueing items.
+const EXTRA_COMMAND_CAPACITY: usize = 8;
+
+/// A sequence of streaming sounds that play one after another.
+///
+/// Each item starts decoding as soon as it's added to the
+/// playlist, so there's no gap between items.
+pub struct PlaylistSoundData {
+	/// The sounds that will play first.
+	pub items: Vec<StreamingSoundData>,
+	/// Settings for the playlist.
+	pub settings: PlaylistSettings,
+}
+
+impl PlaylistSoundData {
+	/// Creates a new [`PlaylistSoundData`] that will play the
+	/// given sounds in order.
+	pub fn new(
+		items: impl IntoIterator<Item = StreamingSoundData>,
+		settings: PlaylistSettings,
+	) -> Self {
+		Self {
+			items: items.into_iter().collect(),
+			settings,
+		}
+	}
+}
+
+impl PlaylistSoundData {
+	#[allow(clippy::type_complexity)]
+	fn split(
+		self,
+		sound_id: Option<SoundId>,
+	) -> Result<(Box<dyn kira::sound::Sound>, PlaylistHandle), Error> {
+		let capacity = self.settings.capacity.max(self.items.len());
+		let mut items = VecDeque::with_capacity(capacity);
+		let mut item_handles = VecDeque::with_capacity(capacity);
+		for (i, data) in self.items.into_iter().enumerate() {
+			let id = PlaylistItemId(i as u64 + 1);
+			let (sound, handle) = data.into_streaming_sound(sound_id)?;
+			items.push_back(Box::new(Item { id, sound }));
+			item_handles.push_back((id, handle));
+		}
+		let num_items = items.len() as u64;
+		let (command_producer, command_consumer) =
+			RingBuffer::new(capacity + EXTRA_COMMAND_CAPACITY).split();
+		// this has room for the queued items, plus the current and
+		// incoming items and one item enqueued after the handle last
+		// emptied the buffer
+		let (unused_item_producer, unused_item_consumer) =
+			RingBuffer::new(capacity + EXTRA_COMMAND_CAPACITY).split();
+		let shared = Arc::new(PlaylistShared::new());
+		Ok((
+			Box::new(PlaylistSound::new(
+				self.settings,
+				items,
+				command_consumer,
+				unused_item_producer,
+				shared.clone(),
+			)),
+			PlaylistHandle {
+				id: sound_id,
+				shared,
+				command_producer,
+				unused_item_consumer,
+				item_handles,
+				enqueued: num_items,
+				capacity,
+			},
+		))
+	}
+}
+
+impl SoundData for PlaylistSoundData {
+	type Error = Error;
+
+	type Handle = PlaylistHandle;
+
+	#[allow(clippy::type_complexity)]
+	fn into_sound(self) -> Result<(Box<dyn kira::sound::Sound>, Self::Handle), Self::Error> {
+		self.split(None)
+	}
+
+	#[allow(clippy::type_complexity)]
+	fn into_sound_with_id(
+		self,
+		id: SoundId,
+	) -> Result<(Box<dyn kira::sound::Sound>, Self::Handle), Self::Error> {
+		self.split(Some(id))
+	}
+}
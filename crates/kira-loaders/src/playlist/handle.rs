@@ -0,0 +1,164 @@
+use std::{collections::VecDeque, fmt::Display, sync::Arc};
+
+use kira::sound::SoundId;
+use ringbuf::{Consumer, Producer};
+
+use crate::{CommandQueueFull, Error, StreamingSoundData, StreamingSoundHandle};
+
+use super::{sound::PlaylistShared, Item, PlaylistCommand, PlaylistItemId};
+
+/// Errors that can occur when adding a sound to a playlist.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum EnqueueError {
+	/// The playlist can't queue up any more items.
+	QueueFull,
+	/// An error occurred when preparing the sound.
+	Error(Error),
+}
+
+impl Display for EnqueueError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			EnqueueError::QueueFull => {
+				f.write_str("Cannot add the sound because the playlist's queue is full")
+			}
+			EnqueueError::Error(error) => error.fmt(f),
+		}
+	}
+}
+
+impl std::error::Error for EnqueueError {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			EnqueueError::Error(error) => Some(error),
+			_ => None,
+		}
+	}
+}
+
+impl From<Error> for EnqueueError {
+	fn from(v: Error) -> Self {
+		Self::Error(v)
+	}
+}
+
+/// Controls a playlist.
+///
+/// When the handle is dropped, the playlist will finish once
+/// it runs out of items to play.
+pub struct PlaylistHandle {
+	pub(crate) id: Option<SoundId>,
+	pub(crate) shared: Arc<PlaylistShared>,
+	pub(crate) command_producer: Producer<PlaylistCommand>,
+	pub(crate) unused_item_consumer: Consumer<Box<Item>>,
+	/// Handles for the items that are queued up or playing.
+	pub(crate) item_handles: VecDeque<(PlaylistItemId, StreamingSoundHandle)>,
+	/// The number of items that have been added to the playlist.
+	pub(crate) enqueued: u64,
+	pub(crate) capacity: usize,
+}
+
+impl PlaylistHandle {
+	/// Returns the unique identifier for the playlist, or `None`
+	/// if the playlist wasn't started by an
+	/// [`AudioManager`](kira::manager::AudioManager).
+	pub fn id(&self) -> Option<SoundId> {
+		self.id
+	}
+
+	/// Returns the ID of the item that's currently playing, if any.
+	pub fn current_item(&self) -> Option<PlaylistItemId> {
+		self.shared.current_item()
+	}
+
+	/// Returns the number of items waiting to be played.
+	pub fn num_queued(&self) -> usize {
+		(self.enqueued - self.shared.dequeued()) as usize
+	}
+
+	/// Returns `true` if the playlist was stopped.
+	pub fn stopped(&self) -> bool {
+		self.shared.stopped()
+	}
+
+	/// Adds a sound to the end of the playlist.
+	///
+	/// The sound starts decoding right away so it's ready
+	/// to play when the previous item finishes.
+	pub fn enqueue(&mut self, data: StreamingSoundData) -> Result<PlaylistItemId, EnqueueError> {
+		self.collect_unused_items();
+		if self.num_queued() >= self.capacity {
+			return Err(EnqueueError::QueueFull);
+		}
+		let (sound, handle) = data.into_streaming_sound(self.id)?;
+		let id = PlaylistItemId(self.enqueued + 1);
+		self.command_producer
+			.push(PlaylistCommand::Enqueue(Box::new(Item { id, sound })))
+			.map_err(|_| EnqueueError::QueueFull)?;
+		self.enqueued += 1;
+		self.item_handles.push_back((id, handle));
+		Ok(id)
+	}
+
+	/// Returns the handle for an item that's queued up or playing.
+	pub fn item(&mut self, id: PlaylistItemId) -> Option<&mut StreamingSoundHandle> {
+		self.collect_unused_items();
+		self.item_handles
+			.iter_mut()
+			.find(|(item_id, _)| *item_id == id)
+			.map(|(_, handle)| handle)
+	}
+
+	/// Moves on to the next item, crossfading to it if the
+	/// playlist has a crossfade duration.
+	pub fn skip(&mut self) -> Result<(), CommandQueueFull> {
+		self.collect_unused_items();
+		self.command_producer
+			.push(PlaylistCommand::Skip)
+			.map_err(|_| CommandQueueFull)
+	}
+
+	/// Removes all of the items that are waiting to be played.
+	///
+	/// The current item keeps playing.
+	pub fn clear(&mut self) -> Result<(), CommandQueueFull> {
+		self.collect_unused_items();
+		self.command_producer
+			.push(PlaylistCommand::Clear)
+			.map_err(|_| CommandQueueFull)
+	}
+
+	/// Stops the playlist.
+	pub fn stop(&mut self) -> Result<(), CommandQueueFull> {
+		self.command_producer
+			.push(PlaylistCommand::Stop)
+			.map_err(|_| CommandQueueFull)
+	}
+
+	/// Returns an error that occurred while decoding one of the
+	/// playlist's items, if any.
+	pub fn pop_error(&mut self) -> Option<(PlaylistItemId, Error)> {
+		self.collect_unused_items();
+		self.item_handles
+			.iter_mut()
+			.find_map(|(id, handle)| handle.pop_error().map(|error| (*id, error)))
+	}
+
+	/// Deallocates items the playlist is done with and forgets
+	/// their handles.
+	fn collect_unused_items(&mut self) {
+		// items are sent back once they've finished playing,
+		// been skipped, or been cleared from the queue
+		while let Some(item) = self.unused_item_consumer.pop() {
+			self.item_handles
+				.retain_mut(|(id, handle)| *id != item.id || handle.has_errors());
+		}
+	}
+}
+
+impl Drop for PlaylistHandle {
+	fn drop(&mut self) {
+		self.shared.mark_handle_dropped();
+	}
+}
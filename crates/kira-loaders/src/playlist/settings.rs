@@ -0,0 +1,60 @@
+use kira::track::TrackId;
+
+/// Settings for a playlist.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub struct PlaylistSettings {
+	/// The mixer track the playlist should play on.
+	pub track: TrackId,
+	/// How long each item should crossfade with the next one
+	/// (in seconds).
+	///
+	/// If this is `0.0`, items play back to back with no gap.
+	/// Items whose length isn't known, and items that loop, are
+	/// not crossfaded when they end, but they are crossfaded when
+	/// they're skipped.
+	pub crossfade_duration: f64,
+	/// The maximum number of items that can be queued up
+	/// at once.
+	pub capacity: usize,
+}
+
+impl PlaylistSettings {
+	/// Creates a new [`PlaylistSettings`] with the default settings.
+	pub fn new() -> Self {
+		Self {
+			track: TrackId::Main,
+			crossfade_duration: 0.0,
+			capacity: 32,
+		}
+	}
+
+	/// Sets the mixer track the playlist should play on.
+	pub fn track(self, track: impl Into<TrackId>) -> Self {
+		Self {
+			track: track.into(),
+			..self
+		}
+	}
+
+	/// Sets how long each item should crossfade with the next one
+	/// (in seconds).
+	pub fn crossfade_duration(self, crossfade_duration: f64) -> Self {
+		Self {
+			crossfade_duration,
+			..self
+		}
+	}
+
+	/// Sets the maximum number of items that can be queued up
+	/// at once.
+	pub fn capacity(self, capacity: usize) -> Self {
+		Self { capacity, ..self }
+	}
+}
+
+impl Default for PlaylistSettings {
+	fn default() -> Self {
+		Self::new()
+	}
+}
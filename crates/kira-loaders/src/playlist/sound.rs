@@ -0,0 +1,227 @@
+use std::{
+	collections::VecDeque,
+	f64::consts::FRAC_PI_2,
+	sync::{
+		atomic::{AtomicBool, AtomicU64, Ordering},
+		Arc,
+	},
+};
+
+use kira::{clock::Clocks, dsp::Frame, parameter::Parameters, sound::Sound, track::TrackId};
+use ringbuf::{Consumer, Producer};
+
+use super::{Item, PlaylistCommand, PlaylistItemId, PlaylistSettings};
+
+pub(crate) struct PlaylistShared {
+	/// The ID of the item that's currently playing, or 0
+	/// if nothing is playing.
+	current_item: AtomicU64,
+	/// The number of items that have been removed from the queue,
+	/// either to be played or because the queue was cleared.
+	dequeued: AtomicU64,
+	handle_dropped: AtomicBool,
+	stopped: AtomicBool,
+}
+
+impl PlaylistShared {
+	pub fn new() -> Self {
+		Self {
+			current_item: AtomicU64::new(0),
+			dequeued: AtomicU64::new(0),
+			handle_dropped: AtomicBool::new(false),
+			stopped: AtomicBool::new(false),
+		}
+	}
+
+	pub fn current_item(&self) -> Option<PlaylistItemId> {
+		match self.current_item.load(Ordering::SeqCst) {
+			0 => None,
+			id => Some(PlaylistItemId(id)),
+		}
+	}
+
+	pub fn dequeued(&self) -> u64 {
+		self.dequeued.load(Ordering::SeqCst)
+	}
+
+	pub fn stopped(&self) -> bool {
+		self.stopped.load(Ordering::SeqCst)
+	}
+
+	pub fn mark_handle_dropped(&self) {
+		self.handle_dropped.store(true, Ordering::SeqCst);
+	}
+}
+
+pub(crate) struct PlaylistSound {
+	command_consumer: Consumer<PlaylistCommand>,
+	/// Sends finished items back to the handle so they aren't
+	/// deallocated on the audio thread.
+	unused_item_producer: Producer<Box<Item>>,
+	track: TrackId,
+	crossfade_duration: f64,
+	current: Option<Box<Item>>,
+	/// The item that's fading in while the current
+	/// item fades out.
+	incoming: Option<Box<Item>>,
+	crossfade_progress: f64,
+	queue: VecDeque<Box<Item>>,
+	stopped: bool,
+	shared: Arc<PlaylistShared>,
+}
+
+impl PlaylistSound {
+	pub fn new(
+		settings: PlaylistSettings,
+		items: VecDeque<Box<Item>>,
+		command_consumer: Consumer<PlaylistCommand>,
+		unused_item_producer: Producer<Box<Item>>,
+		shared: Arc<PlaylistShared>,
+	) -> Self {
+		Self {
+			command_consumer,
+			unused_item_producer,
+			track: settings.track,
+			crossfade_duration: settings.crossfade_duration.max(0.0),
+			current: None,
+			incoming: None,
+			crossfade_progress: 0.0,
+			queue: items,
+			stopped: false,
+			shared,
+		}
+	}
+
+	fn pop_queue(&mut self) -> Option<Box<Item>> {
+		let item = self.queue.pop_front()?;
+		self.shared.dequeued.fetch_add(1, Ordering::SeqCst);
+		Some(item)
+	}
+
+	fn retire(&mut self, item: Box<Item>) {
+		// the handle empties the buffer before queueing more items,
+		// and the buffer has room for every item the playlist can
+		// hold at once, so it can't fill up
+		if self.unused_item_producer.push(item).is_err() {
+			panic!("Unused item producer is full");
+		}
+	}
+
+	fn start_crossfade(&mut self) {
+		self.incoming = self.pop_queue();
+		self.crossfade_progress = 0.0;
+	}
+
+	/// Replaces the current item with the item that was fading in.
+	fn finish_crossfade(&mut self) {
+		if let Some(incoming) = self.incoming.take() {
+			if let Some(current) = self.current.replace(incoming) {
+				self.retire(current);
+			}
+		}
+	}
+
+	fn should_start_crossfade(&self) -> bool {
+		if self.crossfade_duration <= 0.0 || self.incoming.is_some() || self.queue.is_empty() {
+			return false;
+		}
+		match self
+			.current
+			.as_ref()
+			.and_then(|item| item.sound.remaining_duration())
+		{
+			Some(remaining_duration) => remaining_duration <= self.crossfade_duration,
+			None => false,
+		}
+	}
+
+	fn skip(&mut self) {
+		self.finish_crossfade();
+		if self.crossfade_duration > 0.0 && !self.queue.is_empty() {
+			self.start_crossfade();
+		} else if let Some(current) = self.current.take() {
+			self.retire(current);
+		}
+	}
+
+	fn clear(&mut self) {
+		while let Some(item) = self.pop_queue() {
+			self.retire(item);
+		}
+	}
+}
+
+impl Sound for PlaylistSound {
+	fn track(&mut self) -> TrackId {
+		self.track
+	}
+
+	fn on_start_processing(&mut self) {
+		while let Some(command) = self.command_consumer.pop() {
+			match command {
+				PlaylistCommand::Enqueue(item) => self.queue.push_back(item),
+				PlaylistCommand::Skip => self.skip(),
+				PlaylistCommand::Clear => self.clear(),
+				PlaylistCommand::Stop => self.stopped = true,
+			}
+		}
+		for item in self.current.iter_mut().chain(self.incoming.iter_mut()) {
+			item.sound.on_start_processing();
+		}
+		self.shared.current_item.store(
+			self.current.as_ref().map(|item| item.id.0).unwrap_or(0),
+			Ordering::SeqCst,
+		);
+		self.shared.stopped.store(self.stopped, Ordering::SeqCst);
+	}
+
+	fn process(&mut self, dt: f64, parameters: &Parameters, clocks: &Clocks) -> Frame {
+		if self.stopped {
+			return Frame::ZERO;
+		}
+		if self.current.is_none() {
+			self.current = self.pop_queue();
+		}
+		if self.should_start_crossfade() {
+			self.start_crossfade();
+		}
+		let current = match &mut self.current {
+			Some(current) => current,
+			None => return Frame::ZERO,
+		};
+		let out = match &mut self.incoming {
+			Some(incoming) => {
+				// use an equal power crossfade so the volume
+				// doesn't dip in the middle
+				self.crossfade_progress =
+					(self.crossfade_progress + dt / self.crossfade_duration).min(1.0);
+				let angle = self.crossfade_progress * FRAC_PI_2;
+				current.sound.process(dt, parameters, clocks) * angle.cos() as f32
+					+ incoming.sound.process(dt, parameters, clocks) * angle.sin() as f32
+			}
+			None => current.sound.process(dt, parameters, clocks),
+		};
+		let current_finished = current.sound.finished();
+		if self.incoming.is_some() {
+			if self.crossfade_progress >= 1.0 || current_finished {
+				self.finish_crossfade();
+			}
+		} else if current_finished {
+			// start the next item right away so there's
+			// no gap between items
+			if let Some(current) = self.current.take() {
+				self.retire(current);
+			}
+			self.current = self.pop_queue();
+		}
+		out
+	}
+
+	fn finished(&self) -> bool {
+		self.stopped
+			|| (self.shared.handle_dropped.load(Ordering::SeqCst)
+				&& self.current.is_none()
+				&& self.incoming.is_none()
+				&& self.queue.is_empty())
+	}
+}
@@ -0,0 +1,136 @@
+use std::time::Duration;
+
+use kira::dsp::{Frame, Interpolation};
+
+use crate::{
+	test::{create_manager, create_wav, SAMPLE_RATE},
+	DecoderPool, FormatHint, StreamingSoundData, StreamingSoundSettings,
+};
+
+use super::{EnqueueError, PlaylistSettings, PlaylistSoundData};
+
+/// Creates a streaming sound that's fully decoded ahead of time,
+/// so the tests don't depend on the decoder threads.
+fn create_item() -> StreamingSoundData {
+	create_item_in(&DecoderPool::global())
+}
+
+/// Like [`create_item`], but the sound is managed by the
+/// given decoder pool.
+fn create_item_in(pool: &DecoderPool) -> StreamingSoundData {
+	let mut data = StreamingSoundData::from_bytes(
+		create_wav(),
+		FormatHint::new().extension("wav"),
+		StreamingSoundSettings::new()
+			.interpolation(Interpolation::Nearest)
+			.decoder_pool(pool.clone()),
+	)
+	.unwrap();
+	data.preload(1.0).unwrap();
+	data
+}
+
+/// Waits for the decoder threads to notice that every sound in
+/// the pool has been fully decoded.
+fn wait_for_decoding(pool: &DecoderPool) {
+	for _ in 0..1000 {
+		if pool.num_streams() == 0 {
+			break;
+		}
+		std::thread::sleep(Duration::from_millis(1));
+	}
+	assert_eq!(pool.num_streams(), 0);
+}
+
+#[test]
+fn plays_items_without_gaps() {
+	let mut manager = create_manager();
+	let pool = DecoderPool::new(1);
+	let handle = manager
+		.play(PlaylistSoundData::new(
+			[create_item_in(&pool), create_item_in(&pool)],
+			PlaylistSettings::new(),
+		))
+		.unwrap();
+	wait_for_decoding(&pool);
+	let backend = manager.backend_mut();
+	backend.on_start_processing();
+	for _ in 0..SAMPLE_RATE {
+		assert_ne!(backend.process(), Frame::ZERO);
+	}
+	backend.on_start_processing();
+	let second_item = handle.current_item().unwrap();
+	for _ in 0..SAMPLE_RATE {
+		assert_ne!(backend.process(), Frame::ZERO);
+	}
+	assert_eq!(backend.process(), Frame::ZERO);
+	backend.on_start_processing();
+	assert_ne!(handle.current_item(), Some(second_item));
+	assert_eq!(handle.num_queued(), 0);
+}
+
+#[test]
+fn crossfades_between_items() {
+	let mut manager = create_manager();
+	let mut handle = manager
+		.play(PlaylistSoundData::new(
+			[create_item()],
+			PlaylistSettings::new().crossfade_duration(0.1),
+		))
+		.unwrap();
+	let second_item = handle.enqueue(create_item()).unwrap();
+	let backend = manager.backend_mut();
+	// the first call adds the playlist to the renderer, and the
+	// second one delivers the queued item to the playlist
+	backend.on_start_processing();
+	backend.on_start_processing();
+	// the crossfade starts 0.1 seconds before the end of the first
+	// item. process a few extra frames in case it finishes a little
+	// late due to rounding errors
+	for _ in 0..SAMPLE_RATE + 8 {
+		backend.process();
+	}
+	backend.on_start_processing();
+	assert_eq!(handle.current_item(), Some(second_item));
+	let position = handle.item(second_item).unwrap().position();
+	assert!((position - 0.1).abs() < 0.01);
+}
+
+#[test]
+fn skip_and_clear() {
+	let mut manager = create_manager();
+	let mut handle = manager
+		.play(PlaylistSoundData::new(
+			[],
+			PlaylistSettings::new().capacity(3),
+		))
+		.unwrap();
+	let ids = (0..3)
+		.map(|_| handle.enqueue(create_item()).unwrap())
+		.collect::<Vec<_>>();
+	assert!(matches!(
+		handle.enqueue(create_item()),
+		Err(EnqueueError::QueueFull)
+	));
+	assert_eq!(handle.num_queued(), 3);
+	let backend = manager.backend_mut();
+	backend.on_start_processing();
+	backend.on_start_processing();
+	backend.process();
+	backend.on_start_processing();
+	assert_eq!(handle.current_item(), Some(ids[0]));
+	assert_eq!(handle.num_queued(), 2);
+	handle.skip().unwrap();
+	backend.on_start_processing();
+	backend.process();
+	backend.on_start_processing();
+	assert_eq!(handle.current_item(), Some(ids[1]));
+	handle.clear().unwrap();
+	backend.on_start_processing();
+	assert_eq!(handle.num_queued(), 0);
+	assert_eq!(handle.current_item(), Some(ids[1]));
+	// the handles for skipped and cleared items are dropped
+	assert!(handle.item(ids[0]).is_none());
+	assert!(handle.item(ids[1]).is_some());
+	assert!(handle.item(ids[2]).is_none());
+}
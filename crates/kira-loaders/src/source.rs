@@ -0,0 +1,100 @@
+use std::{
+	io::{Read, Seek, SeekFrom},
+	path::Path,
+};
+
+use symphonia::core::{io::MediaSource, probe::Hint};
+
+/// Information about the format of audio data, which helps
+/// detect the format when there's no file name to go by.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub struct FormatHint {
+	/// The file extension typically used for the format
+	/// (e.g. `"ogg"`).
+	pub extension: Option<String>,
+	/// The MIME type of the format (e.g. `"audio/ogg"`).
+	pub mime_type: Option<String>,
+}
+
+impl FormatHint {
+	/// Creates a new [`FormatHint`] with no information.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Sets the file extension typically used for the format
+	/// (e.g. `"ogg"`).
+	pub fn extension(self, extension: impl Into<String>) -> Self {
+		Self {
+			extension: Some(extension.into()),
+			..self
+		}
+	}
+
+	/// Sets the MIME type of the format (e.g. `"audio/ogg"`).
+	pub fn mime_type(self, mime_type: impl Into<String>) -> Self {
+		Self {
+			mime_type: Some(mime_type.into()),
+			..self
+		}
+	}
+
+	/// Creates a hint from the extension of a file path.
+	pub(crate) fn from_path(path: &Path) -> Self {
+		match path.extension().and_then(|extension| extension.to_str()) {
+			Some(extension) => Self::new().extension(extension),
+			None => Self::new(),
+		}
+	}
+
+	pub(crate) fn to_symphonia_hint(&self) -> Hint {
+		let mut hint = Hint::new();
+		if let Some(extension) = &self.extension {
+			hint.with_extension(extension);
+		}
+		if let Some(mime_type) = &self.mime_type {
+			hint.mime_type(mime_type);
+		}
+		hint
+	}
+}
+
+/// Wraps any seekable reader so it can be decoded by symphonia.
+pub(crate) struct SeekableSource<R: Read + Seek + Send> {
+	reader: R,
+	byte_len: u64,
+}
+
+impl<R: Read + Seek + Send> SeekableSource<R> {
+	/// Wraps a reader, seeking to the end once to find out
+	/// how long it is.
+	pub fn new(mut reader: R) -> std::io::Result<Self> {
+		let position = reader.stream_position()?;
+		let byte_len = reader.seek(SeekFrom::End(0))?;
+		reader.seek(SeekFrom::Start(position))?;
+		Ok(Self { reader, byte_len })
+	}
+}
+
+impl<R: Read + Seek + Send> Read for SeekableSource<R> {
+	fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+		self.reader.read(buf)
+	}
+}
+
+impl<R: Read + Seek + Send> Seek for SeekableSource<R> {
+	fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+		self.reader.seek(pos)
+	}
+}
+
+impl<R: Read + Seek + Send> MediaSource for SeekableSource<R> {
+	fn is_seekable(&self) -> bool {
+		true
+	}
+
+	fn byte_len(&self) -> Option<u64> {
+		Some(self.byte_len)
+	}
+}
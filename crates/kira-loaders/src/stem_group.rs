@@ -0,0 +1,23 @@
+mod data;
+mod handle;
+mod settings;
+mod sound;
+
+#[cfg(test)]
+mod test;
+
+pub use data::*;
+pub use handle::*;
+pub use settings::*;
+
+use kira::{tween::Tween, value::Value};
+
+pub(crate) enum StemGroupCommand {
+	SetStemVolume(usize, Value),
+	SetPlaybackRate(Value),
+	Pause(Tween),
+	Resume(Tween),
+	Stop(Tween),
+	SeekBy(f64),
+	SeekTo(f64),
+}
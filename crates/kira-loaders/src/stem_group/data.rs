@@ -0,0 +1,152 @@
+use std::sync::Arc;
+
+use kira::{
+	sound::{static_sound::StaticSoundData, SoundData, SoundId},
+	StartTime,
+};
+use ringbuf::RingBuffer;
+
+use crate::{Error, StreamingSoundData};
+
+use super::{
+	sound::{StemGroupShared, StemGroupSound, StemPlayer},
+	StemGroupHandle, StemGroupSettings,
+};
+
+const COMMAND_BUFFER_CAPACITY: usize = 8;
+
+/// A source of audio in a stem group.
+#[non_exhaustive]
+pub enum Stem {
+	/// Audio loaded into memory all at once.
+	Static(StaticSoundData),
+	/// Audio streamed from a file or other source.
+	Streaming(StreamingSoundData),
+}
+
+impl From<StaticSoundData> for Stem {
+	fn from(data: StaticSoundData) -> Self {
+		Self::Static(data)
+	}
+}
+
+impl From<StreamingSoundData> for Stem {
+	fn from(data: StreamingSoundData) -> Self {
+		Self::Streaming(data)
+	}
+}
+
+/// A group of sounds that play in sync with each other.
+///
+/// The stems share one playhead: starting, pausing, seeking, and
+/// looping the group affects every stem at the same time. Stems
+/// that are shorter than the group are silent until the group
+/// loops or is seeked back. If any streaming stem runs out of
+/// decoded audio, the whole group waits for it, so the stems never
+/// drift apart.
+///
+/// Each stem keeps its own volume, panning, and mixer track.
+pub struct StemGroupData {
+	/// The sounds that make up the group.
+	pub stems: Vec<Stem>,
+	/// Settings for the group.
+	pub settings: StemGroupSettings,
+}
+
+impl StemGroupData {
+	/// Creates a new [`StemGroupData`] from a list of stems.
+	pub fn new(
+		stems: impl IntoIterator<Item = impl Into<Stem>>,
+		settings: StemGroupSettings,
+	) -> Self {
+		Self {
+			stems: stems.into_iter().map(Into::into).collect(),
+			settings,
+		}
+	}
+}
+
+impl StemGroupData {
+	#[allow(clippy::type_complexity)]
+	fn split(
+		self,
+		id: Option<SoundId>,
+	) -> Result<(Box<dyn kira::sound::Sound>, StemGroupHandle), Error> {
+		let settings = self.settings;
+		let mut players = Vec::with_capacity(self.stems.len());
+		let mut stem_handles = Vec::with_capacity(self.stems.len());
+		// the group is as long as its longest stem, if the
+		// lengths of all the stems are known
+		let mut duration = Some(0.0f64);
+		for stem in self.stems {
+			match stem {
+				Stem::Static(data) => {
+					let stem_duration = data.frames.len() as f64 / data.sample_rate as f64;
+					duration = duration.map(|duration| duration.max(stem_duration));
+					players.push(StemPlayer::new_static(data));
+					stem_handles.push(None);
+				}
+				Stem::Streaming(mut data) => {
+					let stem_duration = data
+						.num_frames
+						.map(|num_frames| num_frames as f64 / data.sample_rate as f64);
+					duration = match (duration, stem_duration) {
+						(Some(duration), Some(stem_duration)) => Some(duration.max(stem_duration)),
+						_ => None,
+					};
+					// the group controls the timing of every stem
+					data.settings.start_time = StartTime::Immediate;
+					data.settings.start_position = settings.start_position;
+					// the group passes its own playback rate to the stem
+					// every frame, so the stem never plays backwards
+					data.settings.playback_rate = 1.0.into();
+					// the group loops the stems itself so they all loop
+					// at the same point
+					data.settings.loop_behavior = None;
+					data.wait_at_end = true;
+					data.settings.reverse = false;
+					data.settings.fade_in_tween = None;
+					let (sound, handle) = data.into_streaming_sound(id)?;
+					players.push(StemPlayer::Streaming(sound));
+					stem_handles.push(Some(handle));
+				}
+			}
+		}
+		let (command_producer, command_consumer) = RingBuffer::new(COMMAND_BUFFER_CAPACITY).split();
+		let shared = Arc::new(StemGroupShared::new(settings.start_position));
+		Ok((
+			Box::new(StemGroupSound::new(
+				settings,
+				players,
+				duration,
+				command_consumer,
+				shared.clone(),
+			)),
+			StemGroupHandle {
+				id,
+				shared,
+				command_producer,
+				stem_handles,
+			},
+		))
+	}
+}
+
+impl SoundData for StemGroupData {
+	type Error = Error;
+
+	type Handle = StemGroupHandle;
+
+	#[allow(clippy::type_complexity)]
+	fn into_sound(self) -> Result<(Box<dyn kira::sound::Sound>, Self::Handle), Self::Error> {
+		self.split(None)
+	}
+
+	#[allow(clippy::type_complexity)]
+	fn into_sound_with_id(
+		self,
+		id: SoundId,
+	) -> Result<(Box<dyn kira::sound::Sound>, Self::Handle), Self::Error> {
+		self.split(Some(id))
+	}
+}
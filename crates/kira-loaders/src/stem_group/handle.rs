@@ -0,0 +1,127 @@
+use std::sync::Arc;
+
+use kira::{
+	sound::{static_sound::PlaybackState, SoundId},
+	tween::Tween,
+	value::Value,
+};
+use ringbuf::Producer;
+
+use crate::{CommandQueueFull, Error, StreamingSoundHandle};
+
+use super::{sound::StemGroupShared, StemGroupCommand};
+
+/// Controls a stem group.
+pub struct StemGroupHandle {
+	pub(crate) id: Option<SoundId>,
+	pub(crate) shared: Arc<StemGroupShared>,
+	pub(crate) command_producer: Producer<StemGroupCommand>,
+	/// Handles for the streaming stems, which are used to
+	/// report decoding errors.
+	pub(crate) stem_handles: Vec<Option<StreamingSoundHandle>>,
+}
+
+impl StemGroupHandle {
+	/// Returns the unique identifier for the group, or `None`
+	/// if the group wasn't started by an
+	/// [`AudioManager`](kira::manager::AudioManager).
+	pub fn id(&self) -> Option<SoundId> {
+		self.id
+	}
+
+	/// Returns the current playback state of the group.
+	pub fn state(&self) -> PlaybackState {
+		self.shared.state()
+	}
+
+	/// Returns the current playback position of the group (in seconds).
+	pub fn position(&self) -> f64 {
+		self.shared.position()
+	}
+
+	/// Returns the number of stems in the group.
+	pub fn num_stems(&self) -> usize {
+		self.stem_handles.len()
+	}
+
+	/// Sets the volume of a stem (as a factor of the original volume).
+	///
+	/// Stems are numbered in the order they were given
+	/// to the [`StemGroupData`](crate::StemGroupData).
+	pub fn set_stem_volume(
+		&mut self,
+		stem: usize,
+		volume: impl Into<Value>,
+	) -> Result<(), CommandQueueFull> {
+		self.command_producer
+			.push(StemGroupCommand::SetStemVolume(stem, volume.into()))
+			.map_err(|_| CommandQueueFull)
+	}
+
+	/// Sets the playback rate of the group, as a factor of the
+	/// normal playback rate.
+	pub fn set_playback_rate(
+		&mut self,
+		playback_rate: impl Into<Value>,
+	) -> Result<(), CommandQueueFull> {
+		self.command_producer
+			.push(StemGroupCommand::SetPlaybackRate(playback_rate.into()))
+			.map_err(|_| CommandQueueFull)
+	}
+
+	/// Fades out the group to silence with the given tween and then
+	/// pauses playback.
+	pub fn pause(&mut self, tween: Tween) -> Result<(), CommandQueueFull> {
+		self.command_producer
+			.push(StemGroupCommand::Pause(tween))
+			.map_err(|_| CommandQueueFull)
+	}
+
+	/// Resumes playback and fades in the group from silence
+	/// with the given tween.
+	pub fn resume(&mut self, tween: Tween) -> Result<(), CommandQueueFull> {
+		self.command_producer
+			.push(StemGroupCommand::Resume(tween))
+			.map_err(|_| CommandQueueFull)
+	}
+
+	/// Fades out the group to silence with the given tween and then
+	/// stops playback.
+	///
+	/// Once the group is stopped, it cannot be restarted.
+	pub fn stop(&mut self, tween: Tween) -> Result<(), CommandQueueFull> {
+		self.command_producer
+			.push(StemGroupCommand::Stop(tween))
+			.map_err(|_| CommandQueueFull)
+	}
+
+	/// Sets the playback position of every stem to the specified time
+	/// in seconds.
+	pub fn seek_to(&mut self, position: f64) -> Result<(), CommandQueueFull> {
+		self.command_producer
+			.push(StemGroupCommand::SeekTo(position))
+			.map_err(|_| CommandQueueFull)
+	}
+
+	/// Moves the playback position of every stem by the specified
+	/// amount of time in seconds.
+	pub fn seek_by(&mut self, amount: f64) -> Result<(), CommandQueueFull> {
+		self.command_producer
+			.push(StemGroupCommand::SeekBy(amount))
+			.map_err(|_| CommandQueueFull)
+	}
+
+	/// Returns an error that occurred while decoding one of the
+	/// streaming stems, along with the index of the stem.
+	pub fn pop_error(&mut self) -> Option<(usize, Error)> {
+		self.stem_handles
+			.iter_mut()
+			.enumerate()
+			.find_map(|(index, handle)| {
+				handle
+					.as_mut()
+					.and_then(|handle| handle.pop_error())
+					.map(|error| (index, error))
+			})
+	}
+}
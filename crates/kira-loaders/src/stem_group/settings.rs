@@ -0,0 +1,89 @@
+use kira::{tween::Tween, value::Value, LoopBehavior, StartTime};
+
+/// Settings for a stem group.
+///
+/// These replace the corresponding settings of the individual
+/// stems, except for the volume, panning, and track.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct StemGroupSettings {
+	/// When the stems should start playing.
+	pub start_time: StartTime,
+	/// The initial playback position of the stems (in seconds).
+	pub start_position: f64,
+	/// The playback rate of the stems, as a factor of the
+	/// normal playback rate.
+	///
+	/// Changing the playback rate will change both the speed
+	/// and the pitch of the stems.
+	pub playback_rate: Value,
+	/// The looping behavior of the group.
+	///
+	/// The group loops when it reaches the end of its longest stem,
+	/// and every stem jumps back to the loop start at the same time.
+	/// Streaming stems may need a moment to decode the audio at the
+	/// loop start, and the group waits for them when it loops.
+	pub loop_behavior: Option<LoopBehavior>,
+	/// An optional fade-in from silence.
+	pub fade_in_tween: Option<Tween>,
+}
+
+impl StemGroupSettings {
+	/// Creates a new [`StemGroupSettings`] with the default settings.
+	pub fn new() -> Self {
+		Self {
+			start_time: StartTime::Immediate,
+			start_position: 0.0,
+			playback_rate: Value::Fixed(1.0),
+			loop_behavior: None,
+			fade_in_tween: None,
+		}
+	}
+
+	/// Sets when the stems should start playing.
+	pub fn start_time(self, start_time: impl Into<StartTime>) -> Self {
+		Self {
+			start_time: start_time.into(),
+			..self
+		}
+	}
+
+	/// Sets the initial playback position of the stems (in seconds).
+	pub fn start_position(self, start_position: f64) -> Self {
+		Self {
+			start_position,
+			..self
+		}
+	}
+
+	/// Sets the playback rate of the stems, as a factor of the
+	/// normal playback rate.
+	pub fn playback_rate(self, playback_rate: impl Into<Value>) -> Self {
+		Self {
+			playback_rate: playback_rate.into(),
+			..self
+		}
+	}
+
+	/// Sets the looping behavior of the group.
+	pub fn loop_behavior(self, loop_behavior: impl Into<Option<LoopBehavior>>) -> Self {
+		Self {
+			loop_behavior: loop_behavior.into(),
+			..self
+		}
+	}
+
+	/// Sets the tween used to fade in the stems from silence.
+	pub fn fade_in_tween(self, fade_in_tween: impl Into<Option<Tween>>) -> Self {
+		Self {
+			fade_in_tween: fade_in_tween.into(),
+			..self
+		}
+	}
+}
+
+impl Default for StemGroupSettings {
+	fn default() -> Self {
+		Self::new()
+	}
+}
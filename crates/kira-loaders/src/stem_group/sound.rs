@@ -0,0 +1,369 @@
+use std::sync::{
+	atomic::{AtomicU64, AtomicU8, Ordering},
+	Arc,
+};
+
+use kira::{
+	clock::{ClockTime, Clocks},
+	dsp::Frame,
+	parameter::Parameters,
+	sound::{
+		static_sound::{PlaybackState, StaticSoundData},
+		Sound, SoundEvent, SoundOutputs,
+	},
+	track::TrackId,
+	tween::{Tween, Tweenable},
+	value::{CachedValue, Value},
+	LoopBehavior, StartTime,
+};
+use ringbuf::Consumer;
+
+use crate::streaming::sound::StreamingSound;
+
+use super::{StemGroupCommand, StemGroupSettings};
+
+pub(crate) struct StemGroupShared {
+	state: AtomicU8,
+	position: AtomicU64,
+}
+
+impl StemGroupShared {
+	pub fn new(position: f64) -> Self {
+		Self {
+			state: AtomicU8::new(PlaybackState::Playing as u8),
+			position: AtomicU64::new(position.to_bits()),
+		}
+	}
+
+	pub fn state(&self) -> PlaybackState {
+		match self.state.load(Ordering::SeqCst) {
+			0 => PlaybackState::Playing,
+			1 => PlaybackState::Pausing,
+			2 => PlaybackState::Paused,
+			3 => PlaybackState::Stopping,
+			4 => PlaybackState::Stopped,
+			_ => panic!("Invalid playback state"),
+		}
+	}
+
+	pub fn position(&self) -> f64 {
+		f64::from_bits(self.position.load(Ordering::SeqCst))
+	}
+}
+
+pub(crate) struct StaticStem {
+	data: StaticSoundData,
+	volume: CachedValue,
+	panning: CachedValue,
+}
+
+pub(crate) enum StemPlayer {
+	Static(StaticStem),
+	/// Streaming stems keep track of their own position, but they're
+	/// always advanced by the same amount as the group's playhead.
+	/// When the group loops, they're seeked back to the group's
+	/// playhead.
+	Streaming(StreamingSound),
+}
+
+impl StemPlayer {
+	pub fn new_static(data: StaticSoundData) -> Self {
+		Self::Static(StaticStem {
+			volume: CachedValue::new(.., data.settings.volume, 1.0),
+			panning: CachedValue::new(0.0..=1.0, data.settings.panning, 0.5),
+			data,
+		})
+	}
+
+	fn track(&mut self) -> TrackId {
+		match self {
+			StemPlayer::Static(stem) => stem.data.settings.track,
+			StemPlayer::Streaming(sound) => sound.track(),
+		}
+	}
+
+	/// Returns `true` if the stem can play the next frame
+	/// without waiting for more audio.
+	fn ready(&mut self) -> bool {
+		match self {
+			StemPlayer::Static(_) => true,
+			StemPlayer::Streaming(sound) => sound.ready(),
+		}
+	}
+
+	/// Returns `true` if the stem has nothing left to play.
+	fn finished(&self, position: f64) -> bool {
+		match self {
+			StemPlayer::Static(stem) => position >= stem.data.duration().as_secs_f64(),
+			StemPlayer::Streaming(sound) => sound.reached_end(),
+		}
+	}
+
+	fn set_volume(&mut self, volume: Value) {
+		match self {
+			StemPlayer::Static(stem) => stem.volume.set(volume),
+			StemPlayer::Streaming(sound) => sound.set_volume(volume),
+		}
+	}
+
+	fn seek_to(&mut self, position: f64) {
+		if let StemPlayer::Streaming(sound) = self {
+			sound.seek_to(position);
+		}
+	}
+
+	fn process(
+		&mut self,
+		position: f64,
+		playback_rate: f64,
+		dt: f64,
+		parameters: &Parameters,
+		clocks: &Clocks,
+	) -> Frame {
+		match self {
+			StemPlayer::Static(stem) => {
+				stem.volume.update(parameters);
+				stem.panning.update(parameters);
+				(stem.data.frame_at_position(position) * stem.volume.get() as f32)
+					.panned(stem.panning.get() as f32)
+			}
+			StemPlayer::Streaming(sound) => {
+				// follow the group's playback rate, which is never
+				// negative, so the stem never plays backwards
+				sound.set_playback_rate(Value::Fixed(playback_rate));
+				sound.process(dt, parameters, clocks)
+			}
+		}
+	}
+}
+
+pub(crate) struct StemGroupSound {
+	command_consumer: Consumer<StemGroupCommand>,
+	stems: Vec<StemPlayer>,
+	start_time: StartTime,
+	state: PlaybackState,
+	volume_fade: Tweenable,
+	playback_rate: CachedValue,
+	loop_behavior: Option<LoopBehavior>,
+	/// The length of the longest stem (in seconds), if known.
+	///
+	/// If the length isn't known ahead of time, it's set when
+	/// every stem has reached its end.
+	duration: Option<f64>,
+	position: f64,
+	started: bool,
+	reported_start: bool,
+	unreported_loops: u32,
+	shared: Arc<StemGroupShared>,
+}
+
+impl StemGroupSound {
+	pub fn new(
+		settings: StemGroupSettings,
+		stems: Vec<StemPlayer>,
+		duration: Option<f64>,
+		command_consumer: Consumer<StemGroupCommand>,
+		shared: Arc<StemGroupShared>,
+	) -> Self {
+		Self {
+			command_consumer,
+			stems,
+			start_time: settings.start_time,
+			state: PlaybackState::Playing,
+			volume_fade: if let Some(tween) = settings.fade_in_tween {
+				let mut tweenable = Tweenable::new(0.0);
+				tweenable.set(1.0, tween);
+				tweenable
+			} else {
+				Tweenable::new(1.0)
+			},
+			playback_rate: CachedValue::new(0.0.., settings.playback_rate, 1.0),
+			loop_behavior: settings.loop_behavior,
+			duration,
+			position: settings.start_position,
+			started: false,
+			reported_start: false,
+			unreported_loops: 0,
+			shared,
+		}
+	}
+
+	fn set_state(&mut self, state: PlaybackState) {
+		self.state = state;
+		self.shared.state.store(state as u8, Ordering::SeqCst);
+	}
+
+	fn pause(&mut self, tween: Tween) {
+		self.set_state(PlaybackState::Pausing);
+		self.volume_fade.set(0.0, tween);
+	}
+
+	fn resume(&mut self, tween: Tween) {
+		self.set_state(PlaybackState::Playing);
+		self.volume_fade.set(1.0, tween);
+	}
+
+	fn stop(&mut self, tween: Tween) {
+		self.set_state(PlaybackState::Stopping);
+		self.volume_fade.set(0.0, tween);
+	}
+
+	fn seek_to(&mut self, position: f64) {
+		self.position = position.max(0.0);
+		for stem in &mut self.stems {
+			stem.seek_to(self.position);
+		}
+	}
+
+	/// Moves the playhead forward, looping or stopping the
+	/// group if it reached the end.
+	fn advance(&mut self, dt: f64) {
+		self.position += self.playback_rate.get() * dt;
+		let position = self.position;
+		let reached_end = match self.duration {
+			Some(duration) => position >= duration,
+			None => self.stems.iter().all(|stem| stem.finished(position)),
+		};
+		if !reached_end {
+			return;
+		}
+		let Some(LoopBehavior { start_position }) = self.loop_behavior else {
+			if self.stems.iter().all(|stem| stem.finished(position)) {
+				self.set_state(PlaybackState::Stopped);
+			}
+			return;
+		};
+		let duration = *self.duration.get_or_insert(position);
+		let loop_length = duration - start_position;
+		if loop_length <= 0.0 {
+			return;
+		}
+		while self.position >= duration {
+			self.position -= loop_length;
+			self.unreported_loops += 1;
+		}
+		// every stem loops with the group, no matter how long it is
+		for stem in &mut self.stems {
+			stem.seek_to(self.position);
+		}
+	}
+
+	/// Produces the next frame of each stem and passes it to `output`
+	/// along with the stem's track.
+	fn process_stems(
+		&mut self,
+		dt: f64,
+		parameters: &Parameters,
+		clocks: &Clocks,
+		mut output: impl FnMut(TrackId, Frame),
+	) {
+		if let StartTime::ClockTime(ClockTime { clock, ticks }) = self.start_time {
+			if let Some(clock) = clocks.get(clock) {
+				if clock.ticking() && clock.ticks() >= ticks {
+					self.start_time = StartTime::Immediate;
+				}
+			}
+		}
+		if matches!(self.start_time, StartTime::ClockTime(..)) {
+			return;
+		}
+		if matches!(self.state, PlaybackState::Paused | PlaybackState::Stopped) {
+			return;
+		}
+		// if any stem is waiting for audio, all of the stems wait
+		// so they stay in sync
+		if !self.stems.iter_mut().all(|stem| stem.ready()) {
+			return;
+		}
+		self.started = true;
+		self.playback_rate.update(parameters);
+		if self.volume_fade.update(dt, clocks) {
+			match self.state {
+				PlaybackState::Pausing => self.set_state(PlaybackState::Paused),
+				PlaybackState::Stopping => self.set_state(PlaybackState::Stopped),
+				_ => {}
+			}
+		}
+		let fade = self.volume_fade.value() as f32;
+		let playback_rate = self.playback_rate.get();
+		for stem in &mut self.stems {
+			let track = stem.track();
+			output(
+				track,
+				stem.process(self.position, playback_rate, dt, parameters, clocks) * fade,
+			);
+		}
+		self.advance(dt);
+	}
+}
+
+impl Sound for StemGroupSound {
+	fn track(&mut self) -> TrackId {
+		self.stems
+			.first_mut()
+			.map(|stem| stem.track())
+			.unwrap_or(TrackId::Main)
+	}
+
+	fn on_start_processing(&mut self) {
+		self.shared
+			.position
+			.store(self.position.to_bits(), Ordering::SeqCst);
+		while let Some(command) = self.command_consumer.pop() {
+			match command {
+				StemGroupCommand::SetStemVolume(index, volume) => {
+					if let Some(stem) = self.stems.get_mut(index) {
+						stem.set_volume(volume);
+					}
+				}
+				StemGroupCommand::SetPlaybackRate(playback_rate) => {
+					self.playback_rate.set(playback_rate)
+				}
+				StemGroupCommand::Pause(tween) => self.pause(tween),
+				StemGroupCommand::Resume(tween) => self.resume(tween),
+				StemGroupCommand::Stop(tween) => self.stop(tween),
+				StemGroupCommand::SeekBy(amount) => self.seek_to(self.position + amount),
+				StemGroupCommand::SeekTo(position) => self.seek_to(position),
+			}
+		}
+		for stem in &mut self.stems {
+			if let StemPlayer::Streaming(sound) = stem {
+				sound.on_start_processing();
+			}
+		}
+	}
+
+	fn process(&mut self, dt: f64, parameters: &Parameters, clocks: &Clocks) -> Frame {
+		let mut out = Frame::ZERO;
+		self.process_stems(dt, parameters, clocks, |_, frame| out += frame);
+		out
+	}
+
+	fn process_to_tracks(
+		&mut self,
+		dt: f64,
+		parameters: &Parameters,
+		clocks: &Clocks,
+		outputs: &mut SoundOutputs,
+	) {
+		self.process_stems(dt, parameters, clocks, |track, frame| {
+			outputs.send(track, frame)
+		});
+	}
+
+	fn pop_event(&mut self) -> Option<SoundEvent> {
+		if self.started && !self.reported_start {
+			self.reported_start = true;
+			return Some(SoundEvent::Started);
+		}
+		if self.unreported_loops > 0 {
+			self.unreported_loops -= 1;
+			return Some(SoundEvent::Looped);
+		}
+		None
+	}
+
+	fn finished(&self) -> bool {
+		self.state == PlaybackState::Stopped
+	}
+}
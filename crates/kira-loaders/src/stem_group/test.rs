@@ -0,0 +1,200 @@
+use std::{sync::Arc, time::Duration};
+
+use kira::{
+	dsp::Frame,
+	manager::backend::MockBackend,
+	sound::static_sound::{StaticSoundData, StaticSoundSettings},
+	track::TrackSettings,
+	LoopBehavior,
+};
+
+use crate::{
+	test::{create_manager, create_wav, create_wav_with_length, SAMPLE_RATE},
+	FormatHint, LoadSettings, StreamingSoundData, StreamingSoundSettings,
+};
+
+use super::{Stem, StemGroupData, StemGroupHandle, StemGroupSettings};
+
+/// Processes audio until the group has started playing, giving
+/// the decoder threads time to catch up.
+fn wait_for_playback(backend: &mut MockBackend, handle: &StemGroupHandle, position: f64) -> Frame {
+	for _ in 0..1000 {
+		backend.on_start_processing();
+		if handle.position() > position {
+			break;
+		}
+		backend.process();
+		std::thread::sleep(Duration::from_millis(1));
+	}
+	assert!(handle.position() > position);
+	backend.process()
+}
+
+#[test]
+fn stems_use_their_own_tracks() {
+	let mut manager = create_manager();
+	let muted_track = manager
+		.add_sub_track(TrackSettings::new().volume(0.0))
+		.unwrap();
+	let stem = |value: f32, settings: StaticSoundSettings| StaticSoundData {
+		sample_rate: SAMPLE_RATE,
+		frames: Arc::new(vec![Frame::from_mono(value); 100]),
+		settings,
+		markers: Arc::new([]),
+	};
+	manager
+		.play(StemGroupData::new(
+			[
+				stem(0.5, StaticSoundSettings::new()),
+				stem(0.25, StaticSoundSettings::new().track(muted_track.id())),
+			],
+			StemGroupSettings::new(),
+		))
+		.unwrap();
+	let backend = manager.backend_mut();
+	backend.on_start_processing();
+	let out = backend.process();
+	let expected = Frame::from_mono(0.5).panned(0.5);
+	assert!((out.left - expected.left).abs() < 1.0e-6);
+	assert!((out.right - expected.right).abs() < 1.0e-6);
+}
+
+#[test]
+fn streaming_stems_stay_in_sync() {
+	// a streamed copy of the sound with its volume inverted cancels
+	// out a static copy of the sound, but only if they're in sync
+	let static_stem = crate::load_from_bytes(
+		create_wav(),
+		FormatHint::new().extension("wav"),
+		LoadSettings::new(),
+	)
+	.unwrap();
+	let streaming_stem = StreamingSoundData::from_bytes(
+		create_wav(),
+		FormatHint::new().extension("wav"),
+		StreamingSoundSettings::new().volume(-1.0).buffer_size(256),
+	)
+	.unwrap();
+	let mut manager = create_manager();
+	let mut handle = manager
+		.play(StemGroupData::new(
+			[Stem::from(static_stem), Stem::from(streaming_stem)],
+			StemGroupSettings::new(),
+		))
+		.unwrap();
+	let backend = manager.backend_mut();
+	let check_sync = |backend: &mut MockBackend| {
+		for _ in 0..1000 {
+			let out = backend.process();
+			assert!(out.left.abs() < 1.0e-3 && out.right.abs() < 1.0e-3);
+		}
+	};
+	wait_for_playback(backend, &handle, 0.0);
+	check_sync(backend);
+	handle.seek_to(0.5).unwrap();
+	wait_for_playback(backend, &handle, 0.5);
+	check_sync(backend);
+	backend.on_start_processing();
+	assert!(handle.position() > 0.5);
+}
+
+#[test]
+fn stems_of_different_lengths_loop_together() {
+	// the streaming stem is a shorter, inverted copy of the static
+	// stem, so they cancel out until the streaming stem ends
+	const SHORT_STEM_FRAMES: u32 = 2010;
+	let static_stem = crate::load_from_bytes(
+		create_wav(),
+		FormatHint::new().extension("wav"),
+		LoadSettings::new(),
+	)
+	.unwrap();
+	let streaming_stem = StreamingSoundData::from_bytes(
+		create_wav_with_length(SHORT_STEM_FRAMES),
+		FormatHint::new().extension("wav"),
+		StreamingSoundSettings::new().volume(-1.0),
+	)
+	.unwrap();
+	let mut manager = create_manager();
+	let handle = manager
+		.play(StemGroupData::new(
+			[Stem::from(static_stem), Stem::from(streaming_stem)],
+			StemGroupSettings::new().loop_behavior(LoopBehavior {
+				start_position: 0.0,
+			}),
+		))
+		.unwrap();
+	let backend = manager.backend_mut();
+	let short_stem_end = SHORT_STEM_FRAMES as f64 / SAMPLE_RATE as f64;
+	let margin = 2.0 / SAMPLE_RATE as f64;
+	let mut loops = 0;
+	let mut frames_checked = 0;
+	backend.on_start_processing();
+	for _ in 0..100_000 {
+		let position = handle.position();
+		let out = backend.process();
+		backend.on_start_processing();
+		let next_position = handle.position();
+		// the group is waiting for the streaming stem to decode
+		if next_position == position {
+			std::thread::sleep(Duration::from_millis(1));
+			continue;
+		}
+		if next_position < position {
+			loops += 1;
+		}
+		if position < short_stem_end - margin {
+			assert!(out.left.abs() < 1.0e-3 && out.right.abs() < 1.0e-3);
+		} else if position > short_stem_end + margin {
+			// the short stem is silent until the group loops
+			assert!(out.left.abs() > 0.1);
+		}
+		frames_checked += 1;
+		if loops == 2 {
+			break;
+		}
+	}
+	assert_eq!(loops, 2);
+	assert!(frames_checked >= 2 * SAMPLE_RATE as usize);
+}
+
+#[test]
+fn negative_playback_rates_keep_stems_at_the_group_position() {
+	let static_stem = crate::load_from_bytes(
+		create_wav(),
+		FormatHint::new().extension("wav"),
+		LoadSettings::new(),
+	)
+	.unwrap();
+	let streaming_stem = StreamingSoundData::from_bytes(
+		create_wav(),
+		FormatHint::new().extension("wav"),
+		StreamingSoundSettings::new().volume(-1.0),
+	)
+	.unwrap();
+	let mut manager = create_manager();
+	let mut handle = manager
+		.play(StemGroupData::new(
+			[Stem::from(static_stem), Stem::from(streaming_stem)],
+			StemGroupSettings::new().start_position(0.5),
+		))
+		.unwrap();
+	let backend = manager.backend_mut();
+	wait_for_playback(backend, &handle, 0.5);
+	handle.set_playback_rate(-1.0).unwrap();
+	for _ in 0..100 {
+		backend.on_start_processing();
+		for _ in 0..100 {
+			let out = backend.process();
+			assert!(out.left.abs() < 1.0e-3 && out.right.abs() < 1.0e-3);
+		}
+		std::thread::sleep(Duration::from_millis(1));
+	}
+	backend.on_start_processing();
+	// the group can't play backwards, so it stays where it was,
+	// and the streaming stem stays with it
+	let position = handle.position();
+	assert!(position > 0.5 && position < 0.51);
+	let stem_position = handle.stem_handles[1].as_ref().unwrap().position();
+	assert!((stem_position - position).abs() < 2.0 / SAMPLE_RATE as f64);
+}
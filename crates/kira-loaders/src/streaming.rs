@@ -1,9 +1,11 @@
 mod data;
+mod decoder_pool;
 mod handle;
 mod settings;
-mod sound;
+pub(crate) mod sound;
 
 pub use data::*;
+pub use decoder_pool::*;
 pub use handle::*;
 pub use settings::*;
 
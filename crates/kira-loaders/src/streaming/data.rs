@@ -1,10 +1,26 @@
-use std::{fs::File, path::Path};
+use std::{
+	fs::File,
+	io::{Cursor, Read, Seek},
+	path::Path,
+};
 
-use kira::sound::SoundData;
+use kira::{
+	dsp::Frame,
+	sound::{SoundData, SoundId},
+	LoopBehavior,
+};
 use ringbuf::RingBuffer;
-use symphonia::core::{codecs::Decoder, formats::FormatReader, io::MediaSourceStream, probe::Hint};
+use symphonia::core::{
+	codecs::Decoder,
+	formats::{FormatReader, SeekMode, SeekTo},
+	io::{MediaSource, MediaSourceStream},
+};
 
-use crate::{Error, StreamingSoundHandle, StreamingSoundSettings};
+use crate::{
+	load_frames_from_buffer_ref, loudness::LoudnessMeter, markers::read_wav_cue_points,
+	source::SeekableSource, Error, FormatHint, Loudness, StreamingSoundHandle,
+	StreamingSoundSettings,
+};
 
 use super::sound::StreamingSound;
 
@@ -17,26 +33,74 @@ pub struct StreamingSoundData {
 	pub(crate) decoder: Box<dyn Decoder>,
 	pub(crate) sample_rate: u32,
 	pub(crate) track_id: u32,
+	pub(crate) num_frames: Option<u64>,
+	pub(crate) loudness: Option<Loudness>,
+	pub(crate) head: Option<PreloadedFrames>,
+	pub(crate) loop_head: Option<PreloadedFrames>,
+	/// Whether the sound waits for a seek when it reaches the end
+	/// instead of stopping. This is used for stems, which are
+	/// looped by their group.
+	pub(crate) wait_at_end: bool,
 	/// Settings for the streaming sound.
 	pub settings: StreamingSoundSettings,
 }
 
 impl StreamingSoundData {
-	pub(crate) fn new(
+	/// Creates a [`StreamingSoundData`] for an audio file.
+	pub fn from_file(
 		path: impl AsRef<Path>,
 		settings: StreamingSoundSettings,
 	) -> Result<Self, Error> {
+		let path = path.as_ref();
+		Self::from_media_source(
+			Box::new(File::open(path)?),
+			FormatHint::from_path(path),
+			settings,
+		)
+	}
+
+	/// Creates a [`StreamingSoundData`] for audio data stored in memory.
+	pub fn from_bytes(
+		bytes: impl AsRef<[u8]> + Send + 'static,
+		hint: impl Into<Option<FormatHint>>,
+		settings: StreamingSoundSettings,
+	) -> Result<Self, Error> {
+		Self::from_cursor(Cursor::new(bytes), hint, settings)
+	}
+
+	/// Creates a [`StreamingSoundData`] for audio data read from a [`Cursor`].
+	pub fn from_cursor<T: AsRef<[u8]> + Send + 'static>(
+		cursor: Cursor<T>,
+		hint: impl Into<Option<FormatHint>>,
+		settings: StreamingSoundSettings,
+	) -> Result<Self, Error> {
+		Self::from_media_source(Box::new(cursor), hint, settings)
+	}
+
+	/// Creates a [`StreamingSoundData`] for audio data read from any
+	/// seekable source, like a file inside an archive.
+	pub fn from_reader(
+		reader: impl Read + Seek + Send + 'static,
+		hint: impl Into<Option<FormatHint>>,
+		settings: StreamingSoundSettings,
+	) -> Result<Self, Error> {
+		Self::from_media_source(Box::new(SeekableSource::new(reader)?), hint, settings)
+	}
+
+	/// Creates a [`StreamingSoundData`] for audio data read from a
+	/// symphonia [`MediaSource`].
+	pub fn from_media_source(
+		mut media_source: Box<dyn MediaSource>,
+		hint: impl Into<Option<FormatHint>>,
+		mut settings: StreamingSoundSettings,
+	) -> Result<Self, Error> {
+		let cue_points = read_wav_cue_points(media_source.as_mut())?;
 		let codecs = symphonia::default::get_codecs();
 		let probe = symphonia::default::get_probe();
-		let file = File::open(path)?;
-		let mss = MediaSourceStream::new(Box::new(file), Default::default());
+		let hint = hint.into().unwrap_or_default().to_symphonia_hint();
+		let mss = MediaSourceStream::new(media_source, Default::default());
 		let format_reader = probe
-			.format(
-				&Hint::default(),
-				mss,
-				&Default::default(),
-				&Default::default(),
-			)?
+			.format(&hint, mss, &Default::default(), &Default::default())?
 			.format;
 		let default_track = format_reader.default_track().ok_or(Error::NoDefaultTrack)?;
 		let sample_rate = default_track
@@ -45,34 +109,225 @@ impl StreamingSoundData {
 			.ok_or(Error::UnknownSampleRate)?;
 		let decoder = codecs.make(&default_track.codec_params, &Default::default())?;
 		let track_id = default_track.id;
-		Ok(Self {
+		let num_frames = default_track.codec_params.n_frames;
+		settings.markers.extend(
+			cue_points
+				.into_iter()
+				.map(|cue_point| cue_point.into_marker(sample_rate)),
+		);
+		let mut data = Self {
 			format_reader,
 			decoder,
 			sample_rate,
 			track_id,
+			num_frames,
+			loudness: None,
+			head: None,
+			loop_head: None,
+			wait_at_end: false,
 			settings,
-		})
+		};
+		if data.settings.target_loudness.is_some() {
+			data.measure_loudness()?;
+		}
+		Ok(data)
 	}
-}
 
-impl SoundData for StreamingSoundData {
-	type Error = Error;
+	/// Returns the loudness of the sound, if it has been measured.
+	///
+	/// The loudness is measured automatically if
+	/// [`StreamingSoundSettings::target_loudness`] is set.
+	pub fn loudness(&self) -> Option<Loudness> {
+		self.loudness
+	}
 
-	type Handle = StreamingSoundHandle;
+	/// Decodes the whole sound to measure its loudness.
+	///
+	/// The audio is measured one packet at a time, so the decoded
+	/// audio is never held in memory all at once.
+	pub fn measure_loudness(&mut self) -> Result<Loudness, Error> {
+		if let Some(loudness) = self.loudness {
+			return Ok(loudness);
+		}
+		self.seek(0)?;
+		let mut meter = None;
+		let mut frames = vec![];
+		loop {
+			match self.format_reader.next_packet() {
+				Ok(packet) => {
+					let buffer = self.decoder.decode(&packet)?;
+					let mono = buffer.spec().channels.count() == 1;
+					frames.clear();
+					load_frames_from_buffer_ref(&mut frames, &buffer)?;
+					meter
+						.get_or_insert_with(|| LoudnessMeter::new(self.sample_rate, mono))
+						.add_frames(&frames);
+				}
+				Err(symphonia::core::errors::Error::IoError(error))
+					if error.kind() == std::io::ErrorKind::UnexpectedEof =>
+				{
+					break
+				}
+				Err(error) => return Err(error.into()),
+			}
+		}
+		// rewind so the sound can be streamed from the beginning
+		self.seek(0)?;
+		let loudness = match meter {
+			Some(meter) => meter.finish(),
+			None => Loudness::measure(&[], self.sample_rate),
+		};
+		self.loudness = Some(loudness);
+		Ok(loudness)
+	}
 
-	#[allow(clippy::type_complexity)]
-	fn into_sound(self) -> Result<(Box<dyn kira::sound::Sound>, Self::Handle), Self::Error> {
+	/// Decodes the first `duration` seconds of the sound (starting
+	/// from [`StreamingSoundSettings::start_position`]) ahead of
+	/// time so the sound can start playing instantly. If the sound
+	/// loops, the audio at the loop start is also decoded so the
+	/// sound never runs out of audio when it loops.
+	///
+	/// Preloaded audio is not used if the start position or
+	/// looping behavior is changed afterwards, or if the sound
+	/// plays in reverse.
+	pub fn preload(&mut self, duration: f64) -> Result<(), Error> {
+		let num_frames = (duration.max(0.0) * self.sample_rate as f64).round() as usize;
+		let start_index = self.index_of(self.settings.start_position);
+		self.head = Some(self.decode_frames(start_index, num_frames)?);
+		self.loop_head = match self.settings.loop_behavior {
+			Some(LoopBehavior { start_position }) => {
+				Some(self.decode_frames(self.index_of(start_position), num_frames)?)
+			}
+			None => None,
+		};
+		self.seek(0)?;
+		Ok(())
+	}
+
+	/// Returns the number of seconds of audio that are decoded
+	/// ahead of time.
+	pub fn preloaded_duration(&self) -> f64 {
+		self.head
+			.as_ref()
+			.map(|head| head.frames.len() as f64 / self.sample_rate as f64)
+			.unwrap_or(0.0)
+	}
+
+	pub(crate) fn index_of(&self, position: f64) -> u64 {
+		(position.max(0.0) * self.sample_rate as f64).round() as u64
+	}
+
+	fn seek(&mut self, index: u64) -> Result<u64, Error> {
+		let seeked_to = self.format_reader.seek(
+			SeekMode::Accurate,
+			SeekTo::TimeStamp {
+				ts: index,
+				track_id: self.track_id,
+			},
+		)?;
+		self.decoder.reset();
+		Ok(seeked_to.actual_ts)
+	}
+
+	/// Decodes `num_frames` frames starting from `start_index`.
+	fn decode_frames(
+		&mut self,
+		start_index: u64,
+		num_frames: usize,
+	) -> Result<PreloadedFrames, Error> {
+		// seeking may land before the requested frame, so skip
+		// frames until we reach it
+		let mut index = self.seek(start_index)?;
+		let mut frames = Vec::with_capacity(num_frames);
+		while frames.len() < num_frames {
+			let mut packet_frames = vec![];
+			match self.format_reader.next_packet() {
+				Ok(packet) => {
+					let buffer = self.decoder.decode(&packet)?;
+					load_frames_from_buffer_ref(&mut packet_frames, &buffer)?;
+				}
+				Err(symphonia::core::errors::Error::IoError(error))
+					if error.kind() == std::io::ErrorKind::UnexpectedEof =>
+				{
+					break
+				}
+				Err(error) => return Err(error.into()),
+			}
+			for frame in packet_frames {
+				if index >= start_index && frames.len() < num_frames {
+					frames.push(frame);
+				}
+				index += 1;
+			}
+		}
+		Ok(PreloadedFrames {
+			start_index,
+			frames,
+		})
+	}
+
+	pub(crate) fn into_streaming_sound(
+		self,
+		id: Option<SoundId>,
+	) -> Result<(StreamingSound, StreamingSoundHandle), Error> {
 		let (command_producer, command_consumer) = RingBuffer::new(COMMAND_BUFFER_CAPACITY).split();
 		let (error_producer, error_consumer) = RingBuffer::new(ERROR_BUFFER_CAPACITY).split();
 		let sound = StreamingSound::new(self, command_consumer, error_producer)?;
 		let shared = sound.shared();
 		Ok((
-			Box::new(sound),
+			sound,
 			StreamingSoundHandle {
+				id,
 				shared,
 				command_producer,
 				error_consumer,
 			},
 		))
 	}
+
+	/// Returns the amplitude the sound should be multiplied by
+	/// to reach the target loudness.
+	pub(crate) fn gain(&self) -> f64 {
+		match (self.settings.target_loudness, self.loudness) {
+			(Some(target_loudness), Some(loudness)) => loudness.gain_to(target_loudness),
+			_ => 1.0,
+		}
+	}
+}
+
+/// Audio that was decoded before the sound started playing.
+pub(crate) struct PreloadedFrames {
+	/// The index of the first frame.
+	pub start_index: u64,
+	pub frames: Vec<Frame>,
+}
+
+impl StreamingSoundData {
+	#[allow(clippy::type_complexity)]
+	fn split(
+		self,
+		id: Option<SoundId>,
+	) -> Result<(Box<dyn kira::sound::Sound>, StreamingSoundHandle), Error> {
+		let (sound, handle) = self.into_streaming_sound(id)?;
+		Ok((Box::new(sound), handle))
+	}
+}
+
+impl SoundData for StreamingSoundData {
+	type Error = Error;
+
+	type Handle = StreamingSoundHandle;
+
+	#[allow(clippy::type_complexity)]
+	fn into_sound(self) -> Result<(Box<dyn kira::sound::Sound>, Self::Handle), Self::Error> {
+		self.split(None)
+	}
+
+	#[allow(clippy::type_complexity)]
+	fn into_sound_with_id(
+		self,
+		id: SoundId,
+	) -> Result<(Box<dyn kira::sound::Sound>, Self::Handle), Self::Error> {
+		self.split(Some(id))
+	}
 }
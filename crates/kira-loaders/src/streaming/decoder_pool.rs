@@ -0,0 +1,205 @@
+use std::{
+	fmt::Debug,
+	sync::{
+		atomic::{AtomicBool, AtomicUsize, Ordering},
+		mpsc, Arc, Mutex, OnceLock,
+	},
+	thread::{self, Thread},
+	time::Instant,
+};
+
+use super::sound::decoder_wrapper::DecoderWrapper;
+
+const DEFAULT_NUM_THREADS: usize = 2;
+
+static GLOBAL_POOL: OnceLock<DecoderPool> = OnceLock::new();
+
+/// A group of threads that decode audio for streaming sounds.
+///
+/// Each thread decodes whichever stream has the least audio
+/// buffered, so many streams can share a small number of threads.
+/// Threads sleep until a sound has played enough audio to make
+/// room for more.
+///
+/// Streaming sounds use the [global](DecoderPool::global) pool
+/// unless [`StreamingSoundSettings::decoder_pool`](crate::StreamingSoundSettings::decoder_pool)
+/// is set. The threads of a pool shut down once the pool has been
+/// dropped and all of its streams have finished.
+#[derive(Clone)]
+pub struct DecoderPool {
+	owner: Arc<PoolOwner>,
+}
+
+impl DecoderPool {
+	/// Creates a new [`DecoderPool`] with the given number of threads.
+	///
+	/// At least one thread is always created.
+	pub fn new(num_threads: usize) -> Self {
+		let num_threads = num_threads.max(1);
+		let mut shared_senders = Vec::with_capacity(num_threads);
+		let mut threads = Vec::with_capacity(num_threads);
+		for _ in 0..num_threads {
+			let (shared_sender, shared_receiver) = mpsc::channel();
+			let join_handle = thread::spawn(move || {
+				if let Ok(shared) = shared_receiver.recv() {
+					run_worker(shared);
+				}
+			});
+			shared_senders.push(shared_sender);
+			threads.push(join_handle.thread().clone());
+		}
+		let shared = Arc::new(DecoderPoolShared {
+			streams: Mutex::new(vec![]),
+			num_streams: AtomicUsize::new(0),
+			threads,
+			shut_down: AtomicBool::new(false),
+		});
+		for shared_sender in shared_senders {
+			shared_sender
+				.send(shared.clone())
+				.expect("Decoder thread should be waiting for the pool");
+		}
+		Self {
+			owner: Arc::new(PoolOwner { shared }),
+		}
+	}
+
+	/// Returns the pool shared by all streaming sounds that don't
+	/// specify a pool.
+	///
+	/// The global pool is created the first time it's used.
+	pub fn global() -> Self {
+		GLOBAL_POOL
+			.get_or_init(|| Self::new(DEFAULT_NUM_THREADS))
+			.clone()
+	}
+
+	/// Returns the number of threads in the pool.
+	pub fn num_threads(&self) -> usize {
+		self.owner.shared.threads.len()
+	}
+
+	/// Returns the number of streams that are currently
+	/// being decoded by the pool.
+	pub fn num_streams(&self) -> usize {
+		self.owner.shared.num_streams.load(Ordering::SeqCst)
+	}
+
+	pub(crate) fn shared(&self) -> Arc<DecoderPoolShared> {
+		self.owner.shared.clone()
+	}
+
+	pub(crate) fn add(&self, decoder_wrapper: DecoderWrapper) {
+		let shared = &self.owner.shared;
+		shared.num_streams.fetch_add(1, Ordering::SeqCst);
+		shared.streams.lock().unwrap().push(decoder_wrapper);
+		shared.wake();
+	}
+}
+
+impl Default for DecoderPool {
+	fn default() -> Self {
+		Self::new(DEFAULT_NUM_THREADS)
+	}
+}
+
+impl Debug for DecoderPool {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("DecoderPool")
+			.field("num_threads", &self.num_threads())
+			.field("num_streams", &self.num_streams())
+			.finish()
+	}
+}
+
+impl PartialEq for DecoderPool {
+	fn eq(&self, other: &Self) -> bool {
+		Arc::ptr_eq(&self.owner, &other.owner)
+	}
+}
+
+/// Tells the threads to shut down once the last handle
+/// to the pool is dropped.
+struct PoolOwner {
+	shared: Arc<DecoderPoolShared>,
+}
+
+impl Drop for PoolOwner {
+	fn drop(&mut self) {
+		self.shared.shut_down.store(true, Ordering::SeqCst);
+		self.shared.wake();
+	}
+}
+
+pub(crate) struct DecoderPoolShared {
+	/// The streams that are waiting to be decoded. Streams that
+	/// are currently being decoded are removed from the list
+	/// so other threads don't pick them.
+	streams: Mutex<Vec<DecoderWrapper>>,
+	num_streams: AtomicUsize,
+	threads: Vec<Thread>,
+	shut_down: AtomicBool,
+}
+
+impl DecoderPoolShared {
+	/// Wakes up the threads so they can check for streams
+	/// that need more audio.
+	///
+	/// This doesn't block, so it's safe to call from the
+	/// audio thread.
+	pub fn wake(&self) {
+		for thread in &self.threads {
+			thread.unpark();
+		}
+	}
+
+	/// Removes the stream that most urgently needs to be decoded
+	/// from the list.
+	///
+	/// If no stream needs to be decoded right now, returns the
+	/// earliest time a waiting stream will be ready again.
+	fn take_most_urgent_stream(&self) -> Result<DecoderWrapper, Option<Instant>> {
+		let now = Instant::now();
+		let mut streams = self.streams.lock().unwrap();
+		let index = streams
+			.iter()
+			.enumerate()
+			.filter_map(|(index, stream)| stream.priority(now).map(|priority| (index, priority)))
+			.min_by(|(_, a), (_, b)| a.total_cmp(b))
+			.map(|(index, _)| index);
+		match index {
+			Some(index) => Ok(streams.swap_remove(index)),
+			None => Err(streams.iter().filter_map(DecoderWrapper::resume_at).min()),
+		}
+	}
+
+	fn finished(&self) -> bool {
+		self.shut_down.load(Ordering::SeqCst) && self.num_streams.load(Ordering::SeqCst) == 0
+	}
+}
+
+fn run_worker(shared: Arc<DecoderPoolShared>) {
+	while !shared.finished() {
+		match shared.take_most_urgent_stream() {
+			Ok(mut stream) => {
+				if stream.decode_turn() {
+					shared.num_streams.fetch_sub(1, Ordering::SeqCst);
+					// let the other threads know if this was the last stream
+					if shared.finished() {
+						shared.wake();
+					}
+				} else {
+					shared.streams.lock().unwrap().push(stream);
+				}
+			}
+			// wait for the next stream that's backing off after
+			// making no progress to be ready again
+			Err(Some(resume_at)) => {
+				thread::park_timeout(resume_at.saturating_duration_since(Instant::now()))
+			}
+			// the audio thread will wake us up once a stream
+			// has room for more frames
+			Err(None) => thread::park(),
+		}
+	}
+}
@@ -1,6 +1,10 @@
 use std::{fmt::Display, sync::Arc};
 
-use kira::{sound::static_sound::PlaybackState, tween::Tween, value::Value};
+use kira::{
+	sound::{static_sound::PlaybackState, SoundId},
+	tween::Tween,
+	value::Value,
+};
 use ringbuf::{Consumer, Producer};
 
 use crate::Error;
@@ -22,12 +26,23 @@ impl std::error::Error for CommandQueueFull {}
 
 /// Controls a streaming sound.
 pub struct StreamingSoundHandle {
+	pub(crate) id: Option<SoundId>,
 	pub(crate) shared: Arc<Shared>,
 	pub(crate) command_producer: Producer<Command>,
 	pub(crate) error_consumer: Consumer<Error>,
 }
 
 impl StreamingSoundHandle {
+	/// Returns the unique identifier for the sound, or `None`
+	/// if the sound wasn't started by an
+	/// [`AudioManager`](kira::manager::AudioManager).
+	///
+	/// Sounds that are played as part of a playlist or stem group
+	/// share the identifier of the playlist or stem group.
+	pub fn id(&self) -> Option<SoundId> {
+		self.id
+	}
+
 	/// Returns the current playback state of the sound.
 	pub fn state(&self) -> PlaybackState {
 		self.shared.state()
@@ -38,6 +53,15 @@ impl StreamingSoundHandle {
 		self.shared.position()
 	}
 
+	/// Returns the number of frames of audio the sound needed
+	/// to play but that hadn't been decoded in time.
+	///
+	/// The sound is silent during these frames. Frames spent waiting
+	/// for audio after the sound starts or seeks are not counted.
+	pub fn underrun_frames(&self) -> u64 {
+		self.shared.underrun_frames()
+	}
+
 	/// Sets the volume of the sound (as a factor of the original volume).
 	pub fn set_volume(&mut self, volume: impl Into<Value>) -> Result<(), CommandQueueFull> {
 		self.command_producer
@@ -111,4 +135,8 @@ impl StreamingSoundHandle {
 	pub fn pop_error(&mut self) -> Option<Error> {
 		self.error_consumer.pop()
 	}
+
+	pub(crate) fn has_errors(&self) -> bool {
+		!self.error_consumer.is_empty()
+	}
 }
@@ -1,7 +1,43 @@
-use kira::{track::TrackId, tween::Tween, value::Value, LoopBehavior, StartTime};
+use kira::{
+	dsp::Interpolation, sound::Marker, track::TrackId, tween::Tween, value::Value, LoopBehavior,
+	StartTime,
+};
+
+use super::DecoderPool;
+
+/// What a streaming sound should do when an error occurs
+/// while decoding audio.
+///
+/// Errors are reported by [`StreamingSoundHandle::pop_error`](crate::StreamingSoundHandle::pop_error).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorPolicy {
+	/// The sound stops after any error.
+	Stop,
+	/// Packets of audio that can't be decoded are skipped.
+	/// Other errors stop the sound.
+	SkipBadPackets,
+	/// Packets of audio that can't be decoded are skipped, and
+	/// reading the audio is retried up to `max_retries` times in
+	/// a row if it fails. Other errors stop the sound.
+	///
+	/// The stream waits a little longer before each retry (10ms
+	/// after the first failure, 20ms after the second, and so on).
+	RetryIo {
+		/// The maximum number of times in a row to retry reading
+		/// the audio before stopping the sound.
+		max_retries: u32,
+	},
+}
+
+impl Default for ErrorPolicy {
+	fn default() -> Self {
+		Self::Stop
+	}
+}
 
 /// Settings for a streaming sound.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 #[non_exhaustive]
 pub struct StreamingSoundSettings {
 	/// When the instance should start playing.
@@ -19,12 +55,52 @@ pub struct StreamingSoundSettings {
 	/// The panning of the sound, where 0 is hard left
 	/// and 1 is hard right.
 	pub panning: Value,
+	/// Whether the sound should play in reverse.
+	///
+	/// If set to `true`, the start position will be relative
+	/// to the end of the sound.
+	///
+	/// If the file doesn't say how long it is, the whole file has
+	/// to be decoded to find the end. This happens on the decoder
+	/// thread, so the sound may take a while to start, and its
+	/// position will be reported as 0 until it does.
+	pub reverse: bool,
 	/// The looping behavior of the sound.
 	pub loop_behavior: Option<LoopBehavior>,
 	/// The mixer track this sound should play on.
 	pub track: TrackId,
 	/// An optional fade-in from silence.
 	pub fade_in_tween: Option<Tween>,
+	/// How audio between frames is estimated when the sound
+	/// doesn't play at its original sample rate.
+	pub interpolation: Interpolation,
+	/// If set, the sound will be amplified so its integrated
+	/// loudness matches this level (in LUFS).
+	///
+	/// Measuring the loudness requires decoding the whole file
+	/// when the sound is created.
+	pub target_loudness: Option<f64>,
+	/// The pool of threads that decodes the sound.
+	///
+	/// If this is `None`, the [global](DecoderPool::global)
+	/// pool is used.
+	pub decoder_pool: Option<DecoderPool>,
+	/// The number of frames of audio that can be decoded
+	/// ahead of time.
+	///
+	/// A larger buffer makes the sound less likely to run out
+	/// of audio when the decoder threads are busy, but uses more
+	/// memory.
+	pub buffer_size: usize,
+	/// What the sound should do when an error occurs
+	/// while decoding audio.
+	pub error_policy: ErrorPolicy,
+	/// Points in time that emit events when the playback
+	/// position crosses them.
+	///
+	/// Cue points in WAV files are added to these markers
+	/// when the sound is created.
+	pub markers: Vec<Marker>,
 }
 
 impl StreamingSoundSettings {
@@ -36,9 +112,16 @@ impl StreamingSoundSettings {
 			volume: Value::Fixed(1.0),
 			playback_rate: Value::Fixed(1.0),
 			panning: Value::Fixed(0.5),
+			reverse: false,
 			loop_behavior: None,
 			track: TrackId::Main,
 			fade_in_tween: None,
+			interpolation: Interpolation::default(),
+			target_loudness: None,
+			decoder_pool: None,
+			buffer_size: 16_384,
+			error_policy: ErrorPolicy::default(),
+			markers: vec![],
 		}
 	}
 
@@ -87,6 +170,11 @@ impl StreamingSoundSettings {
 		}
 	}
 
+	/// Sets whether the sound should play in reverse.
+	pub fn reverse(self, reverse: bool) -> Self {
+		Self { reverse, ..self }
+	}
+
 	/// Sets the looping behavior of the sound.
 	pub fn loop_behavior(self, loop_behavior: impl Into<Option<LoopBehavior>>) -> Self {
 		Self {
@@ -110,6 +198,63 @@ impl StreamingSoundSettings {
 			..self
 		}
 	}
+
+	/// Sets how audio between frames is estimated when the sound
+	/// doesn't play at its original sample rate.
+	pub fn interpolation(self, interpolation: Interpolation) -> Self {
+		Self {
+			interpolation,
+			..self
+		}
+	}
+
+	/// Sets the loudness (in LUFS) the sound should be
+	/// normalized to.
+	pub fn target_loudness(self, target_loudness: impl Into<Option<f64>>) -> Self {
+		Self {
+			target_loudness: target_loudness.into(),
+			..self
+		}
+	}
+
+	/// Sets the pool of threads that decodes the sound.
+	pub fn decoder_pool(self, decoder_pool: impl Into<Option<DecoderPool>>) -> Self {
+		Self {
+			decoder_pool: decoder_pool.into(),
+			..self
+		}
+	}
+
+	/// Sets the number of frames of audio that can be decoded
+	/// ahead of time.
+	pub fn buffer_size(self, buffer_size: usize) -> Self {
+		Self {
+			buffer_size,
+			..self
+		}
+	}
+
+	/// Sets what the sound should do when an error occurs
+	/// while decoding audio.
+	pub fn error_policy(self, error_policy: ErrorPolicy) -> Self {
+		Self {
+			error_policy,
+			..self
+		}
+	}
+
+	/// Sets the points in time that emit events when the
+	/// playback position crosses them.
+	pub fn markers(self, markers: Vec<Marker>) -> Self {
+		Self { markers, ..self }
+	}
+
+	/// Adds a point in time that emits an event when the
+	/// playback position crosses it.
+	pub fn with_marker(mut self, marker: Marker) -> Self {
+		self.markers.push(marker);
+		self
+	}
 }
 
 impl Default for StreamingSoundSettings {
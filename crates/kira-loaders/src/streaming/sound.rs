@@ -1,32 +1,47 @@
-mod decoder_wrapper;
+pub(crate) mod decoder_wrapper;
 
-use std::sync::{
-	atomic::{AtomicBool, AtomicU64, AtomicU8, Ordering},
-	Arc,
+use std::{
+	collections::VecDeque,
+	sync::{
+		atomic::{AtomicBool, AtomicU64, AtomicU8, Ordering},
+		Arc,
+	},
 };
 
 use kira::{
 	clock::{ClockTime, Clocks},
-	dsp::{interpolate_frame, Frame},
+	dsp::{Frame, Interpolation},
 	parameter::Parameters,
-	sound::{static_sound::PlaybackState, Sound},
+	sound::{static_sound::PlaybackState, Sound, SoundEvent},
 	track::TrackId,
 	tween::{Tween, Tweenable},
-	value::CachedValue,
+	value::{CachedValue, Value},
 	StartTime,
 };
 use ringbuf::{Consumer, Producer, RingBuffer};
 
 use crate::{Command, Error, StreamingSoundData};
 
+use super::{DecoderPool, DecoderPoolShared};
+
 use self::decoder_wrapper::DecoderWrapper;
 
-const BUFFER_SIZE: usize = 16_384;
-const SEEK_DESTINATION_NONE: u64 = u64::MAX;
+/// The smallest number of frames the frame buffer can hold.
+const MIN_BUFFER_SIZE: usize = 64;
+/// The number of upcoming frames needed to interpolate
+/// the current frame.
+const LOOKAHEAD: usize = 5;
+/// The maximum number of frames the sound plays before waking
+/// up the decoder threads to refill the buffer.
+const MAX_WAKE_INTERVAL: usize = 1024;
+/// How many loop events can be queued up in addition to
+/// marker events before new events are discarded.
+const LOOP_EVENT_CAPACITY: usize = 16;
 
 pub(crate) struct Shared {
 	state: AtomicU8,
 	position: AtomicU64,
+	underrun_frames: AtomicU64,
 }
 
 impl Shared {
@@ -44,24 +59,147 @@ impl Shared {
 	pub fn position(&self) -> f64 {
 		f64::from_bits(self.position.load(Ordering::SeqCst))
 	}
+
+	pub fn underrun_frames(&self) -> u64 {
+		self.underrun_frames.load(Ordering::SeqCst)
+	}
+}
+
+/// A frame sent from the decoder thread to the sound.
+pub(crate) struct DecodedFrame {
+	/// The seek request the frame was decoded after. Frames
+	/// from before the latest seek are discarded.
+	seek_id: u64,
+	index: u64,
+	frame: Frame,
+}
+
+/// Tells the decoder thread to jump to a different frame
+/// and/or change the direction of playback.
+pub(crate) struct SeekRequest {
+	index: AtomicU64,
+	reverse: AtomicBool,
+	id: AtomicU64,
+}
+
+impl SeekRequest {
+	fn new() -> Self {
+		Self {
+			index: AtomicU64::new(0),
+			reverse: AtomicBool::new(false),
+			id: AtomicU64::new(0),
+		}
+	}
+
+	fn request(&self, index: u64, reverse: bool, id: u64) {
+		// the id is stored last so the decoder never sees a new
+		// id with an old destination
+		self.index.store(index, Ordering::SeqCst);
+		self.reverse.store(reverse, Ordering::SeqCst);
+		self.id.store(id, Ordering::SeqCst);
+	}
+
+	/// Returns the destination, direction, and id of the latest seek
+	/// request if it's newer than `last_id`.
+	fn take(&self, last_id: u64) -> Option<(u64, bool, u64)> {
+		let id = self.id.load(Ordering::SeqCst);
+		if id == last_id {
+			return None;
+		}
+		Some((
+			self.index.load(Ordering::SeqCst),
+			self.reverse.load(Ordering::SeqCst),
+			id,
+		))
+	}
+}
+
+/// Tells the sound that the decoder has sent its last frame.
+///
+/// A decoder that waits at the end of the audio for a seek (like
+/// the decoder for a stem) tags the signal with the ID of the seek
+/// it belongs to, so the sound doesn't mistake an old signal for
+/// the end of the audio after seeking.
+pub(crate) struct FinishedSignal(AtomicU64);
+
+impl FinishedSignal {
+	/// The value stored when the decoder has stopped for good.
+	const FINISHED_FOR_GOOD: u64 = u64::MAX;
+	/// The value stored when the decoder hasn't reached the end.
+	const NOT_FINISHED: u64 = u64::MAX - 1;
+
+	fn new() -> Self {
+		Self(AtomicU64::new(Self::NOT_FINISHED))
+	}
+
+	/// Signals that the decoder has stopped and won't send
+	/// any more frames.
+	pub fn finish(&self) {
+		self.0.store(Self::FINISHED_FOR_GOOD, Ordering::SeqCst);
+	}
+
+	/// Signals that the decoder has reached the end of the audio
+	/// after the seek with the given ID, but will send more frames
+	/// if the sound seeks again.
+	pub fn reach_end(&self, seek_id: u64) {
+		self.0.store(seek_id, Ordering::SeqCst);
+	}
+
+	/// Returns `true` if the decoder won't send any more frames
+	/// for the seek with the given ID.
+	fn received(&self, seek_id: u64) -> bool {
+		let value = self.0.load(Ordering::SeqCst);
+		value == Self::FINISHED_FOR_GOOD || value == seek_id
+	}
 }
 
 pub(crate) struct StreamingSound {
 	command_consumer: Consumer<Command>,
 	sample_rate: u32,
-	frame_consumer: Consumer<(u64, Frame)>,
-	seek_destination_sender: Arc<AtomicU64>,
+	frame_consumer: Consumer<DecodedFrame>,
+	decoder_pool: Arc<DecoderPoolShared>,
+	frames_since_wake: usize,
+	/// The number of frames the sound plays before waking up
+	/// the decoder threads to refill the buffer.
+	wake_interval: usize,
+	seek_request: Arc<SeekRequest>,
+	seek_id: u64,
 	stopped_signal_sender: Arc<AtomicBool>,
-	finished_signal_receiver: Arc<AtomicBool>,
+	finished_signal_receiver: Arc<FinishedSignal>,
+	/// Whether the sound keeps waiting for a seek when it reaches
+	/// the end instead of stopping.
+	wait_at_end: bool,
 	track: TrackId,
 	start_time: StartTime,
 	state: PlaybackState,
 	volume_fade: Tweenable,
+	reverse: bool,
+	num_frames: Option<u64>,
+	looping: bool,
+	/// Whether the decoder is currently sending frames
+	/// in reverse order.
+	decoding_reverse: bool,
 	current_frame: u64,
 	fractional_position: f64,
 	volume: CachedValue,
 	playback_rate: CachedValue,
 	panning: CachedValue,
+	interpolation: Interpolation,
+	/// The three frames that were played before the current
+	/// frame, which are needed by some interpolation modes.
+	history: [Frame; 3],
+	gain: f32,
+	/// Whether the sound has received audio since it started
+	/// or last seeked.
+	received_audio: bool,
+	underrun_frames: u64,
+	started: bool,
+	reported_start: bool,
+	/// The frame index and ID of each marker, sorted by index.
+	markers: Vec<(u64, u32)>,
+	/// Loop and marker events that haven't been reported yet,
+	/// in the order they happened.
+	unreported_events: VecDeque<SoundEvent>,
 	shared: Arc<Shared>,
 }
 
@@ -74,39 +212,64 @@ impl StreamingSound {
 		let sample_rate = data.sample_rate;
 		let start_time = data.settings.start_time;
 		let volume = CachedValue::new(.., data.settings.volume, 1.0);
-		let playback_rate = CachedValue::new(0.0.., data.settings.playback_rate, 1.0);
+		let playback_rate = CachedValue::new(.., data.settings.playback_rate, 1.0);
 		let panning = CachedValue::new(0.0..=1.0, data.settings.panning, 0.5);
 		let fade_in_tween = data.settings.fade_in_tween;
 		let track = data.settings.track;
-		let (mut frame_producer, frame_consumer) = RingBuffer::new(BUFFER_SIZE).split();
-		// pre-seed the frame ringbuffer with a zero frame. this is the "previous" frame
-		// when the sound just started.
-		frame_producer
-			.push((0, Frame::ZERO))
-			.expect("The frame producer shouldn't be full because we just created it");
-		let seek_destination_sender = Arc::new(AtomicU64::new(SEEK_DESTINATION_NONE));
-		let seek_destination_receiver = seek_destination_sender.clone();
+		let gain = data.gain() as f32;
+		let interpolation = data.settings.interpolation;
+		let reverse = data.settings.reverse;
+		let num_frames = data.num_frames;
+		let looping = data.settings.loop_behavior.is_some();
+		let mut markers = data
+			.settings
+			.markers
+			.iter()
+			.map(|marker| {
+				let index = (marker.position.max(0.0) * sample_rate as f64).round() as u64;
+				(index, marker.id)
+			})
+			.collect::<Vec<_>>();
+		markers.sort_unstable();
+		let decoder_pool = data
+			.settings
+			.decoder_pool
+			.clone()
+			.unwrap_or_else(DecoderPool::global);
+		let buffer_size = data.settings.buffer_size.max(MIN_BUFFER_SIZE);
+		let (frame_producer, frame_consumer) = RingBuffer::new(buffer_size).split();
+		let seek_request = Arc::new(SeekRequest::new());
 		let stopped_signal_sender = Arc::new(AtomicBool::new(false));
 		let stopped_signal_receiver = stopped_signal_sender.clone();
-		let finished_signal_sender = Arc::new(AtomicBool::new(false));
+		let finished_signal_sender = Arc::new(FinishedSignal::new());
 		let finished_signal_receiver = finished_signal_sender.clone();
-		let decoder_wrapper = DecoderWrapper::new(
+		let wait_at_end = data.wait_at_end;
+		let mut decoder_wrapper = DecoderWrapper::new(
 			data,
 			frame_producer,
-			seek_destination_receiver,
+			error_producer,
+			seek_request.clone(),
 			stopped_signal_receiver,
 			finished_signal_sender,
 		)?;
 		let current_frame = decoder_wrapper.current_frame();
-		decoder_wrapper.start(error_producer);
+		// send any preloaded frames right away so the sound
+		// can start playing immediately
+		decoder_wrapper.push_decoded_frames();
+		decoder_pool.add(decoder_wrapper);
 		let start_position = current_frame as f64 / sample_rate as f64;
 		Ok(Self {
 			command_consumer,
 			sample_rate,
 			frame_consumer,
-			seek_destination_sender,
+			decoder_pool: decoder_pool.shared(),
+			frames_since_wake: 0,
+			wake_interval: (buffer_size / 4).min(MAX_WAKE_INTERVAL),
+			seek_request,
+			seek_id: 0,
 			stopped_signal_sender,
 			finished_signal_receiver,
+			wait_at_end,
 			track,
 			start_time,
 			state: PlaybackState::Playing,
@@ -117,13 +280,27 @@ impl StreamingSound {
 			} else {
 				Tweenable::new(1.0)
 			},
+			reverse,
+			num_frames,
+			looping,
+			decoding_reverse: reverse,
 			current_frame,
 			fractional_position: 0.0,
 			volume,
 			playback_rate,
 			panning,
+			interpolation,
+			history: [Frame::ZERO; 3],
+			gain,
+			received_audio: false,
+			underrun_frames: 0,
+			started: false,
+			reported_start: false,
+			unreported_events: VecDeque::with_capacity(markers.len() + LOOP_EVENT_CAPACITY),
+			markers,
 			shared: Arc::new(Shared {
 				position: AtomicU64::new(start_position.to_bits()),
+				underrun_frames: AtomicU64::new(0),
 				state: AtomicU8::new(PlaybackState::Playing as u8),
 			}),
 		})
@@ -138,32 +315,129 @@ impl StreamingSound {
 		self.shared.state.store(state as u8, Ordering::SeqCst);
 	}
 
+	/// Discards frames that were decoded before the latest seek.
+	fn discard_stale_frames(&mut self) {
+		let seek_id = self.seek_id;
+		let num_stale_frames = self
+			.frame_consumer
+			.iter()
+			.take_while(|frame| frame.seek_id != seek_id)
+			.count();
+		self.frame_consumer.discard(num_stale_frames);
+	}
+
 	fn update_current_frame(&mut self) {
-		self.frame_consumer.access(|a, b| {
-			let mut iter = a.iter().chain(b.iter());
-			if let Some((index, _)) = iter.nth(1) {
-				self.current_frame = *index;
-			}
-		});
-	}
-
-	fn next_frames(&self) -> [Frame; 4] {
-		let mut frames = [Frame::ZERO; 4];
-		self.frame_consumer.access(|a, b| {
-			let mut iter = a.iter().chain(b.iter());
-			for frame in &mut frames {
-				*frame = iter
-					.next()
-					.copied()
-					.map(|(_, frame)| frame)
-					.unwrap_or(Frame::ZERO);
+		let next_index = self.frame_consumer.iter().next().map(|frame| frame.index);
+		if let Some(index) = next_index {
+			// the decoder jumps back to the loop start when it
+			// reaches the end of the sound
+			let looped = if self.decoding_reverse {
+				index > self.current_frame
+			} else {
+				index < self.current_frame
+			};
+			if looped && self.received_audio {
+				self.push_event(SoundEvent::Looped);
 			}
-		});
+			self.current_frame = index;
+		}
+	}
+
+	/// Returns the frames around the current frame that the
+	/// interpolation mode needs, where `frames[3]` is the
+	/// current frame.
+	fn next_frames(&self) -> [Frame; 8] {
+		let taps = self.interpolation.taps();
+		let mut frames = [Frame::ZERO; 8];
+		frames[taps.start..3].copy_from_slice(&self.history[taps.start..]);
+		let mut iter = self.frame_consumer.iter();
+		for frame in &mut frames[3..taps.end] {
+			*frame = iter.next().map(|frame| frame.frame).unwrap_or(Frame::ZERO);
+		}
 		frames
 	}
 
+	/// Returns the playback rate, taking the `reverse` setting into account.
+	fn playback_rate(&self) -> f64 {
+		if self.reverse {
+			-self.playback_rate.get()
+		} else {
+			self.playback_rate.get()
+		}
+	}
+
 	fn position(&self) -> f64 {
-		(self.current_frame as f64 + self.fractional_position) / self.sample_rate as f64
+		let position = if self.decoding_reverse {
+			self.current_frame as f64 - self.fractional_position
+		} else {
+			self.current_frame as f64 + self.fractional_position
+		};
+		position / self.sample_rate as f64
+	}
+
+	/// Adds an event to the queue. Events that don't fit in the
+	/// queue are discarded so the queue never allocates.
+	fn push_event(&mut self, event: SoundEvent) {
+		if self.unreported_events.len() < self.unreported_events.capacity() {
+			self.unreported_events.push_back(event);
+		}
+	}
+
+	/// Queues up the markers at a frame that the playback
+	/// position just moved past.
+	fn queue_markers(&mut self, index: u64) {
+		let start = self
+			.markers
+			.partition_point(|(marker_index, _)| *marker_index < index);
+		for i in start..self.markers.len() {
+			let (marker_index, id) = self.markers[i];
+			if marker_index != index {
+				break;
+			}
+			self.push_event(SoundEvent::MarkerCrossed(id));
+		}
+	}
+
+	/// Returns `true` if the sound has enough audio
+	/// to play the next frame.
+	pub fn ready(&mut self) -> bool {
+		self.discard_stale_frames();
+		self.frame_consumer.len() >= LOOKAHEAD || self.decoder_finished()
+	}
+
+	/// Returns `true` if the decoder won't send any more frames
+	/// unless the sound seeks.
+	fn decoder_finished(&self) -> bool {
+		self.finished_signal_receiver.received(self.seek_id)
+	}
+
+	/// Returns `true` if the sound has played all of its audio.
+	pub fn reached_end(&self) -> bool {
+		self.decoder_finished() && self.frame_consumer.is_empty()
+	}
+
+	pub fn set_volume(&mut self, volume: Value) {
+		self.volume.set(volume);
+	}
+
+	pub fn set_playback_rate(&mut self, playback_rate: Value) {
+		self.playback_rate.set(playback_rate);
+	}
+
+	/// Returns how long the sound will keep playing (in seconds)
+	/// at its current playback rate, or `None` if that can't
+	/// be determined.
+	pub fn remaining_duration(&self) -> Option<f64> {
+		let playback_rate = self.playback_rate().abs();
+		if self.looping || playback_rate == 0.0 {
+			return None;
+		}
+		let remaining_frames = if self.decoding_reverse {
+			self.current_frame as f64 - self.fractional_position
+		} else {
+			self.num_frames? as f64 - self.current_frame as f64 - self.fractional_position
+		};
+		Some(remaining_frames.max(0.0) / (self.sample_rate as f64 * playback_rate))
 	}
 
 	fn pause(&mut self, tween: Tween) {
@@ -181,12 +455,21 @@ impl StreamingSound {
 		self.volume_fade.set(0.0, tween);
 	}
 
-	fn seek_to_index(&mut self, index: u64) {
-		self.seek_destination_sender.store(index, Ordering::SeqCst);
+	fn seek_to_index(&mut self, index: u64, reverse: bool) {
+		self.seek_id += 1;
+		self.seek_request.request(index, reverse, self.seek_id);
+		self.decoding_reverse = reverse;
+		self.current_frame = index;
+		self.fractional_position = 0.0;
+		self.history = [Frame::ZERO; 3];
+		self.received_audio = false;
+		self.discard_stale_frames();
+		self.decoder_pool.wake();
 	}
 
-	fn seek_to(&mut self, position: f64) {
-		self.seek_to_index((position * self.sample_rate as f64).round() as u64);
+	pub fn seek_to(&mut self, position: f64) {
+		let index = (position.max(0.0) * self.sample_rate as f64).round() as u64;
+		self.seek_to_index(index, self.decoding_reverse);
 	}
 
 	fn seek_by(&mut self, amount: f64) {
@@ -203,6 +486,9 @@ impl Sound for StreamingSound {
 		self.shared
 			.position
 			.store(self.position().to_bits(), Ordering::SeqCst);
+		self.shared
+			.underrun_frames
+			.store(self.underrun_frames, Ordering::SeqCst);
 		while let Some(command) = self.command_consumer.pop() {
 			match command {
 				Command::SetVolume(volume) => self.volume.set(volume),
@@ -231,14 +517,29 @@ impl Sound for StreamingSound {
 		if matches!(self.state, PlaybackState::Paused | PlaybackState::Stopped) {
 			return Frame::ZERO;
 		}
-		// pause playback while waiting for audio data. the first frame
-		// in the ringbuffer is the previous frame, so we need to make
-		// sure there's at least 2 before we continue playing.
-		if self.frame_consumer.len() < 2 && !self.finished_signal_receiver.load(Ordering::SeqCst) {
+		self.playback_rate.update(parameters);
+		// if the direction of playback changed, ask the decoder to
+		// start sending frames in the other direction
+		let playback_rate = self.playback_rate();
+		if playback_rate != 0.0 && (playback_rate < 0.0) != self.decoding_reverse {
+			let index = self.current_frame;
+			self.seek_to_index(index, playback_rate < 0.0);
+		}
+		self.discard_stale_frames();
+		// a sound that's waiting at the end stays silent until it seeks
+		if self.wait_at_end && self.reached_end() {
+			return Frame::ZERO;
+		}
+		// pause playback while waiting for audio data
+		if self.frame_consumer.len() < LOOKAHEAD && !self.decoder_finished() {
+			// waiting for the first audio isn't an underrun, since
+			// the decoder hasn't had a chance to catch up yet
+			if self.received_audio {
+				self.underrun_frames += 1;
+			}
 			return Frame::ZERO;
 		}
 		self.volume.update(parameters);
-		self.playback_rate.update(parameters);
 		self.panning.update(parameters);
 		if self.volume_fade.update(dt, clocks) {
 			match self.state {
@@ -248,26 +549,43 @@ impl Sound for StreamingSound {
 			}
 		}
 		self.update_current_frame();
+		self.received_audio = true;
+		self.started = true;
 		let next_frames = self.next_frames();
-		let out = interpolate_frame(
-			next_frames[0],
-			next_frames[1],
-			next_frames[2],
-			next_frames[3],
-			self.fractional_position as f32,
-		);
-		self.fractional_position += self.sample_rate as f64 * self.playback_rate.get() * dt;
+		let out = self
+			.interpolation
+			.interpolate(next_frames, self.fractional_position as f32);
+		self.fractional_position += self.sample_rate as f64 * playback_rate.abs() * dt;
 		while self.fractional_position >= 1.0 {
 			self.fractional_position -= 1.0;
-			self.frame_consumer.pop();
+			// check for a loop before each frame so the loop is
+			// reported between the markers on either side of it
+			self.update_current_frame();
+			if let Some(frame) = self.frame_consumer.pop() {
+				self.queue_markers(frame.index);
+				self.history = [self.history[1], self.history[2], frame.frame];
+				self.frames_since_wake += 1;
+			}
 		}
-		if self.finished_signal_receiver.load(Ordering::SeqCst) && self.frame_consumer.is_empty() {
+		if self.frames_since_wake >= self.wake_interval {
+			self.frames_since_wake = 0;
+			self.decoder_pool.wake();
+		}
+		if !self.wait_at_end && self.reached_end() {
 			self.set_state(PlaybackState::Stopped);
 		}
-		(out * self.volume_fade.value() as f32 * self.volume.get() as f32)
+		(out * self.gain * self.volume_fade.value() as f32 * self.volume.get() as f32)
 			.panned(self.panning.get() as f32)
 	}
 
+	fn pop_event(&mut self) -> Option<SoundEvent> {
+		if self.started && !self.reported_start {
+			self.reported_start = true;
+			return Some(SoundEvent::Started);
+		}
+		self.unreported_events.pop_front()
+	}
+
 	fn finished(&self) -> bool {
 		self.state == PlaybackState::Stopped
 	}
@@ -276,6 +594,7 @@ impl Sound for StreamingSound {
 impl Drop for StreamingSound {
 	fn drop(&mut self) {
 		self.stopped_signal_sender.store(true, Ordering::SeqCst);
+		self.decoder_pool.wake();
 		println!("dropped sound");
 	}
 }
@@ -1,10 +1,10 @@
 use std::{
 	collections::VecDeque,
 	sync::{
-		atomic::{AtomicBool, AtomicU64, Ordering},
+		atomic::{AtomicBool, Ordering},
 		Arc,
 	},
-	time::Duration,
+	time::{Duration, Instant},
 };
 
 use kira::{dsp::Frame, LoopBehavior};
@@ -17,69 +17,227 @@ use symphonia::core::{
 	sample::Sample,
 };
 
-use crate::{Error, StreamingSoundData};
+use crate::{streaming::data::PreloadedFrames, Error, ErrorPolicy, StreamingSoundData};
 
-use super::SEEK_DESTINATION_NONE;
+use super::{DecodedFrame, FinishedSignal, SeekRequest};
 
-const DECODER_THREAD_SLEEP_DURATION: Duration = Duration::from_millis(1);
+/// The maximum number of frames decoded before giving other
+/// streams a chance to decode.
+const FRAMES_PER_TURN: usize = 4096;
+/// The number of frames decoded at a time when playing backwards.
+const REVERSE_CHUNK_SIZE: u64 = 8192;
+/// How long a stream waits before decoding again after a
+/// turn where it made no progress. The delay doubles for each
+/// unproductive turn in a row, up to [`MAX_IDLE_DELAY`].
+const MIN_IDLE_DELAY: Duration = Duration::from_millis(1);
+const MAX_IDLE_DELAY: Duration = Duration::from_millis(64);
+/// How long to wait before retrying a failed read. The delay
+/// grows with each retry in a row.
+const IO_RETRY_DELAY: Duration = Duration::from_millis(10);
+
+/// The outcome of decoding a packet.
+enum NextPacket {
+	Decoded,
+	/// The packet was corrupt and was replaced with silence.
+	Skipped,
+	/// Reading the packet failed, but may succeed if retried
+	/// after a short wait.
+	Retry,
+	EndOfFile,
+}
 
 pub struct DecoderWrapper {
 	format_reader: Box<dyn FormatReader>,
 	decoder: Box<dyn Decoder>,
 	sample_rate: u32,
 	track_id: u32,
+	num_frames: Option<u64>,
 	loop_behavior: Option<LoopBehavior>,
-	frame_producer: Producer<(u64, Frame)>,
-	seek_destination_receiver: Arc<AtomicU64>,
+	frame_producer: Producer<DecodedFrame>,
+	error_producer: Producer<Error>,
+	seek_request: Arc<SeekRequest>,
 	stopped_signal_receiver: Arc<AtomicBool>,
-	finished_signal_sender: Arc<AtomicBool>,
+	finished_signal_sender: Arc<FinishedSignal>,
+	/// Whether the decoder waits for a seek when it reaches the
+	/// end of the audio instead of finishing.
+	wait_at_end: bool,
+	/// Whether the decoder has reached the end of the audio and
+	/// is waiting for a seek.
+	waiting_at_end: bool,
 	decoded_frames: VecDeque<Frame>,
+	reverse: bool,
+	seek_id: u64,
+	/// The index of the next frame that will be sent to the sound.
+	/// When playing backwards, this is one past the next frame, so
+	/// it reaches 0 at the beginning of the file.
 	current_frame: u64,
+	/// The number of frames sent to the sound during the
+	/// current turn.
+	frames_pushed: usize,
+	loop_head: Option<PreloadedFrames>,
+	/// The number of decoded frames to throw away after
+	/// an inexact seek.
+	frames_to_skip: u64,
+	error_policy: ErrorPolicy,
+	/// The number of times in a row reading the audio
+	/// has failed.
+	io_retries: u32,
+	/// Whether the last step of decoding produced any audio.
+	made_progress: bool,
+	/// The number of turns in a row that didn't produce any audio.
+	idle_turns: u32,
+	/// The stream won't be decoded again until this time (unless
+	/// it's stopped or seeked), either because it's waiting to
+	/// retry a failed read or because it made no progress.
+	resume_at: Option<Instant>,
+	/// The start position (counted from the end) of a reverse
+	/// sound whose length isn't known yet. Finding the length
+	/// means decoding the whole file, so it's done on the first
+	/// turn instead of when the sound is created.
+	reverse_start_index: Option<u64>,
 }
 
 impl DecoderWrapper {
 	pub fn new(
 		data: StreamingSoundData,
-		frame_producer: Producer<(u64, Frame)>,
-		seek_destination_receiver: Arc<AtomicU64>,
+		frame_producer: Producer<DecodedFrame>,
+		error_producer: Producer<Error>,
+		seek_request: Arc<SeekRequest>,
 		stopped_signal_receiver: Arc<AtomicBool>,
-		finished_signal_sender: Arc<AtomicBool>,
+		finished_signal_sender: Arc<FinishedSignal>,
 	) -> Result<Self, Error> {
 		let mut wrapper = Self {
 			format_reader: data.format_reader,
 			decoder: data.decoder,
 			sample_rate: data.sample_rate,
 			track_id: data.track_id,
+			num_frames: data.num_frames,
 			loop_behavior: data.settings.loop_behavior,
 			frame_producer,
-			seek_destination_receiver,
+			error_producer,
+			seek_request,
 			stopped_signal_receiver,
 			finished_signal_sender,
+			wait_at_end: data.wait_at_end,
+			waiting_at_end: false,
 			decoded_frames: VecDeque::new(),
+			reverse: data.settings.reverse,
+			seek_id: 0,
 			current_frame: 0,
+			frames_pushed: 0,
+			loop_head: data.loop_head,
+			frames_to_skip: 0,
+			error_policy: data.settings.error_policy,
+			io_retries: 0,
+			made_progress: false,
+			idle_turns: 0,
+			resume_at: None,
+			reverse_start_index: None,
 		};
-		wrapper.seek(data.settings.start_position)?;
+		let start_index =
+			(data.settings.start_position * wrapper.sample_rate as f64).round() as u64;
+		if wrapper.reverse {
+			// in reverse, the start position is relative to the end
+			match wrapper.num_frames {
+				Some(num_frames) => {
+					wrapper.seek_to_index(num_frames.saturating_sub(start_index + 1))?
+				}
+				None => wrapper.reverse_start_index = Some(start_index),
+			}
+		} else {
+			match &data.head {
+				Some(head) if head.start_index == start_index => wrapper.queue_preloaded(head)?,
+				_ => wrapper.seek_to_index(start_index)?,
+			}
+		}
 		Ok(wrapper)
 	}
 
+	/// Returns the index of the first frame that will be played.
 	pub fn current_frame(&self) -> u64 {
-		self.current_frame
+		if self.reverse {
+			self.current_frame.saturating_sub(1)
+		} else {
+			self.current_frame
+		}
 	}
 
-	pub fn start(mut self, mut error_producer: Producer<Error>) {
-		std::thread::spawn(move || loop {
+	/// Returns how urgently this stream needs to be decoded, or
+	/// `None` if its buffer is full or it's waiting to decode again
+	/// after making no progress.
+	///
+	/// Lower values are more urgent.
+	pub fn priority(&self, now: Instant) -> Option<f64> {
+		if self.stopped_signal_receiver.load(Ordering::SeqCst)
+			|| self.seek_request.take(self.seek_id).is_some()
+		{
+			return Some(-1.0);
+		}
+		if self.frame_producer.is_full()
+			|| self.waiting_at_end
+			|| self.resume_at.is_some_and(|time| time > now)
+		{
+			return None;
+		}
+		Some(self.frame_producer.len() as f64 / self.frame_producer.capacity() as f64)
+	}
+
+	/// Returns the time the stream will be ready to decode again,
+	/// if it's waiting after making no progress.
+	pub fn resume_at(&self) -> Option<Instant> {
+		self.resume_at
+	}
+
+	/// Decodes frames until the buffer is full, enough frames
+	/// have been decoded for this turn, or decoding stops making
+	/// progress.
+	///
+	/// If the turn didn't produce any audio (for example, because
+	/// reading failed or every packet was corrupt), the stream
+	/// waits a little before it's decoded again so it doesn't hog
+	/// the thread.
+	///
+	/// Returns `true` if the stream is finished and no longer
+	/// needs to be decoded.
+	pub fn decode_turn(&mut self) -> bool {
+		self.frames_pushed = 0;
+		self.resume_at = None;
+		let mut made_progress = false;
+		while self.frames_pushed < FRAMES_PER_TURN {
+			self.made_progress = false;
 			match self.run() {
-				Ok(should_end_thread) => {
-					if should_end_thread {
-						break;
-					}
-				}
+				Ok(true) => return true,
+				Ok(false) => {}
 				Err(error) => {
-					error_producer.push(error).ok();
-					break;
+					// let the sound play the frames it already has
+					// and then stop
+					self.error_producer.push(error).ok();
+					self.finished_signal_sender.finish();
+					return true;
 				}
 			}
-		});
+			made_progress |= self.made_progress;
+			// give up the rest of the turn if nothing happened, like
+			// after a failed read, so the stream is tried again later
+			if self.frame_producer.is_full() || !self.made_progress {
+				break;
+			}
+		}
+		if made_progress || self.frame_producer.is_full() || self.waiting_at_end {
+			self.idle_turns = 0;
+		} else {
+			let delay = MIN_IDLE_DELAY
+				.saturating_mul(1 << self.idle_turns.min(6))
+				.min(MAX_IDLE_DELAY);
+			let idle_until = Instant::now() + delay;
+			// don't cut short the wait before retrying a read
+			self.resume_at = Some(
+				self.resume_at
+					.map_or(idle_until, |time| time.max(idle_until)),
+			);
+			self.idle_turns = self.idle_turns.saturating_add(1);
+		}
+		false
 	}
 
 	fn run(&mut self) -> Result<bool, Error> {
@@ -87,36 +245,79 @@ impl DecoderWrapper {
 		if self.stopped_signal_receiver.load(Ordering::SeqCst) {
 			return Ok(true);
 		}
-		// if the frame ringbuffer is full, sleep for a bit
-		if self.frame_producer.is_full() {
-			std::thread::sleep(DECODER_THREAD_SLEEP_DURATION);
-			return Ok(false);
+		// check for seek requests
+		if let Some((index, reverse, seek_id)) = self.seek_request.take(self.seek_id) {
+			self.reverse_start_index = None;
+			self.waiting_at_end = false;
+			self.reverse = reverse;
+			self.seek_to_index(index)?;
+			self.seek_id = seek_id;
 		}
-		// check for seek commands
-		let seek_destination = self.seek_destination_receiver.load(Ordering::SeqCst);
-		if seek_destination != SEEK_DESTINATION_NONE {
-			self.seek_to_index(seek_destination)?;
-			self.seek_destination_receiver
-				.store(SEEK_DESTINATION_NONE, Ordering::SeqCst);
+		if let Some(start_index) = self.reverse_start_index.take() {
+			let num_frames = self.num_frames()?;
+			self.seek_to_index(num_frames.saturating_sub(start_index + 1))?;
+			self.made_progress = true;
+		}
+		// if the frame ringbuffer is full or we've reached the
+		// end, wait for the sound to consume some frames or seek
+		if self.frame_producer.is_full() || self.waiting_at_end {
+			return Ok(false);
 		}
 		// if we have leftover frames from the last decode, push
 		// those first
 		if let Some(frame) = self.decoded_frames.pop_front() {
+			let index = if self.reverse {
+				self.current_frame -= 1;
+				self.current_frame
+			} else {
+				self.current_frame += 1;
+				self.current_frame - 1
+			};
 			self.frame_producer
-				.push((self.current_frame, frame))
+				.push(DecodedFrame {
+					seek_id: self.seek_id,
+					index,
+					frame,
+				})
+				.ok()
 				.expect("Frame producer should not be full because we just checked that");
-			self.current_frame += 1;
+			self.frames_pushed += 1;
+			self.made_progress = true;
 		// otherwise, decode some new frames
 		} else {
-			let reached_end_of_file = self.decode()?;
+			let reached_end_of_file = if self.reverse {
+				self.decode_reverse_chunk()?
+			} else {
+				self.decode()?
+			};
 			if reached_end_of_file {
 				// if there aren't any new frames and the sound is looping,
 				// seek back to the loop position
 				if let Some(LoopBehavior { start_position }) = self.loop_behavior {
-					self.seek(start_position)?;
+					if self.reverse {
+						let num_frames = self.num_frames()?;
+						self.seek_to_index(num_frames.saturating_sub(1))?;
+					} else {
+						let loop_start = (start_position * self.sample_rate as f64).round() as u64;
+						match self.loop_head.take() {
+							Some(loop_head) if loop_head.start_index == loop_start => {
+								self.queue_preloaded(&loop_head)?;
+								self.loop_head = Some(loop_head);
+							}
+							loop_head => {
+								self.loop_head = loop_head;
+								self.seek(start_position)?;
+							}
+						}
+					}
+				// if the sound may seek again, tell it we've reached
+				// the end and wait
+				} else if self.wait_at_end {
+					self.finished_signal_sender.reach_end(self.seek_id);
+					self.waiting_at_end = true;
 				// otherwise, tell the sound to finish and end the thread
 				} else {
-					self.finished_signal_sender.store(true, Ordering::SeqCst);
+					self.finished_signal_sender.finish();
 					return Ok(true);
 				}
 			}
@@ -124,26 +325,137 @@ impl DecoderWrapper {
 		Ok(false)
 	}
 
+	/// Sends as many decoded frames to the sound as will fit
+	/// in the frame buffer.
+	pub fn push_decoded_frames(&mut self) {
+		while !self.decoded_frames.is_empty() && !self.frame_producer.is_full() {
+			self.run().ok();
+		}
+	}
+
+	/// Queues up frames that were decoded ahead of time and moves
+	/// the decoder to the frame after them.
+	fn queue_preloaded(&mut self, preloaded: &PreloadedFrames) -> Result<(), Error> {
+		let next_index = preloaded.start_index + preloaded.frames.len() as u64;
+		let seeked_to = self.seek_exact(next_index)?;
+		self.frames_to_skip = next_index.saturating_sub(seeked_to);
+		self.decoded_frames = preloaded.frames.iter().copied().collect();
+		self.current_frame = preloaded.start_index;
+		Ok(())
+	}
+
 	fn decode(&mut self) -> Result<bool, Error> {
-		match self.format_reader.next_packet() {
-			Ok(packet) => {
-				let buffer = self.decoder.decode(&packet)?;
-				load_frames_from_buffer_ref(&mut self.decoded_frames, &buffer)?;
+		// a failed read may leave the reader partway through a
+		// packet, so seek back to the next frame we need
+		if self.io_retries > 0 {
+			let seeked_to = self.seek_exact(self.current_frame)?;
+			self.frames_to_skip = self.current_frame.saturating_sub(seeked_to);
+		}
+		let mut frames = std::mem::take(&mut self.decoded_frames);
+		let result = self.decode_packet(&mut frames);
+		self.decoded_frames = frames;
+		match result? {
+			NextPacket::Decoded | NextPacket::Skipped => {
+				self.made_progress = true;
+				while self.frames_to_skip > 0 && self.decoded_frames.pop_front().is_some() {
+					self.frames_to_skip -= 1;
+				}
 			}
-			Err(error) => match error {
-				symphonia::core::errors::Error::IoError(error) => {
-					if error.kind() == std::io::ErrorKind::UnexpectedEof {
-						return Ok(true);
+			NextPacket::Retry => {}
+			NextPacket::EndOfFile => return Ok(true),
+		}
+		Ok(false)
+	}
+
+	/// Decodes the next packet and adds its frames to `frames`,
+	/// handling errors according to the [`ErrorPolicy`].
+	fn decode_packet(&mut self, frames: &mut VecDeque<Frame>) -> Result<NextPacket, Error> {
+		let packet = match self.format_reader.next_packet() {
+			Ok(packet) => packet,
+			Err(symphonia::core::errors::Error::IoError(error)) => {
+				if error.kind() == std::io::ErrorKind::UnexpectedEof {
+					return Ok(NextPacket::EndOfFile);
+				}
+				if let ErrorPolicy::RetryIo { max_retries } = self.error_policy {
+					if self.io_retries < max_retries {
+						self.io_retries += 1;
+						// give whatever caused the error some time to
+						// clear up before reading again
+						self.resume_at =
+							Some(Instant::now() + IO_RETRY_DELAY.saturating_mul(self.io_retries));
+						return Ok(NextPacket::Retry);
 					}
-					return Err(symphonia::core::errors::Error::IoError(error).into());
 				}
-				error => return Err(error.into()),
-			},
+				return Err(symphonia::core::errors::Error::IoError(error).into());
+			}
+			Err(error) => return Err(error.into()),
+		};
+		self.io_retries = 0;
+		match self.decoder.decode(&packet) {
+			Ok(buffer) => {
+				load_frames_from_buffer_ref(frames, &buffer)?;
+				Ok(NextPacket::Decoded)
+			}
+			Err(error @ symphonia::core::errors::Error::DecodeError(_))
+				if self.error_policy != ErrorPolicy::Stop =>
+			{
+				self.error_producer.push(error.into()).ok();
+				// fill in the packet with silence so the frames after
+				// it keep their place in the audio. (packet durations
+				// are measured in frames.)
+				frames.resize(frames.len() + packet.duration() as usize, Frame::ZERO);
+				Ok(NextPacket::Skipped)
+			}
+			Err(error) => Err(error.into()),
 		}
-		Ok(false)
 	}
 
-	fn seek_to_index(&mut self, index: u64) -> Result<(), Error> {
+	/// Decodes the chunk of frames before the current frame and queues
+	/// them up in reverse order.
+	///
+	/// Returns `true` if the beginning of the loop (or the file) was reached.
+	fn decode_reverse_chunk(&mut self) -> Result<bool, Error> {
+		let loop_start = match self.loop_behavior {
+			Some(LoopBehavior { start_position }) => {
+				(start_position * self.sample_rate as f64).round() as u64
+			}
+			None => 0,
+		};
+		if self.current_frame <= loop_start {
+			return Ok(true);
+		}
+		let chunk_end = self.current_frame;
+		let chunk_start = chunk_end.saturating_sub(REVERSE_CHUNK_SIZE).max(loop_start);
+		// seeking may land before the requested frame, so keep track of
+		// the index of each decoded frame
+		let mut index = self.seek_exact(chunk_start)?;
+		let mut chunk = VecDeque::new();
+		while index < chunk_end {
+			let mut frames = VecDeque::new();
+			match self.decode_packet(&mut frames)? {
+				NextPacket::Decoded | NextPacket::Skipped => {}
+				// try decoding the whole chunk again on the next turn
+				NextPacket::Retry => return Ok(false),
+				NextPacket::EndOfFile => break,
+			}
+			for frame in frames {
+				if (chunk_start..chunk_end).contains(&index) {
+					chunk.push_front(frame);
+				}
+				index += 1;
+			}
+		}
+		// if the file ended early, the frames at the end of the
+		// chunk are missing
+		self.current_frame = chunk_start + chunk.len() as u64;
+		self.made_progress |= !chunk.is_empty();
+		self.decoded_frames = chunk;
+		Ok(self.decoded_frames.is_empty())
+	}
+
+	/// Seeks to the given frame and returns the index of the
+	/// frame the decoder actually landed on.
+	fn seek_exact(&mut self, index: u64) -> Result<u64, Error> {
 		let seeked_to = self.format_reader.seek(
 			SeekMode::Accurate,
 			SeekTo::TimeStamp {
@@ -151,7 +463,23 @@ impl DecoderWrapper {
 				track_id: self.track_id,
 			},
 		)?;
-		self.current_frame = seeked_to.actual_ts;
+		self.decoder.reset();
+		Ok(seeked_to.actual_ts)
+	}
+
+	fn seek_to_index(&mut self, index: u64) -> Result<(), Error> {
+		self.decoded_frames.clear();
+		self.frames_to_skip = 0;
+		if self.reverse {
+			// the next chunk will be decoded from this position
+			self.current_frame = index + 1;
+		} else {
+			// the reader may land before the requested frame, so skip
+			// ahead to keep the playback position accurate
+			let seeked_to = self.seek_exact(index)?;
+			self.frames_to_skip = index.saturating_sub(seeked_to);
+			self.current_frame = seeked_to.max(index);
+		}
 		Ok(())
 	}
 
@@ -160,8 +488,35 @@ impl DecoderWrapper {
 		self.seek_to_index(index)?;
 		Ok(())
 	}
-}
 
+	/// Returns the total number of frames in the file, decoding
+	/// the whole file to count them if the file doesn't say.
+	fn num_frames(&mut self) -> Result<u64, Error> {
+		if let Some(num_frames) = self.num_frames {
+			return Ok(num_frames);
+		}
+		self.seek_exact(0)?;
+		let mut num_frames = 0;
+		loop {
+			let mut frames = VecDeque::new();
+			match self.format_reader.next_packet() {
+				Ok(packet) => {
+					let buffer = self.decoder.decode(&packet)?;
+					load_frames_from_buffer_ref(&mut frames, &buffer)?;
+					num_frames += frames.len() as u64;
+				}
+				Err(symphonia::core::errors::Error::IoError(error))
+					if error.kind() == std::io::ErrorKind::UnexpectedEof =>
+				{
+					break
+				}
+				Err(error) => return Err(error.into()),
+			}
+		}
+		self.num_frames = Some(num_frames);
+		Ok(num_frames)
+	}
+}
 fn load_frames_from_buffer_ref(
 	frames: &mut VecDeque<Frame>,
 	buffer: &AudioBufferRef,
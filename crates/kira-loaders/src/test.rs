@@ -0,0 +1,749 @@
+use std::{
+	io::{Cursor, Read, Seek, SeekFrom},
+	sync::{mpsc, Arc, Mutex},
+	time::{Duration, Instant},
+};
+
+use kira::{
+	dsp::{Frame, Interpolation},
+	manager::{backend::MockBackend, event::Event, AudioManager, AudioManagerSettings},
+	sound::{static_sound::PlaybackState, Marker},
+	LoopBehavior,
+};
+use symphonia::core::{
+	audio::AudioBufferRef,
+	codecs::{CodecDescriptor, CodecParameters, Decoder, DecoderOptions, FinalizeResult},
+	errors::{decode_error, unsupported_error},
+	formats::Packet,
+};
+
+use crate::{
+	DecoderPool, Error, ErrorPolicy, FormatHint, LoadSettings, Loudness, StreamingSoundData,
+	StreamingSoundHandle, StreamingSoundSettings,
+};
+
+pub(crate) const SAMPLE_RATE: u32 = 8000;
+
+/// Creates an [`AudioManager`] with a [`MockBackend`] running
+/// at [`SAMPLE_RATE`].
+pub(crate) fn create_manager() -> AudioManager<MockBackend> {
+	AudioManager::new(
+		MockBackend::new(SAMPLE_RATE),
+		AudioManagerSettings::default(),
+	)
+	.unwrap()
+}
+
+/// Creates a WAV file containing one second of a stereo square wave.
+pub(crate) fn create_wav() -> Vec<u8> {
+	create_wav_with_length(SAMPLE_RATE)
+}
+
+/// Creates a WAV file containing a stereo square wave
+/// with the given number of frames.
+pub(crate) fn create_wav_with_length(num_frames: u32) -> Vec<u8> {
+	let samples = (0..num_frames)
+		.flat_map(|i| {
+			let sample: i16 = if (i / 20) % 2 == 0 { 8192 } else { -8192 };
+			[sample, sample]
+		})
+		.collect::<Vec<_>>();
+	let data_len = samples.len() as u32 * 2;
+	let mut wav = vec![];
+	wav.extend_from_slice(b"RIFF");
+	wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+	wav.extend_from_slice(b"WAVE");
+	wav.extend_from_slice(b"fmt ");
+	wav.extend_from_slice(&16u32.to_le_bytes());
+	wav.extend_from_slice(&1u16.to_le_bytes());
+	wav.extend_from_slice(&2u16.to_le_bytes());
+	wav.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+	wav.extend_from_slice(&(SAMPLE_RATE * 4).to_le_bytes());
+	wav.extend_from_slice(&4u16.to_le_bytes());
+	wav.extend_from_slice(&16u16.to_le_bytes());
+	wav.extend_from_slice(b"data");
+	wav.extend_from_slice(&data_len.to_le_bytes());
+	for sample in samples {
+		wav.extend_from_slice(&sample.to_le_bytes());
+	}
+	wav
+}
+
+/// A reader that fails or stalls when it reaches a certain
+/// point in the data.
+struct FaultyReader {
+	cursor: Cursor<Vec<u8>>,
+	fault_position: u64,
+	stall: bool,
+	faults_left: u32,
+	/// The times at which reads failed.
+	failed_reads: Arc<Mutex<Vec<Instant>>>,
+}
+
+impl FaultyReader {
+	/// Creates a reader that fails or stalls once.
+	fn new(fault_position: u64, stall: bool) -> Self {
+		Self {
+			cursor: Cursor::new(create_wav()),
+			fault_position,
+			stall,
+			faults_left: 1,
+			failed_reads: Arc::new(Mutex::new(vec![])),
+		}
+	}
+
+	/// Creates a reader that fails `num_faults` times in a row
+	/// and then succeeds.
+	fn failing(fault_position: u64, num_faults: u32) -> Self {
+		Self {
+			faults_left: num_faults,
+			..Self::new(fault_position, false)
+		}
+	}
+
+	fn failed_reads(&self) -> Arc<Mutex<Vec<Instant>>> {
+		self.failed_reads.clone()
+	}
+}
+
+impl Read for FaultyReader {
+	fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+		if self.faults_left > 0 && self.cursor.position() >= self.fault_position {
+			self.faults_left -= 1;
+			if self.stall {
+				std::thread::sleep(Duration::from_millis(50));
+			} else {
+				self.failed_reads.lock().unwrap().push(Instant::now());
+				return Err(std::io::Error::other("read failed"));
+			}
+		}
+		self.cursor.read(buf)
+	}
+}
+
+impl Seek for FaultyReader {
+	fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+		self.cursor.seek(pos)
+	}
+}
+
+/// A decoder that reports one packet of audio as corrupt.
+struct FaultyDecoder {
+	decoder: Box<dyn Decoder>,
+	/// The number of packets to decode before the corrupt one.
+	packets_left: u32,
+}
+
+impl Decoder for FaultyDecoder {
+	fn try_new(_: &CodecParameters, _: &DecoderOptions) -> symphonia::core::errors::Result<Self> {
+		unsupported_error("the faulty decoder wraps another decoder")
+	}
+
+	fn supported_codecs() -> &'static [CodecDescriptor] {
+		&[]
+	}
+
+	fn reset(&mut self) {
+		self.decoder.reset()
+	}
+
+	fn codec_params(&self) -> &CodecParameters {
+		self.decoder.codec_params()
+	}
+
+	fn decode(&mut self, packet: &Packet) -> symphonia::core::errors::Result<AudioBufferRef<'_>> {
+		match self.packets_left.checked_sub(1) {
+			Some(packets_left) => self.packets_left = packets_left,
+			None => {
+				self.packets_left = u32::MAX;
+				return decode_error("corrupt packet");
+			}
+		}
+		self.decoder.decode(packet)
+	}
+
+	fn finalize(&mut self) -> FinalizeResult {
+		self.decoder.finalize()
+	}
+}
+
+/// Plays a streaming sound until it finishes, returning
+/// the handle and the number of frames that were played.
+fn play_to_end(
+	reader: FaultyReader,
+	settings: StreamingSoundSettings,
+) -> (StreamingSoundHandle, usize) {
+	let mut manager = create_manager();
+	let data = StreamingSoundData::from_reader(reader, None, settings.buffer_size(64)).unwrap();
+	let handle = manager.play(data).unwrap();
+	let backend = manager.backend_mut();
+	let mut frames_played = 0;
+	for _ in 0..1000 {
+		backend.on_start_processing();
+		if handle.state() == PlaybackState::Stopped {
+			break;
+		}
+		for _ in 0..100 {
+			if backend.process() != Frame::ZERO {
+				frames_played += 1;
+			}
+		}
+		std::thread::sleep(Duration::from_millis(1));
+	}
+	(handle, frames_played)
+}
+
+#[test]
+fn stream_from_memory() {
+	let mut data = StreamingSoundData::from_bytes(
+		create_wav(),
+		FormatHint::new().extension("wav"),
+		StreamingSoundSettings::new().interpolation(Interpolation::Nearest),
+	)
+	.unwrap();
+	assert_eq!(data.sample_rate, SAMPLE_RATE);
+	// the square wave's samples peak at -12 dBFS, and the
+	// reconstructed waveform overshoots that
+	let loudness = data.measure_loudness().unwrap();
+	assert!(loudness.integrated.is_finite());
+	assert!(loudness.true_peak > -12.05);
+	let mut manager = create_manager();
+	let mut handle = manager.play(data).unwrap();
+	let backend = manager.backend_mut();
+	let mut frames = vec![];
+	for _ in 0..1000 {
+		backend.on_start_processing();
+		if handle.state() == PlaybackState::Stopped {
+			break;
+		}
+		for _ in 0..100 {
+			let frame = backend.process();
+			if frame != Frame::ZERO {
+				frames.push(frame);
+			}
+		}
+		std::thread::sleep(Duration::from_millis(1));
+	}
+	// the whole square wave is played, switching sign every 20 frames
+	assert_eq!(frames.len(), SAMPLE_RATE as usize);
+	for (i, frame) in frames.iter().enumerate() {
+		assert_eq!(frame.left, frame.right);
+		assert_eq!(frame.left > 0.0, (i / 20) % 2 == 0);
+	}
+	assert!(handle.pop_error().is_none());
+}
+
+#[test]
+fn streaming_loudness_matches_static_loudness() {
+	let mut streaming = StreamingSoundData::from_bytes(
+		create_wav(),
+		FormatHint::new().extension("wav"),
+		StreamingSoundSettings::new(),
+	)
+	.unwrap();
+	let static_sound = crate::load_from_bytes(create_wav(), None, LoadSettings::new()).unwrap();
+	assert_eq!(
+		streaming.measure_loudness().unwrap(),
+		Loudness::of_static_sound(&static_sound)
+	);
+}
+
+#[test]
+fn stream_from_reader() {
+	let data = StreamingSoundData::from_reader(
+		Cursor::new(create_wav()),
+		None,
+		StreamingSoundSettings::new().target_loudness(-16.0),
+	)
+	.unwrap();
+	assert!(data.loudness().is_some());
+	assert!((data.gain() - 1.0).abs() > 0.01);
+}
+
+#[test]
+fn load_from_bytes() {
+	let data = crate::load_from_bytes(
+		create_wav(),
+		FormatHint::new().mime_type("audio/wav"),
+		LoadSettings::new(),
+	)
+	.unwrap();
+	assert_eq!(data.sample_rate, SAMPLE_RATE);
+	assert_eq!(data.frames.len(), SAMPLE_RATE as usize);
+	assert_eq!(data.frames[0], Frame::from_mono(0.25));
+	assert_eq!(data.frames[20], Frame::from_mono(-0.25));
+}
+
+#[test]
+fn load_from_reader() {
+	let data = crate::load_from_reader(
+		Cursor::new(create_wav()),
+		None,
+		LoadSettings::new().sample_rate(16_000),
+	)
+	.unwrap();
+	assert_eq!(data.sample_rate, 16_000);
+	assert_eq!(data.frames.len(), 16_000);
+}
+
+#[test]
+fn load_in_background() {
+	let mut handle = crate::load_from_reader_in_background(
+		Cursor::new(create_wav()),
+		FormatHint::new().extension("wav"),
+		LoadSettings::new(),
+	);
+	let result = loop {
+		if let Some(result) = handle.try_take() {
+			break result;
+		}
+		std::thread::sleep(Duration::from_millis(1));
+	};
+	assert!(handle.finished());
+	assert_eq!(result.unwrap().frames.len(), SAMPLE_RATE as usize);
+	let progress = handle.progress();
+	assert_eq!(progress.decoded_frames, SAMPLE_RATE as u64);
+	assert_eq!(progress.fraction(), Some(1.0));
+}
+
+/// A reader that blocks until it's allowed to start reading.
+struct GatedReader {
+	cursor: Cursor<Vec<u8>>,
+	gate: Option<mpsc::Receiver<()>>,
+}
+
+impl Read for GatedReader {
+	fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+		if let Some(gate) = self.gate.take() {
+			gate.recv().ok();
+		}
+		self.cursor.read(buf)
+	}
+}
+
+impl Seek for GatedReader {
+	fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+		self.cursor.seek(pos)
+	}
+}
+
+#[test]
+fn cancel_loading() {
+	let (open_gate, gate) = mpsc::channel();
+	let mut handle = crate::load_from_reader_in_background(
+		GatedReader {
+			cursor: Cursor::new(create_wav()),
+			gate: Some(gate),
+		},
+		None,
+		LoadSettings::new(),
+	);
+	// the loader can't decode anything until the gate opens,
+	// so it's guaranteed to notice the cancellation
+	handle.cancel();
+	open_gate.send(()).unwrap();
+	let result = loop {
+		if let Some(result) = handle.try_take() {
+			break result;
+		}
+		std::thread::sleep(Duration::from_millis(1));
+	};
+	assert!(matches!(result, Err(Error::Cancelled)));
+}
+
+#[test]
+fn cancel_while_resampling_and_normalizing() {
+	for settings in [
+		LoadSettings::new().sample_rate(SAMPLE_RATE * 2),
+		LoadSettings::new().target_loudness(-16.0),
+	] {
+		// let the last packet finish decoding, then cancel
+		let mut calls_after_decoding = 0;
+		let result = crate::decode(
+			Box::new(Cursor::new(create_wav())),
+			None,
+			settings,
+			|progress| {
+				if progress.decoded_frames == SAMPLE_RATE as u64 {
+					calls_after_decoding += 1;
+				}
+				calls_after_decoding < 2
+			},
+		);
+		assert!(matches!(result, Err(Error::Cancelled)));
+	}
+}
+
+#[test]
+fn stream_in_reverse() {
+	let mut manager = create_manager();
+	let mut handle = manager
+		.play(
+			StreamingSoundData::from_bytes(
+				create_wav(),
+				FormatHint::new().extension("wav"),
+				StreamingSoundSettings::new()
+					.reverse(true)
+					.interpolation(Interpolation::Nearest),
+			)
+			.unwrap(),
+		)
+		.unwrap();
+	let backend = manager.backend_mut();
+	backend.on_start_processing();
+	// wait for the decoder to send the last frame of the file
+	let mut out = backend.process();
+	for _ in 0..1000 {
+		if out != Frame::ZERO {
+			break;
+		}
+		std::thread::sleep(Duration::from_millis(1));
+		out = backend.process();
+	}
+	// the square wave switches sign every 20 frames, and the
+	// file ends partway through a negative half cycle
+	assert!(out.left < 0.0);
+	for _ in 0..19 {
+		assert!(backend.process().left < 0.0);
+	}
+	assert!(backend.process().left > 0.0);
+	backend.on_start_processing();
+	let position = handle.position();
+	assert!(position < 1.0 && position > 0.99);
+	assert!(handle.pop_error().is_none());
+}
+
+#[test]
+fn stream_in_reverse_without_known_length() {
+	let mut manager = create_manager();
+	// reading the end of the file is slow, which shouldn't
+	// hold up playing the sound
+	let mut data = StreamingSoundData::from_reader(
+		FaultyReader::new(16_000, true),
+		None,
+		StreamingSoundSettings::new()
+			.reverse(true)
+			.interpolation(Interpolation::Nearest),
+	)
+	.unwrap();
+	data.num_frames = None;
+	let start_time = Instant::now();
+	let mut handle = manager.play(data).unwrap();
+	assert!(start_time.elapsed() < Duration::from_millis(50));
+	let backend = manager.backend_mut();
+	backend.on_start_processing();
+	let mut out = backend.process();
+	for _ in 0..1000 {
+		if out != Frame::ZERO {
+			break;
+		}
+		std::thread::sleep(Duration::from_millis(1));
+		out = backend.process();
+	}
+	// the file ends partway through a negative half cycle
+	assert!(out.left < 0.0);
+	for _ in 0..19 {
+		assert!(backend.process().left < 0.0);
+	}
+	assert!(backend.process().left > 0.0);
+	backend.on_start_processing();
+	let position = handle.position();
+	assert!(position < 1.0 && position > 0.99);
+	assert!(handle.pop_error().is_none());
+}
+
+#[test]
+fn decoder_pool_shares_threads() {
+	let pool = DecoderPool::new(1);
+	let mut manager = create_manager();
+	let mut handles = (0..2)
+		.map(|_| {
+			let data = StreamingSoundData::from_bytes(
+				create_wav(),
+				FormatHint::new().extension("wav"),
+				StreamingSoundSettings::new()
+					.decoder_pool(pool.clone())
+					.loop_behavior(LoopBehavior {
+						start_position: 0.0,
+					}),
+			)
+			.unwrap();
+			manager.play(data).unwrap()
+		})
+		.collect::<Vec<_>>();
+	assert_eq!(pool.num_threads(), 1);
+	assert_eq!(pool.num_streams(), 2);
+	let backend = manager.backend_mut();
+	backend.on_start_processing();
+	let mut out = backend.process();
+	for _ in 0..1000 {
+		if out != Frame::ZERO {
+			break;
+		}
+		std::thread::sleep(Duration::from_millis(1));
+		out = backend.process();
+	}
+	assert!(out != Frame::ZERO);
+	// once the sounds are stopped and dropped, the pool should
+	// stop decoding them
+	for handle in &mut handles {
+		handle.stop(Default::default()).unwrap();
+	}
+	// the sounds can't fade out while they're waiting for
+	// the decoder, so give it time to catch up
+	for _ in 0..1000 {
+		backend.on_start_processing();
+		if handles
+			.iter()
+			.all(|handle| handle.state() == PlaybackState::Stopped)
+		{
+			break;
+		}
+		for _ in 0..SAMPLE_RATE / 100 {
+			backend.process();
+		}
+		std::thread::sleep(Duration::from_millis(1));
+	}
+	backend.on_start_processing();
+	backend.collect_unused_resources();
+	for _ in 0..1000 {
+		if pool.num_streams() == 0 {
+			break;
+		}
+		std::thread::sleep(Duration::from_millis(1));
+	}
+	assert_eq!(pool.num_streams(), 0);
+}
+
+#[test]
+fn streams_back_off_when_they_make_no_progress() {
+	let pool = DecoderPool::new(1);
+	let mut manager = create_manager();
+	let reader = FaultyReader::failing(16_000, u32::MAX);
+	let failed_reads = reader.failed_reads();
+	let data = StreamingSoundData::from_reader(
+		reader,
+		None,
+		StreamingSoundSettings::new()
+			.decoder_pool(pool.clone())
+			.buffer_size(SAMPLE_RATE as usize)
+			.error_policy(ErrorPolicy::RetryIo {
+				max_retries: u32::MAX,
+			}),
+	)
+	.unwrap();
+	let _handle = manager.play(data).unwrap();
+	std::thread::sleep(Duration::from_millis(200));
+	// the reader keeps failing, so the stream should wait
+	// between attempts instead of retrying constantly
+	let num_failed_reads = failed_reads.lock().unwrap().len();
+	assert!(num_failed_reads > 0);
+	assert!(num_failed_reads < 50);
+}
+
+#[test]
+fn preloaded_sounds_start_instantly() {
+	let mut manager = create_manager();
+	let mut data = StreamingSoundData::from_bytes(
+		create_wav(),
+		FormatHint::new().extension("wav"),
+		StreamingSoundSettings::new()
+			.start_position(0.5)
+			.buffer_size(1024)
+			.interpolation(Interpolation::Nearest),
+	)
+	.unwrap();
+	data.preload(0.1).unwrap();
+	assert!((data.preloaded_duration() - 0.1).abs() < 1.0e-9);
+	manager.play(data).unwrap();
+	let backend = manager.backend_mut();
+	backend.on_start_processing();
+	// frame 4000 is the start of a positive half cycle
+	for _ in 0..20 {
+		assert!(backend.process().left > 0.0);
+	}
+	assert!(backend.process().left < 0.0);
+}
+
+#[test]
+fn stop_on_io_error() {
+	let (mut handle, frames_played) = play_to_end(
+		FaultyReader::new(16_000, false),
+		StreamingSoundSettings::new().error_policy(ErrorPolicy::Stop),
+	);
+	assert_eq!(handle.state(), PlaybackState::Stopped);
+	assert!(frames_played < SAMPLE_RATE as usize);
+	assert!(matches!(handle.pop_error(), Some(Error::SymphoniaError(_))));
+}
+
+#[test]
+fn retry_io_errors() {
+	let (mut handle, frames_played) = play_to_end(
+		FaultyReader::new(16_000, false),
+		StreamingSoundSettings::new().error_policy(ErrorPolicy::RetryIo { max_retries: 1 }),
+	);
+	assert_eq!(handle.state(), PlaybackState::Stopped);
+	assert_eq!(frames_played, SAMPLE_RATE as usize);
+	assert!(handle.pop_error().is_none());
+}
+
+#[test]
+fn skipped_packets_keep_their_place() {
+	let mut manager = create_manager();
+	let mut data = StreamingSoundData::from_bytes(
+		create_wav(),
+		FormatHint::new().extension("wav"),
+		StreamingSoundSettings::new().error_policy(ErrorPolicy::SkipBadPackets),
+	)
+	.unwrap();
+	data.decoder = Box::new(FaultyDecoder {
+		decoder: data.decoder,
+		packets_left: 1,
+	});
+	let mut handle = manager.play(data).unwrap();
+	let backend = manager.backend_mut();
+	let mut position = 0.0;
+	for _ in 0..1000 {
+		backend.on_start_processing();
+		if handle.state() == PlaybackState::Stopped {
+			break;
+		}
+		position = handle.position();
+		for _ in 0..100 {
+			backend.process();
+		}
+		std::thread::sleep(Duration::from_millis(1));
+	}
+	assert!(matches!(handle.pop_error(), Some(Error::SymphoniaError(_))));
+	// the skipped packet still takes up time, so the sound
+	// reaches the end of the file
+	assert!(position > 0.98, "{}", position);
+}
+
+#[test]
+fn wait_between_io_retries() {
+	let reader = FaultyReader::failing(16_000, 3);
+	let failed_reads = reader.failed_reads();
+	let (mut handle, frames_played) = play_to_end(
+		reader,
+		StreamingSoundSettings::new().error_policy(ErrorPolicy::RetryIo { max_retries: 3 }),
+	);
+	assert_eq!(handle.state(), PlaybackState::Stopped);
+	assert_eq!(frames_played, SAMPLE_RATE as usize);
+	assert!(handle.pop_error().is_none());
+	let failed_reads = failed_reads.lock().unwrap();
+	assert_eq!(failed_reads.len(), 3);
+	for (i, times) in failed_reads.windows(2).enumerate() {
+		let expected_delay = Duration::from_millis(10) * (i as u32 + 1);
+		assert!(times[1] - times[0] >= expected_delay);
+	}
+}
+
+#[test]
+fn report_underruns() {
+	let (handle, frames_played) = play_to_end(
+		FaultyReader::new(16_000, true),
+		StreamingSoundSettings::new(),
+	);
+	assert_eq!(frames_played, SAMPLE_RATE as usize);
+	assert!(handle.underrun_frames() > 0);
+}
+
+/// Creates a WAV file with cue points at 0.1 and 0.5 seconds.
+/// The first cue point has a label.
+fn create_wav_with_cue_points() -> Vec<u8> {
+	let mut wav = create_wav();
+	let mut cue_chunk = vec![];
+	cue_chunk.extend_from_slice(&2u32.to_le_bytes());
+	for (id, frame) in [(1u32, SAMPLE_RATE / 10), (7, SAMPLE_RATE / 2)] {
+		cue_chunk.extend_from_slice(&id.to_le_bytes());
+		cue_chunk.extend_from_slice(&frame.to_le_bytes());
+		cue_chunk.extend_from_slice(b"data");
+		cue_chunk.extend_from_slice(&0u32.to_le_bytes());
+		cue_chunk.extend_from_slice(&0u32.to_le_bytes());
+		cue_chunk.extend_from_slice(&frame.to_le_bytes());
+	}
+	wav.extend_from_slice(b"cue ");
+	wav.extend_from_slice(&(cue_chunk.len() as u32).to_le_bytes());
+	wav.extend_from_slice(&cue_chunk);
+	let mut list_chunk = b"adtl".to_vec();
+	list_chunk.extend_from_slice(b"labl");
+	list_chunk.extend_from_slice(&9u32.to_le_bytes());
+	list_chunk.extend_from_slice(&1u32.to_le_bytes());
+	list_chunk.extend_from_slice(b"drop\0\0");
+	wav.extend_from_slice(b"LIST");
+	wav.extend_from_slice(&(list_chunk.len() as u32).to_le_bytes());
+	wav.extend_from_slice(&list_chunk);
+	let riff_size = wav.len() as u32 - 8;
+	wav[4..8].copy_from_slice(&riff_size.to_le_bytes());
+	wav
+}
+
+#[test]
+fn read_wav_cue_points() {
+	let expected_markers = [Marker::new(1, 0.1).name("drop"), Marker::new(7, 0.5)];
+	let sound = crate::load_from_bytes(
+		create_wav_with_cue_points(),
+		FormatHint::new().extension("wav"),
+		LoadSettings::new(),
+	)
+	.unwrap();
+	assert_eq!(*sound.markers, expected_markers);
+	let data = StreamingSoundData::from_bytes(
+		create_wav_with_cue_points(),
+		FormatHint::new().extension("wav"),
+		StreamingSoundSettings::new(),
+	)
+	.unwrap();
+	assert_eq!(data.settings.markers, expected_markers);
+}
+
+#[test]
+fn streaming_sounds_report_markers() {
+	let mut manager = create_manager();
+	let data = StreamingSoundData::from_bytes(
+		create_wav_with_cue_points(),
+		FormatHint::new().extension("wav"),
+		StreamingSoundSettings::new()
+			.loop_behavior(LoopBehavior {
+				start_position: 0.0,
+			})
+			.with_marker(Marker::new(3, 0.25)),
+	)
+	.unwrap();
+	let handle = manager.play(data).unwrap();
+	let mut events = vec![];
+	let mut frames_played = 0;
+	// play a little more than one loop
+	for _ in 0..1000 {
+		let backend = manager.backend_mut();
+		backend.on_start_processing();
+		if frames_played >= SAMPLE_RATE + SAMPLE_RATE / 5 {
+			break;
+		}
+		for _ in 0..100 {
+			if backend.process() != Frame::ZERO {
+				frames_played += 1;
+			}
+		}
+		events.extend(
+			std::iter::from_fn(|| manager.pop_event()).filter(|event| {
+				matches!(event, Event::MarkerCrossed { .. } | Event::SoundLooped(..))
+			}),
+		);
+		std::thread::sleep(Duration::from_millis(1));
+	}
+	let id = handle.id().unwrap();
+	let marker = |marker| Event::MarkerCrossed { sound: id, marker };
+	// the loop is reported between the markers on either side of it
+	assert_eq!(
+		events,
+		[
+			marker(1),
+			marker(3),
+			marker(7),
+			Event::SoundLooped(id),
+			marker(1)
+		]
+	);
+}
@@ -1,11 +1,18 @@
 //! Precise timing for audio events.
 
 mod clocks;
+mod external;
 mod handle;
+mod musical;
 mod time;
 
+#[cfg(test)]
+mod test;
+
 pub use clocks::*;
+pub use external::ExternalClockSettings;
 pub use handle::*;
+pub use musical::*;
 pub use time::*;
 
 use std::sync::{
@@ -15,6 +22,7 @@ use std::sync::{
 
 use atomic_arena::Key;
 
+use self::external::ExternalTime;
 use crate::{
 	parameter::Parameters,
 	value::{CachedValue, Value},
@@ -65,7 +73,11 @@ impl ClockShared {
 pub struct Clock {
 	shared: Arc<ClockShared>,
 	ticking: bool,
-	interval: CachedValue,
+	/// The interval in seconds, or the tempo in beats
+	/// per minute for musical clocks.
+	speed: CachedValue,
+	musical_settings: Option<MusicalClockSettings>,
+	external_time: Option<ExternalTime>,
 	ticks: u64,
 	tick_timer: f64,
 }
@@ -75,7 +87,28 @@ impl Clock {
 		Self {
 			shared: Arc::new(ClockShared::new()),
 			ticking: false,
-			interval: CachedValue::new(0.0.., interval, 1.0),
+			speed: CachedValue::new(0.0.., interval, 1.0),
+			musical_settings: None,
+			external_time: None,
+			ticks: 0,
+			tick_timer: 1.0,
+		}
+	}
+
+	pub(crate) fn new_external(interval: Value, settings: ExternalClockSettings) -> Self {
+		Self {
+			external_time: Some(ExternalTime::new(settings)),
+			..Self::new(interval)
+		}
+	}
+
+	pub(crate) fn new_musical(tempo: Value, settings: MusicalClockSettings) -> Self {
+		Self {
+			shared: Arc::new(ClockShared::new()),
+			ticking: false,
+			speed: CachedValue::new(0.0.., tempo, 120.0),
+			musical_settings: Some(settings),
+			external_time: None,
 			ticks: 0,
 			tick_timer: 1.0,
 		}
@@ -95,8 +128,48 @@ impl Clock {
 		self.ticks
 	}
 
+	/// Returns the number of times the clock has ticked, including
+	/// the progress towards the next tick as a fraction.
+	pub fn fractional_position(&self) -> f64 {
+		self.ticks as f64 + (1.0 - self.tick_timer)
+	}
+
+	/// Returns the settings of the clock if it's a musical clock.
+	pub fn musical_settings(&self) -> Option<MusicalClockSettings> {
+		self.musical_settings
+	}
+
+	/// Returns the current position of the clock in bars and beats
+	/// if it's a musical clock.
+	pub fn musical_time(&self) -> Option<MusicalTime> {
+		self.musical_settings
+			.map(|settings| settings.musical_time(self.ticks))
+	}
+
 	pub(crate) fn set_interval(&mut self, interval: Value) {
-		self.interval.set(interval);
+		if self.musical_settings.is_none() {
+			self.speed.set(interval);
+		}
+	}
+
+	pub(crate) fn set_tempo(&mut self, tempo: Value) {
+		if self.musical_settings.is_some() {
+			self.speed.set(tempo);
+		}
+	}
+
+	pub(crate) fn set_external_time(&mut self, time: f64) {
+		if let Some(external_time) = &mut self.external_time {
+			external_time.set_time(time);
+		}
+	}
+
+	/// Returns the number of ticks per second.
+	fn tick_rate(&self) -> f64 {
+		match self.musical_settings {
+			Some(settings) => self.speed.get() / 60.0 * settings.ticks_per_beat as f64,
+			None => 1.0 / self.speed.get(),
+		}
 	}
 
 	pub(crate) fn start(&mut self) {
@@ -113,17 +186,42 @@ impl Clock {
 		self.pause();
 		self.ticks = 0;
 		self.shared.ticks.store(0, Ordering::SeqCst);
+		if let Some(external_time) = &mut self.external_time {
+			external_time.reset();
+		}
 	}
 
 	pub(crate) fn update(&mut self, dt: f64, parameters: &Parameters) {
-		self.interval.update(parameters);
+		self.speed.update(parameters);
 		if self.ticking {
-			self.tick_timer -= dt / self.interval.get();
-			while self.tick_timer <= 0.0 {
-				self.tick_timer += 1.0;
-				self.ticks += 1;
-				self.shared.ticks.fetch_add(1, Ordering::SeqCst);
+			let dt = match &mut self.external_time {
+				Some(external_time) => external_time.advance(dt),
+				None => dt,
+			};
+			let ticks = dt * self.tick_rate();
+			if ticks < 0.0 {
+				self.rewind(-ticks);
+				return;
+			}
+			self.tick_timer -= ticks;
+			if self.tick_timer <= 0.0 {
+				// the clock can move many ticks at once when an
+				// external timeline jumps ahead, so the elapsed ticks
+				// are counted all at once rather than one by one
+				let elapsed_ticks = (-self.tick_timer).floor() + 1.0;
+				self.tick_timer += elapsed_ticks;
+				self.ticks = self.ticks.saturating_add(elapsed_ticks as u64);
+				self.shared.ticks.store(self.ticks, Ordering::SeqCst);
 			}
 		}
 	}
+
+	/// Moves the clock backwards, which only happens when an
+	/// external timeline jumps back in time.
+	fn rewind(&mut self, ticks: f64) {
+		let position = (self.fractional_position() - ticks).max(0.0);
+		self.ticks = position as u64;
+		self.tick_timer = 1.0 - position.fract();
+		self.shared.ticks.store(self.ticks, Ordering::SeqCst);
+	}
 }
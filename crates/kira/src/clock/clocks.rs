@@ -3,7 +3,7 @@ use ringbuf::Producer;
 
 use crate::{
 	clock::{Clock, ClockId},
-	manager::command::ClockCommand,
+	manager::{command::ClockCommand, event::Event},
 };
 
 use super::Parameters;
@@ -62,6 +62,16 @@ impl Clocks {
 					clock.set_interval(interval);
 				}
 			}
+			ClockCommand::SetTempo(id, tempo) => {
+				if let Some(clock) = self.clocks.get_mut(id.0) {
+					clock.set_tempo(tempo);
+				}
+			}
+			ClockCommand::SetExternalTime(id, time) => {
+				if let Some(clock) = self.clocks.get_mut(id.0) {
+					clock.set_external_time(time);
+				}
+			}
 			ClockCommand::Start(id) => {
 				if let Some(clock) = self.clocks.get_mut(id.0) {
 					clock.start();
@@ -80,9 +90,25 @@ impl Clocks {
 		}
 	}
 
-	pub(crate) fn update(&mut self, dt: f64, parameters: &Parameters) {
-		for (_, clock) in &mut self.clocks {
+	pub(crate) fn update(
+		&mut self,
+		dt: f64,
+		parameters: &Parameters,
+		event_producer: &mut Producer<Event>,
+	) {
+		for (key, clock) in &mut self.clocks {
+			let previous_ticks = clock.ticks();
 			clock.update(dt, parameters);
+			// only the latest tick is reported, so a clock that jumps
+			// ahead can't flood the event queue
+			if clock.ticks() > previous_ticks {
+				event_producer
+					.push(Event::ClockTicked {
+						clock: ClockId(key),
+						ticks: clock.ticks(),
+					})
+					.ok();
+			}
 		}
 	}
 }
@@ -0,0 +1,92 @@
+/// Settings for a clock that follows an external timeline.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub struct ExternalClockSettings {
+	/// Roughly how long (in seconds) the clock takes to catch
+	/// up with the external timeline when they drift apart.
+	pub correction_time: f64,
+	/// How far (in seconds) the clock can drift from the external
+	/// timeline before it jumps to the external time instead of
+	/// gradually catching up.
+	pub max_drift: f64,
+}
+
+impl ExternalClockSettings {
+	/// Creates a new [`ExternalClockSettings`] with the default settings.
+	pub fn new() -> Self {
+		Self {
+			correction_time: 0.5,
+			max_drift: 0.25,
+		}
+	}
+
+	/// Sets roughly how long (in seconds) the clock takes to catch
+	/// up with the external timeline when they drift apart.
+	pub fn correction_time(self, correction_time: f64) -> Self {
+		Self {
+			correction_time,
+			..self
+		}
+	}
+
+	/// Sets how far (in seconds) the clock can drift from the external
+	/// timeline before it jumps to the external time.
+	pub fn max_drift(self, max_drift: f64) -> Self {
+		Self { max_drift, ..self }
+	}
+}
+
+impl Default for ExternalClockSettings {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+/// Keeps a clock in sync with timestamps reported by
+/// gameplay code.
+pub(crate) struct ExternalTime {
+	settings: ExternalClockSettings,
+	/// The position of the clock on the external timeline.
+	time: f64,
+	/// The most recently reported external time, extrapolated
+	/// to the current frame.
+	target_time: f64,
+}
+
+impl ExternalTime {
+	pub fn new(settings: ExternalClockSettings) -> Self {
+		Self {
+			settings,
+			time: 0.0,
+			target_time: 0.0,
+		}
+	}
+
+	pub fn set_time(&mut self, time: f64) {
+		self.target_time = time;
+	}
+
+	pub fn reset(&mut self) {
+		self.time = 0.0;
+		self.target_time = 0.0;
+	}
+
+	/// Returns how far the clock should move (in seconds)
+	/// during this frame.
+	///
+	/// Small amounts of drift are corrected by speeding up or
+	/// slowing down the clock, which never moves backwards in
+	/// this case. Larger differences are corrected immediately.
+	pub fn advance(&mut self, dt: f64) -> f64 {
+		self.target_time += dt;
+		let drift = self.target_time - (self.time + dt);
+		let step = if drift.abs() > self.settings.max_drift {
+			dt + drift
+		} else {
+			let correction = drift * (dt / self.settings.correction_time).min(1.0);
+			(dt + correction).max(0.0)
+		};
+		self.time += step;
+		step
+	}
+}
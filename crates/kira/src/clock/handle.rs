@@ -6,7 +6,7 @@ use crate::{
 	value::Value,
 };
 
-use super::{ClockId, ClockShared, ClockTime};
+use super::{ClockId, ClockShared, ClockTime, MusicalClockSettings, MusicalTime};
 
 /// Controls a [`Clock`](super::Clock).
 ///
@@ -15,6 +15,7 @@ use super::{ClockId, ClockShared, ClockTime};
 pub struct ClockHandle {
 	pub(crate) id: ClockId,
 	pub(crate) shared: Arc<ClockShared>,
+	pub(crate) musical_settings: Option<MusicalClockSettings>,
 	pub(crate) command_producer: CommandProducer,
 }
 
@@ -38,7 +39,21 @@ impl ClockHandle {
 		}
 	}
 
+	/// Returns the settings of the clock if it's a musical clock.
+	pub fn musical_settings(&self) -> Option<MusicalClockSettings> {
+		self.musical_settings
+	}
+
+	/// Returns the current position of the clock in bars and beats
+	/// if it's a musical clock.
+	pub fn musical_time(&self) -> Option<MusicalTime> {
+		self.musical_settings
+			.map(|settings| settings.musical_time(self.shared.ticks()))
+	}
+
 	/// Sets the duration of time between each tick (in seconds).
+	///
+	/// This has no effect on musical clocks.
 	pub fn set_interval(&mut self, interval: impl Into<Value>) -> Result<(), CommandError> {
 		self.command_producer
 			.push(Command::Clock(ClockCommand::SetInterval(
@@ -47,6 +62,28 @@ impl ClockHandle {
 			)))
 	}
 
+	/// Sets the tempo of the clock (in beats per minute).
+	///
+	/// This only has an effect on musical clocks.
+	pub fn set_tempo(&mut self, tempo: impl Into<Value>) -> Result<(), CommandError> {
+		self.command_producer
+			.push(Command::Clock(ClockCommand::SetTempo(
+				self.id,
+				tempo.into(),
+			)))
+	}
+
+	/// Reports the current time (in seconds) of the external
+	/// timeline the clock is following.
+	///
+	/// The clock will speed up or slow down to catch up with
+	/// the external time, or jump to it if the clock has drifted
+	/// too far. This only has an effect on external clocks.
+	pub fn set_external_time(&mut self, time: f64) -> Result<(), CommandError> {
+		self.command_producer
+			.push(Command::Clock(ClockCommand::SetExternalTime(self.id, time)))
+	}
+
 	/// Starts or resumes the clock.
 	pub fn start(&mut self) -> Result<(), CommandError> {
 		self.command_producer
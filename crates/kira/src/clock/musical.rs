@@ -0,0 +1,120 @@
+/// The number of beats in each bar and the note value
+/// of each beat.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimeSignature {
+	/// The number of beats in each bar (the top number
+	/// of the time signature).
+	pub beats_per_bar: u32,
+	/// The note value that counts as one beat (the bottom
+	/// number of the time signature).
+	///
+	/// This doesn't affect timing, since the tempo is always
+	/// measured in beats.
+	pub beat_unit: u32,
+}
+
+impl TimeSignature {
+	/// Creates a new [`TimeSignature`].
+	pub fn new(beats_per_bar: u32, beat_unit: u32) -> Self {
+		Self {
+			beats_per_bar,
+			beat_unit,
+		}
+	}
+}
+
+impl Default for TimeSignature {
+	fn default() -> Self {
+		Self::new(4, 4)
+	}
+}
+
+/// A position in a piece of music.
+///
+/// Bars and beats are counted from 1, like in a score, and
+/// ticks are counted from 0. The first tick of a musical clock
+/// is bar 1, beat 1, tick 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MusicalTime {
+	/// The bar, starting from 1.
+	pub bar: u64,
+	/// The beat within the bar, starting from 1.
+	pub beat: u32,
+	/// The tick within the beat, starting from 0.
+	pub tick: u32,
+}
+
+impl MusicalTime {
+	/// Creates a new [`MusicalTime`] at the start of the
+	/// given beat.
+	pub fn new(bar: u64, beat: u32) -> Self {
+		Self { bar, beat, tick: 0 }
+	}
+
+	/// Sets the tick within the beat.
+	pub fn tick(self, tick: u32) -> Self {
+		Self { tick, ..self }
+	}
+}
+
+/// Settings for a clock that measures time in bars and beats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct MusicalClockSettings {
+	/// The time signature of the music.
+	pub time_signature: TimeSignature,
+	/// The number of times the clock ticks per beat.
+	pub ticks_per_beat: u32,
+}
+
+impl MusicalClockSettings {
+	/// Creates a new [`MusicalClockSettings`] with the default settings.
+	pub fn new() -> Self {
+		Self {
+			time_signature: TimeSignature::default(),
+			ticks_per_beat: 4,
+		}
+	}
+
+	/// Sets the time signature of the music.
+	pub fn time_signature(self, time_signature: TimeSignature) -> Self {
+		Self {
+			time_signature,
+			..self
+		}
+	}
+
+	/// Sets the number of times the clock ticks per beat.
+	pub fn ticks_per_beat(self, ticks_per_beat: u32) -> Self {
+		Self {
+			ticks_per_beat,
+			..self
+		}
+	}
+
+	/// Converts a number of ticks into a position in bars and beats.
+	pub fn musical_time(&self, ticks: u64) -> MusicalTime {
+		let ticks_per_beat = self.ticks_per_beat.max(1) as u64;
+		let beats_per_bar = self.time_signature.beats_per_bar.max(1) as u64;
+		let beats = ticks / ticks_per_beat;
+		MusicalTime {
+			bar: beats / beats_per_bar + 1,
+			beat: (beats % beats_per_bar) as u32 + 1,
+			tick: (ticks % ticks_per_beat) as u32,
+		}
+	}
+
+	/// Converts a position in bars and beats into a number of ticks.
+	pub fn ticks(&self, time: MusicalTime) -> u64 {
+		let ticks_per_beat = self.ticks_per_beat.max(1) as u64;
+		let beats_per_bar = self.time_signature.beats_per_bar.max(1) as u64;
+		let beats = time.bar.saturating_sub(1) * beats_per_bar + time.beat.saturating_sub(1) as u64;
+		beats * ticks_per_beat + time.tick as u64
+	}
+}
+
+impl Default for MusicalClockSettings {
+	fn default() -> Self {
+		Self::new()
+	}
+}
@@ -0,0 +1,157 @@
+use crate::{
+	manager::{backend::MockBackend, event::Event, AudioManager},
+	test::create_manager,
+	tween::Tween,
+};
+
+use super::{ClockTime, ExternalClockSettings, MusicalClockSettings, MusicalTime, TimeSignature};
+
+#[test]
+fn musical_time_conversions() {
+	let settings = MusicalClockSettings::new()
+		.time_signature(TimeSignature::new(3, 4))
+		.ticks_per_beat(4);
+	assert_eq!(settings.ticks(MusicalTime::new(1, 1)), 0);
+	assert_eq!(settings.ticks(MusicalTime::new(5, 3)), 56);
+	assert_eq!(settings.ticks(MusicalTime::new(5, 3).tick(2)), 58);
+	for ticks in 0..100 {
+		assert_eq!(settings.ticks(settings.musical_time(ticks)), ticks);
+	}
+	assert_eq!(
+		settings.musical_time(58),
+		MusicalTime {
+			bar: 5,
+			beat: 3,
+			tick: 2
+		}
+	);
+}
+
+#[test]
+fn musical_clocks_count_bars_and_beats() {
+	let mut manager = create_manager(1);
+	let mut clock = manager
+		.add_musical_clock(
+			60.0,
+			MusicalClockSettings::new()
+				.time_signature(TimeSignature::new(3, 4))
+				.ticks_per_beat(2),
+		)
+		.unwrap();
+	clock.start().unwrap();
+	assert_eq!(clock.musical_time(), Some(MusicalTime::new(1, 1)));
+	let backend = manager.backend_mut();
+	backend.on_start_processing();
+	// at 60 BPM with 2 ticks per beat, the clock ticks twice per second
+	for _ in 0..4 {
+		backend.process();
+	}
+	assert_eq!(clock.musical_time(), Some(MusicalTime::new(2, 2)));
+	assert_eq!(
+		ClockTime::at_beat(&clock, 2, 2),
+		Some(ClockTime {
+			clock: clock.id(),
+			ticks: 8,
+		})
+	);
+}
+
+#[test]
+fn musical_clocks_follow_tempo_parameters() {
+	let mut manager = create_manager(1);
+	let mut tempo = manager.add_parameter(60.0).unwrap();
+	let mut clock = manager
+		.add_musical_clock(&tempo, MusicalClockSettings::new().ticks_per_beat(1))
+		.unwrap();
+	clock.start().unwrap();
+	let backend = manager.backend_mut();
+	backend.on_start_processing();
+	backend.process();
+	assert_eq!(clock.time().ticks, 1);
+	tempo
+		.set(
+			120.0,
+			Tween {
+				duration: std::time::Duration::ZERO,
+				..Default::default()
+			},
+		)
+		.unwrap();
+	let backend = manager.backend_mut();
+	backend.on_start_processing();
+	// the parameter is updated after the clocks, so the new tempo
+	// takes effect on the next frame
+	backend.process();
+	backend.process();
+	assert_eq!(clock.time().ticks, 4);
+}
+
+#[test]
+fn interval_clocks_have_no_musical_time() {
+	let mut manager = create_manager(1);
+	let clock = manager.add_clock(1.0).unwrap();
+	assert_eq!(clock.musical_time(), None);
+	assert_eq!(ClockTime::at_beat(&clock, 2, 1), None);
+}
+
+#[test]
+fn external_clocks_follow_external_time() {
+	// a power of two sample rate keeps the timing exact
+	let mut manager = create_manager(64);
+	let mut clock = manager
+		.add_external_clock(
+			0.25,
+			ExternalClockSettings::new()
+				.correction_time(0.25)
+				.max_drift(0.5),
+		)
+		.unwrap();
+	clock.start().unwrap();
+	let process = |manager: &mut AudioManager<MockBackend>, frames: usize| {
+		let backend = manager.backend_mut();
+		backend.on_start_processing();
+		for _ in 0..frames {
+			backend.process();
+		}
+	};
+	// without external timestamps, the clock runs on its own
+	process(&mut manager, 8);
+	assert_eq!(clock.time().ticks, 0);
+	// large differences make the clock jump
+	clock.set_external_time(2.0).unwrap();
+	process(&mut manager, 1);
+	assert_eq!(clock.time().ticks, 8);
+	// small differences are corrected gradually
+	clock.set_external_time(2.015625 + 0.375).unwrap();
+	process(&mut manager, 1);
+	assert_eq!(clock.time().ticks, 8);
+	process(&mut manager, 63);
+	assert_eq!(clock.time().ticks, 13);
+	// jumping back in time rewinds the clock
+	clock.set_external_time(0.5).unwrap();
+	process(&mut manager, 1);
+	assert_eq!(clock.time().ticks, 2);
+}
+
+#[test]
+fn external_clocks_jump_far_ahead_at_once() {
+	let mut manager = create_manager(1);
+	let mut clock = manager
+		.add_external_clock(1.0 / 60.0, ExternalClockSettings::new())
+		.unwrap();
+	clock.start().unwrap();
+	// a server timestamp can be billions of ticks away
+	clock.set_external_time(1.0e9).unwrap();
+	let backend = manager.backend_mut();
+	backend.on_start_processing();
+	backend.process();
+	assert_eq!(clock.time().ticks, 60_000_000_060);
+	let events = std::iter::from_fn(|| manager.pop_event()).collect::<Vec<_>>();
+	assert_eq!(
+		events,
+		[Event::ClockTicked {
+			clock: clock.id(),
+			ticks: 60_000_000_060,
+		}]
+	);
+}
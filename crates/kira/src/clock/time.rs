@@ -1,6 +1,6 @@
 use std::ops::{Add, AddAssign, Sub, SubAssign};
 
-use super::ClockId;
+use super::{ClockHandle, ClockId, MusicalTime};
 
 /// An instant in time associated with a [`Clock`](super::Clock).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -11,6 +11,27 @@ pub struct ClockTime {
 	pub ticks: u64,
 }
 
+impl ClockTime {
+	/// Returns the time when a musical clock reaches the start
+	/// of the given bar and beat (both counted from 1).
+	///
+	/// Returns `None` if the clock isn't a musical clock.
+	pub fn at_beat(clock: &ClockHandle, bar: u64, beat: u32) -> Option<Self> {
+		Self::at_musical_time(clock, MusicalTime::new(bar, beat))
+	}
+
+	/// Returns the time when a musical clock reaches the
+	/// given position.
+	///
+	/// Returns `None` if the clock isn't a musical clock.
+	pub fn at_musical_time(clock: &ClockHandle, time: MusicalTime) -> Option<Self> {
+		clock.musical_settings().map(|settings| Self {
+			clock: clock.id(),
+			ticks: settings.ticks(time),
+		})
+	}
+}
+
 impl Add<u64> for ClockTime {
 	type Output = ClockTime;
 
@@ -44,3 +65,12 @@ impl SubAssign<u64> for ClockTime {
 		self.ticks -= ticks;
 	}
 }
+
+/// A length of time measured in ticks of a [`Clock`](super::Clock).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClockDuration {
+	/// The [`Clock`](super::Clock) this duration is measured with.
+	pub clock: ClockId,
+	/// The length of time in ticks.
+	pub ticks: f64,
+}
@@ -1,8 +1,14 @@
 //! Tools for manipulating audio signals.
 
+mod fft;
 mod frame;
+mod interpolation;
+mod resample;
 
+pub(crate) use fft::fft;
 pub use frame::*;
+pub use interpolation::*;
+pub use resample::*;
 
 /// Given a previous frame, a current frame, the two next frames,
 /// and a position `x` from 0.0 to 1.0 between the current frame
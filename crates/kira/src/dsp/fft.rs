@@ -0,0 +1,43 @@
+use std::f32::consts::PI;
+
+/// Performs an in-place fast Fourier transform on a signal
+/// split into its real and imaginary parts.
+///
+/// The length of the signal must be a power of two.
+// This is the iterative radix-2 Cooley-Tukey algorithm.
+pub(crate) fn fft(real: &mut [f32], imag: &mut [f32]) {
+	let len = real.len();
+	debug_assert!(len.is_power_of_two());
+	debug_assert_eq!(len, imag.len());
+	if len < 2 {
+		return;
+	}
+	// reorder the samples by bit-reversed index
+	let bits = len.trailing_zeros();
+	for i in 0..len {
+		let j = i.reverse_bits() >> (usize::BITS - bits);
+		if j > i {
+			real.swap(i, j);
+			imag.swap(i, j);
+		}
+	}
+	let mut size = 2;
+	while size <= len {
+		let half_size = size / 2;
+		let angle = -2.0 * PI / size as f32;
+		for start in (0..len).step_by(size) {
+			for k in 0..half_size {
+				let (sin, cos) = (angle * k as f32).sin_cos();
+				let even = start + k;
+				let odd = even + half_size;
+				let odd_real = real[odd] * cos - imag[odd] * sin;
+				let odd_imag = real[odd] * sin + imag[odd] * cos;
+				real[odd] = real[even] - odd_real;
+				imag[odd] = imag[even] - odd_imag;
+				real[even] += odd_real;
+				imag[even] += odd_imag;
+			}
+		}
+		size *= 2;
+	}
+}
@@ -0,0 +1,97 @@
+use std::{f32::consts::PI, ops::Range};
+
+use super::{interpolate_frame, Frame};
+
+/// The number of frames on each side of the interpolated
+/// position used by [`interpolate_frame_sinc`].
+const SINC_RADIUS: usize = 4;
+
+/// How to estimate audio between two frames when a sound
+/// is played at a different rate than it was recorded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub enum Interpolation {
+	/// Uses the closest frame. This is the cheapest, but
+	/// produces harsh, "crunchy" audio, which can be desirable
+	/// for retro sounds.
+	Nearest,
+	/// Draws a straight line between the two closest frames.
+	Linear,
+	/// Fits a smooth curve through the four closest frames
+	/// (see [`interpolate_frame`]).
+	#[default]
+	Cubic,
+	/// Uses a windowed-sinc filter over the eight closest frames.
+	/// This is the most expensive, but has the least distortion.
+	Sinc,
+}
+
+impl Interpolation {
+	/// Returns the indices of the frames that [`Interpolation::interpolate`]
+	/// reads for this mode. The other frames can be left as
+	/// [`Frame::ZERO`], so they don't have to be fetched.
+	pub fn taps(self) -> Range<usize> {
+		match self {
+			Interpolation::Nearest | Interpolation::Linear => 3..5,
+			Interpolation::Cubic => 2..6,
+			Interpolation::Sinc => 0..8,
+		}
+	}
+
+	/// Given 8 consecutive frames, where `frames[3]` is the current
+	/// frame, and a position `fraction` from 0.0 to 1.0 between the
+	/// current frame and the next frame, get an approximated frame.
+	pub fn interpolate(self, frames: [Frame; 8], fraction: f32) -> Frame {
+		match self {
+			Interpolation::Nearest => interpolate_frame_nearest(frames[3], frames[4], fraction),
+			Interpolation::Linear => interpolate_frame_linear(frames[3], frames[4], fraction),
+			Interpolation::Cubic => {
+				interpolate_frame(frames[2], frames[3], frames[4], frames[5], fraction)
+			}
+			Interpolation::Sinc => interpolate_frame_sinc(frames, fraction),
+		}
+	}
+}
+
+/// Given a current frame, the next frame, and a position `fraction`
+/// from 0.0 to 1.0 between them, get whichever frame is closer.
+pub fn interpolate_frame_nearest(current: Frame, next: Frame, fraction: f32) -> Frame {
+	if fraction < 0.5 {
+		current
+	} else {
+		next
+	}
+}
+
+/// Given a current frame, the next frame, and a position `fraction`
+/// from 0.0 to 1.0 between them, get a frame on the straight line
+/// between them.
+pub fn interpolate_frame_linear(current: Frame, next: Frame, fraction: f32) -> Frame {
+	current + (next - current) * fraction
+}
+
+/// Given 8 consecutive frames, where `frames[3]` is the current
+/// frame, and a position `fraction` from 0.0 to 1.0 between the
+/// current frame and the next frame, get an approximated frame.
+// This uses a Lanczos kernel, which is a sinc function windowed
+// by a wider sinc function.
+pub fn interpolate_frame_sinc(frames: [Frame; 8], fraction: f32) -> Frame {
+	let mut out = Frame::ZERO;
+	let mut total_weight = 0.0;
+	for (i, frame) in frames.iter().enumerate() {
+		let x = i as f32 - (SINC_RADIUS - 1) as f32 - fraction;
+		let weight = sinc(x) * sinc(x / SINC_RADIUS as f32);
+		out += *frame * weight;
+		total_weight += weight;
+	}
+	// normalize the weights so constant signals stay constant
+	out / total_weight
+}
+
+fn sinc(x: f32) -> f32 {
+	if x == 0.0 {
+		1.0
+	} else {
+		(PI * x).sin() / (PI * x)
+	}
+}
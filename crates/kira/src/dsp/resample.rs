@@ -0,0 +1,66 @@
+use std::f64::consts::PI;
+
+use super::Frame;
+
+/// The number of zero crossings of the sinc function on each
+/// side of the center of the resampling filter. More zero
+/// crossings give a sharper cutoff at the cost of speed.
+const ZERO_CROSSINGS: usize = 16;
+
+/// Converts audio from one sample rate to another using a
+/// band-limited windowed-sinc filter.
+///
+/// When converting to a lower sample rate, frequencies above
+/// the new Nyquist frequency are removed to prevent aliasing.
+///
+/// This is relatively slow, so it's meant to be used ahead of
+/// time (like when loading a sound) rather than on the audio thread.
+pub fn resample(frames: &[Frame], from_sample_rate: u32, to_sample_rate: u32) -> Vec<Frame> {
+	if from_sample_rate == to_sample_rate || frames.is_empty() {
+		return frames.to_vec();
+	}
+	resampled_frames(frames, from_sample_rate, to_sample_rate).collect()
+}
+
+/// Like [`resample`], but returns an iterator that computes each
+/// frame as it's needed, so a long conversion can be done in
+/// pieces or stopped partway through.
+///
+/// Unlike [`resample`], the frames are filtered even if the
+/// sample rates are the same.
+pub fn resampled_frames(
+	frames: &[Frame],
+	from_sample_rate: u32,
+	to_sample_rate: u32,
+) -> impl ExactSizeIterator<Item = Frame> + '_ {
+	let step = from_sample_rate as f64 / to_sample_rate as f64;
+	// the cutoff frequency as a fraction of the input Nyquist frequency
+	let cutoff = (1.0 / step).min(1.0);
+	let half_width = ZERO_CROSSINGS as f64 / cutoff;
+	let output_len = (frames.len() as f64 / step).ceil() as usize;
+	(0..output_len).map(move |i| {
+		let position = i as f64 * step;
+		let first = (position - half_width).ceil().max(0.0) as usize;
+		let last = ((position + half_width).floor() as usize).min(frames.len() - 1);
+		let mut out = Frame::ZERO;
+		for (index, frame) in frames.iter().enumerate().take(last + 1).skip(first) {
+			let x = index as f64 - position;
+			out += *frame * (cutoff * sinc(cutoff * x) * blackman(x / half_width)) as f32;
+		}
+		out
+	})
+}
+
+fn sinc(x: f64) -> f64 {
+	if x == 0.0 {
+		1.0
+	} else {
+		(PI * x).sin() / (PI * x)
+	}
+}
+
+/// A Blackman window, where `x` ranges from -1.0 to 1.0.
+fn blackman(x: f64) -> f64 {
+	let t = (x + 1.0) * 0.5;
+	0.42 - 0.5 * (2.0 * PI * t).cos() + 0.08 * (4.0 * PI * t).cos()
+}
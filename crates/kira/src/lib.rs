@@ -30,13 +30,18 @@ pub mod dsp;
 mod error;
 mod loop_behavior;
 pub mod manager;
+pub mod modulator;
 pub mod parameter;
+pub mod recorder;
 pub mod sound;
 mod start_time;
 pub mod track;
 pub mod tween;
 pub mod value;
 
+#[cfg(test)]
+mod test;
+
 pub use error::*;
 pub use loop_behavior::*;
 pub use start_time::*;
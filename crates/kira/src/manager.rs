@@ -3,19 +3,28 @@
 pub mod backend;
 pub(crate) mod command;
 pub mod error;
+pub mod event;
+
+#[cfg(test)]
+mod test;
 
 use std::sync::Arc;
 
-use ringbuf::RingBuffer;
+use ringbuf::{Consumer, RingBuffer};
 
 use crate::{
-	clock::{Clock, ClockHandle, ClockId},
+	clock::{Clock, ClockHandle, ClockId, ExternalClockSettings, MusicalClockSettings},
 	error::CommandError,
+	modulator::{Modulator, ModulatorHandle, ModulatorId, ModulatorSettings},
 	parameter::{Parameter, ParameterHandle, ParameterId},
-	sound::SoundData,
-	track::{effect::Effect, SubTrackId, Track, TrackHandle, TrackId, TrackSettings},
+	recorder::{Recorder, RecorderHandle, RecorderId, RecorderSettings},
+	sound::{SoundData, SoundId},
+	track::{
+		effect::Effect, MeteringSettings, SubTrackId, Track, TrackHandle, TrackId, TrackSettings,
+		TrackShared,
+	},
 	tween::Tween,
-	value::Value,
+	value::{Expression, ExpressionHandle, ExpressionId, LiveExpression, Value},
 };
 
 use self::{
@@ -25,10 +34,14 @@ use self::{
 		Backend, Renderer,
 	},
 	command::{
-		producer::CommandProducer, ClockCommand, Command, MixerCommand, ParameterCommand,
-		SoundCommand,
+		producer::CommandProducer, ClockCommand, Command, ExpressionCommand, MixerCommand,
+		ModulatorCommand, ParameterCommand, RecorderCommand, SoundCommand,
 	},
-	error::{AddClockError, AddParameterError, AddSubTrackError, PlaySoundError},
+	error::{
+		AddClockError, AddExpressionError, AddModulatorError, AddParameterError, AddRecorderError,
+		AddSubTrackError, PlaySoundError,
+	},
+	event::Event,
 };
 
 /// The playback state for all audio.
@@ -67,12 +80,26 @@ pub struct AudioManagerSettings {
 	pub sound_capacity: usize,
 	/// The maximum number of parameters that can exist at a time.
 	pub parameter_capacity: usize,
+	/// The maximum number of modulators that can exist at a time.
+	pub modulator_capacity: usize,
+	/// The maximum number of expressions that can exist at a time.
+	pub expression_capacity: usize,
 	/// The maximum number of mixer sub-tracks that can exist at a time.
 	pub sub_track_capacity: usize,
 	/// The maximum number of clocks that can exist at a time.
 	pub clock_capacity: usize,
+	/// The maximum number of recorders that can exist at a time.
+	pub recorder_capacity: usize,
+	/// The number of events that can be waiting to be retrieved
+	/// at a time.
+	///
+	/// If the queue is full, new events are discarded.
+	pub event_capacity: usize,
 	/// Effects that should be added to the main mixer track.
 	pub main_track_effects: Vec<Box<dyn Effect>>,
+	/// Whether the levels of the main mixer track should be
+	/// measured, and if so, how.
+	pub main_track_metering: Option<MeteringSettings>,
 }
 
 impl AudioManagerSettings {
@@ -108,6 +135,22 @@ impl AudioManagerSettings {
 		}
 	}
 
+	/// Sets the maximum number of modulators that can exist at a time.
+	pub fn modulator_capacity(self, modulator_capacity: usize) -> Self {
+		Self {
+			modulator_capacity,
+			..self
+		}
+	}
+
+	/// Sets the maximum number of expressions that can exist at a time.
+	pub fn expression_capacity(self, expression_capacity: usize) -> Self {
+		Self {
+			expression_capacity,
+			..self
+		}
+	}
+
 	/// Sets the maximum number of mixer sub-tracks that can exist at a time.
 	pub fn sub_track_capacity(self, sub_track_capacity: usize) -> Self {
 		Self {
@@ -124,11 +167,41 @@ impl AudioManagerSettings {
 		}
 	}
 
+	/// Sets the maximum number of recorders that can exist at a time.
+	pub fn recorder_capacity(self, recorder_capacity: usize) -> Self {
+		Self {
+			recorder_capacity,
+			..self
+		}
+	}
+
+	/// Sets the number of events that can be waiting to be
+	/// retrieved at a time.
+	///
+	/// If the queue is full, new events are discarded.
+	pub fn event_capacity(self, event_capacity: usize) -> Self {
+		Self {
+			event_capacity,
+			..self
+		}
+	}
+
 	/// Specifies an effect to add to the main mixer track.
 	pub fn with_main_track_effect(mut self, effect: impl Effect + 'static) -> Self {
 		self.main_track_effects.push(Box::new(effect));
 		self
 	}
+
+	/// Enables measuring the levels of the main mixer track.
+	pub fn main_track_metering(
+		self,
+		main_track_metering: impl Into<Option<MeteringSettings>>,
+	) -> Self {
+		Self {
+			main_track_metering: main_track_metering.into(),
+			..self
+		}
+	}
 }
 
 impl Default for AudioManagerSettings {
@@ -137,9 +210,14 @@ impl Default for AudioManagerSettings {
 			command_capacity: 128,
 			sound_capacity: 128,
 			parameter_capacity: 128,
+			modulator_capacity: 16,
+			expression_capacity: 16,
 			sub_track_capacity: 128,
 			clock_capacity: 8,
+			recorder_capacity: 4,
+			event_capacity: 256,
 			main_track_effects: vec![],
+			main_track_metering: None,
 		}
 	}
 }
@@ -149,7 +227,9 @@ pub struct AudioManager<B: Backend> {
 	backend: B,
 	context: Arc<Context>,
 	command_producer: CommandProducer,
+	event_consumer: Consumer<Event>,
 	resource_controllers: ResourceControllers,
+	main_track_shared: Arc<TrackShared>,
 }
 
 impl<B: Backend> AudioManager<B> {
@@ -159,17 +239,21 @@ impl<B: Backend> AudioManager<B> {
 		let context = Arc::new(Context::new(sample_rate));
 		let (command_producer, command_consumer) =
 			RingBuffer::new(settings.command_capacity).split();
+		let (event_producer, event_consumer) = RingBuffer::new(settings.event_capacity).split();
 		let (unused_resource_producers, unused_resource_collector) =
 			create_unused_resource_channels(&settings);
 		let (resources, resource_controllers) =
 			create_resources(settings, unused_resource_producers, &context);
-		let renderer = Renderer::new(context.clone(), resources, command_consumer);
+		let main_track_shared = resources.mixer.main_track_shared();
+		let renderer = Renderer::new(context.clone(), resources, command_consumer, event_producer);
 		backend.init(renderer, unused_resource_collector)?;
 		Ok(Self {
 			backend,
 			context,
 			command_producer: CommandProducer::new(command_producer),
+			event_consumer,
 			resource_controllers,
+			main_track_shared,
 		})
 	}
 
@@ -184,7 +268,7 @@ impl<B: Backend> AudioManager<B> {
 			.try_reserve()
 			.map_err(|_| PlaySoundError::SoundLimitReached)?;
 		let (sound, handle) = sound_data
-			.into_sound()
+			.into_sound_with_id(SoundId(key))
 			.map_err(PlaySoundError::IntoSoundError)?;
 		self.command_producer
 			.push(Command::Sound(SoundCommand::Add(key, sound)))?;
@@ -210,6 +294,49 @@ impl<B: Backend> AudioManager<B> {
 		Ok(handle)
 	}
 
+	/// Creates a modulator, like an LFO or smoothed random noise.
+	pub fn add_modulator(
+		&mut self,
+		settings: impl Into<ModulatorSettings>,
+	) -> Result<ModulatorHandle, AddModulatorError> {
+		let id = ModulatorId(
+			self.resource_controllers
+				.modulator_controller
+				.try_reserve()
+				.map_err(|_| AddModulatorError::ModulatorLimitReached)?,
+		);
+		let modulator = Modulator::new(settings.into());
+		let handle = ModulatorHandle {
+			id,
+			shared: modulator.shared(),
+			command_producer: self.command_producer.clone(),
+		};
+		self.command_producer
+			.push(Command::Modulator(ModulatorCommand::Add(id, modulator)))?;
+		Ok(handle)
+	}
+
+	/// Creates an expression, which combines multiple values into one.
+	pub fn add_expression(
+		&mut self,
+		expression: Expression,
+	) -> Result<ExpressionHandle, AddExpressionError> {
+		let id = ExpressionId(
+			self.resource_controllers
+				.expression_controller
+				.try_reserve()
+				.map_err(|_| AddExpressionError::ExpressionLimitReached)?,
+		);
+		let expression = LiveExpression::new(expression);
+		let handle = ExpressionHandle {
+			id,
+			shared: expression.shared(),
+		};
+		self.command_producer
+			.push(Command::Expression(ExpressionCommand::Add(id, expression)))?;
+		Ok(handle)
+	}
+
 	/// Creates a mixer sub-track.
 	pub fn add_sub_track(
 		&mut self,
@@ -224,7 +351,7 @@ impl<B: Backend> AudioManager<B> {
 		let sub_track = Track::new(settings, &self.context);
 		let handle = TrackHandle {
 			id: TrackId::Sub(id),
-			shared: Some(sub_track.shared()),
+			shared: sub_track.shared(),
 			command_producer: self.command_producer.clone(),
 		};
 		self.command_producer
@@ -244,6 +371,7 @@ impl<B: Backend> AudioManager<B> {
 		let handle = ClockHandle {
 			id,
 			shared: clock.shared(),
+			musical_settings: None,
 			command_producer: self.command_producer.clone(),
 		};
 		self.command_producer
@@ -251,6 +379,87 @@ impl<B: Backend> AudioManager<B> {
 		Ok(handle)
 	}
 
+	/// Creates a clock that follows the timeline of an external
+	/// time source, like a video decoder or a game server.
+	///
+	/// The clock ticks once per `interval` seconds of the external
+	/// timeline. Use [`ClockHandle::set_external_time`] to report
+	/// the current time of the external timeline.
+	pub fn add_external_clock(
+		&mut self,
+		interval: impl Into<Value>,
+		settings: ExternalClockSettings,
+	) -> Result<ClockHandle, AddClockError> {
+		let id = ClockId(
+			self.resource_controllers
+				.clock_controller
+				.try_reserve()
+				.map_err(|_| AddClockError::ClockLimitReached)?,
+		);
+		let clock = Clock::new_external(interval.into(), settings);
+		let handle = ClockHandle {
+			id,
+			shared: clock.shared(),
+			musical_settings: None,
+			command_producer: self.command_producer.clone(),
+		};
+		self.command_producer
+			.push(Command::Clock(ClockCommand::Add(id, clock)))?;
+		Ok(handle)
+	}
+
+	/// Creates a clock that ticks at a tempo (in beats per minute)
+	/// and keeps track of bars and beats.
+	pub fn add_musical_clock(
+		&mut self,
+		tempo: impl Into<Value>,
+		settings: MusicalClockSettings,
+	) -> Result<ClockHandle, AddClockError> {
+		let id = ClockId(
+			self.resource_controllers
+				.clock_controller
+				.try_reserve()
+				.map_err(|_| AddClockError::ClockLimitReached)?,
+		);
+		let clock = Clock::new_musical(tempo.into(), settings);
+		let handle = ClockHandle {
+			id,
+			shared: clock.shared(),
+			musical_settings: Some(settings),
+			command_producer: self.command_producer.clone(),
+		};
+		self.command_producer
+			.push(Command::Clock(ClockCommand::Add(id, clock)))?;
+		Ok(handle)
+	}
+
+	/// Creates a recorder, which captures the output of a mixer track.
+	///
+	/// Recorders don't capture any audio until
+	/// [`RecorderHandle::start`] is called.
+	pub fn add_recorder(
+		&mut self,
+		settings: RecorderSettings,
+	) -> Result<RecorderHandle, AddRecorderError> {
+		let id = RecorderId(
+			self.resource_controllers
+				.recorder_controller
+				.try_reserve()
+				.map_err(|_| AddRecorderError::RecorderLimitReached)?,
+		);
+		let (frame_producer, frame_consumer) = RingBuffer::new(settings.buffer_capacity).split();
+		let recorder = Recorder::new(settings, frame_producer);
+		let handle = RecorderHandle {
+			id,
+			shared: recorder.shared(),
+			command_producer: self.command_producer.clone(),
+			frame_consumer,
+		};
+		self.command_producer
+			.push(Command::Recorder(RecorderCommand::Add(id, recorder)))?;
+		Ok(handle)
+	}
+
 	/// Fades out and pauses all audio.
 	pub fn pause(&mut self, fade_out_tween: Tween) -> Result<(), CommandError> {
 		self.command_producer.push(Command::Pause(fade_out_tween))
@@ -261,11 +470,21 @@ impl<B: Backend> AudioManager<B> {
 		self.command_producer.push(Command::Resume(fade_out_tween))
 	}
 
+	/// Removes and returns the oldest event from the audio thread,
+	/// or `None` if there are no events waiting to be read.
+	///
+	/// This should be called regularly (for example, once per
+	/// game frame) until it returns `None` so the event queue
+	/// doesn't fill up.
+	pub fn pop_event(&mut self) -> Option<Event> {
+		self.event_consumer.pop()
+	}
+
 	/// Returns a handle to the main mixer track.
 	pub fn main_track(&self) -> TrackHandle {
 		TrackHandle {
 			id: TrackId::Main,
-			shared: None,
+			shared: self.main_track_shared.clone(),
 			command_producer: self.command_producer.clone(),
 		}
 	}
@@ -275,6 +494,12 @@ impl<B: Backend> AudioManager<B> {
 		self.context.state()
 	}
 
+	/// Returns the sample rate the backend is outputting audio at
+	/// (in Hz).
+	pub fn sample_rate(&self) -> u32 {
+		self.context.sample_rate()
+	}
+
 	/// Returns the number of sounds that can be loaded at a time.
 	pub fn sound_capacity(&self) -> usize {
 		self.resource_controllers.sound_controller.capacity()
@@ -285,6 +510,16 @@ impl<B: Backend> AudioManager<B> {
 		self.resource_controllers.parameter_controller.capacity()
 	}
 
+	/// Returns the number of modulators that can exist at a time.
+	pub fn modulator_capacity(&self) -> usize {
+		self.resource_controllers.modulator_controller.capacity()
+	}
+
+	/// Returns the number of expressions that can exist at a time.
+	pub fn expression_capacity(&self) -> usize {
+		self.resource_controllers.expression_controller.capacity()
+	}
+
 	/// Returns the number of mixer sub-tracks that can exist at a time.
 	pub fn sub_track_capacity(&self) -> usize {
 		self.resource_controllers.sub_track_controller.capacity()
@@ -295,6 +530,11 @@ impl<B: Backend> AudioManager<B> {
 		self.resource_controllers.clock_controller.capacity()
 	}
 
+	/// Returns the number of recorders that can exist at a time.
+	pub fn recorder_capacity(&self) -> usize {
+		self.resource_controllers.recorder_controller.capacity()
+	}
+
 	/// Returns the number of sounds that are currently loaded.
 	pub fn num_sounds(&self) -> usize {
 		self.resource_controllers.sound_controller.len()
@@ -305,6 +545,16 @@ impl<B: Backend> AudioManager<B> {
 		self.resource_controllers.parameter_controller.len()
 	}
 
+	/// Returns the number of modulators that currently exist.
+	pub fn num_modulators(&self) -> usize {
+		self.resource_controllers.modulator_controller.len()
+	}
+
+	/// Returns the number of expressions that currently exist.
+	pub fn num_expressions(&self) -> usize {
+		self.resource_controllers.expression_controller.len()
+	}
+
 	/// Returns the number of mixer sub-tracks that currently exist.
 	pub fn num_sub_tracks(&self) -> usize {
 		self.resource_controllers.sub_track_controller.len()
@@ -315,6 +565,11 @@ impl<B: Backend> AudioManager<B> {
 		self.resource_controllers.clock_controller.len()
 	}
 
+	/// Returns the number of recorders that currently exist.
+	pub fn num_recorders(&self) -> usize {
+		self.resource_controllers.recorder_controller.len()
+	}
+
 	/// Returns a mutable reference to this manager's backend.
 	pub fn backend_mut(&mut self) -> &mut B {
 		&mut self.backend
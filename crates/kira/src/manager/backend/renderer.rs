@@ -2,11 +2,11 @@ pub(crate) mod context;
 
 use std::sync::{atomic::Ordering, Arc};
 
-use ringbuf::Consumer;
+use ringbuf::{Consumer, Producer};
 
 use crate::{
 	dsp::Frame,
-	manager::{command::Command, MainPlaybackState},
+	manager::{command::Command, event::Event, MainPlaybackState},
 	parameter::Parameter,
 };
 
@@ -23,6 +23,13 @@ pub struct Renderer {
 	context: Arc<Context>,
 	resources: Resources,
 	command_consumer: Consumer<Command>,
+	/// Sends events to the [`AudioManager`](crate::manager::AudioManager).
+	/// If the event queue is full, new events are discarded.
+	event_producer: Producer<Event>,
+	/// A command that has to wait until unused resources can be
+	/// sent off the audio thread. No other commands run until it
+	/// does, so commands always run in the order they were sent.
+	deferred_command: Option<Command>,
 	state: MainPlaybackState,
 	fade_volume: Parameter,
 }
@@ -32,11 +39,14 @@ impl Renderer {
 		context: Arc<Context>,
 		resources: Resources,
 		command_consumer: Consumer<Command>,
+		event_producer: Producer<Event>,
 	) -> Self {
 		Self {
 			context,
 			resources,
 			command_consumer,
+			event_producer,
+			deferred_command: None,
 			state: MainPlaybackState::Playing,
 			fade_volume: Parameter::new(1.0),
 		}
@@ -45,17 +55,37 @@ impl Renderer {
 	/// Called by the backend when it's time to process
 	/// a new batch of samples.
 	pub fn on_start_processing(&mut self) {
-		self.resources.sounds.on_start_processing();
+		self.resources
+			.sounds
+			.on_start_processing(&mut self.event_producer);
 		self.resources.parameters.on_start_processing();
 		self.resources.mixer.on_start_processing();
 		self.resources.clocks.on_start_processing();
+		self.resources.recorders.on_start_processing();
 
-		while let Some(command) = self.command_consumer.pop() {
+		while let Some(command) = self
+			.deferred_command
+			.take()
+			.or_else(|| self.command_consumer.pop())
+		{
 			match command {
 				Command::Sound(command) => self.resources.sounds.run_command(command),
-				Command::Parameter(command) => self.resources.parameters.run_command(command),
+				Command::Parameter(command) => {
+					if !self.resources.parameters.can_run_command(&command) {
+						self.deferred_command = Some(Command::Parameter(command));
+						break;
+					}
+					self.resources.parameters.run_command(command)
+				}
+				Command::Modulator(command) => {
+					self.resources.parameters.run_modulator_command(command)
+				}
+				Command::Expression(command) => {
+					self.resources.parameters.run_expression_command(command)
+				}
 				Command::Mixer(command) => self.resources.mixer.run_command(command),
 				Command::Clock(command) => self.resources.clocks.run_command(command),
+				Command::Recorder(command) => self.resources.recorders.run_command(command),
 				Command::Pause(fade_out_tween) => {
 					self.state = MainPlaybackState::Pausing;
 					self.context
@@ -82,6 +112,7 @@ impl Renderer {
 		{
 			if self.state == MainPlaybackState::Pausing {
 				self.state = MainPlaybackState::Paused;
+				self.event_producer.push(Event::MainPlaybackPaused).ok();
 			}
 		}
 
@@ -89,12 +120,17 @@ impl Renderer {
 			return Frame::ZERO;
 		}
 		if self.state == MainPlaybackState::Playing {
-			self.resources
-				.clocks
-				.update(self.context.dt, &self.resources.parameters);
-			self.resources
-				.parameters
-				.update(self.context.dt, &self.resources.clocks);
+			self.resources.clocks.update(
+				self.context.dt,
+				&self.resources.parameters,
+				&mut self.event_producer,
+			);
+			self.resources.parameters.update(
+				self.context.dt,
+				&self.resources.clocks,
+				&self.resources.mixer,
+				&mut self.event_producer,
+			);
 		}
 		self.resources.sounds.process(
 			self.context.dt,
@@ -106,6 +142,9 @@ impl Renderer {
 			.resources
 			.mixer
 			.process(self.context.dt, &self.resources.parameters);
+		self.resources
+			.recorders
+			.record(&self.resources.mixer, &self.resources.clocks);
 		out * self.fade_volume.value() as f32
 	}
 }
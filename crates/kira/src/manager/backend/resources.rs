@@ -9,9 +9,12 @@ use ringbuf::{Consumer, Producer, RingBuffer};
 use crate::{
 	clock::{Clock, Clocks},
 	manager::AudioManagerSettings,
-	parameter::{Parameter, Parameters},
+	modulator::{Modulator, Modulators},
+	parameter::{Automation, Parameter, Parameters},
+	recorder::{Recorder, Recorders},
 	sound::Sound,
 	track::Track,
+	value::{Expressions, LiveExpression},
 };
 
 use self::{mixer::Mixer, sounds::Sounds};
@@ -21,8 +24,12 @@ use super::context::Context;
 pub(crate) struct UnusedResourceProducers {
 	pub sound: Producer<Box<dyn Sound>>,
 	pub parameter: Producer<Parameter>,
+	pub automation: Producer<Automation>,
+	pub modulator: Producer<Modulator>,
+	pub expression: Producer<LiveExpression>,
 	pub sub_track: Producer<Track>,
 	pub clock: Producer<Clock>,
+	pub recorder: Producer<Recorder>,
 }
 
 /// Collects resources that have been discarded by
@@ -31,8 +38,12 @@ pub(crate) struct UnusedResourceProducers {
 pub struct UnusedResourceCollector {
 	unused_sound_consumer: Consumer<Box<dyn Sound>>,
 	unused_parameter_consumer: Consumer<Parameter>,
+	unused_automation_consumer: Consumer<Automation>,
+	unused_modulator_consumer: Consumer<Modulator>,
+	unused_expression_consumer: Consumer<LiveExpression>,
 	unused_sub_track_consumer: Consumer<Track>,
 	unused_clock_consumer: Consumer<Clock>,
+	unused_recorder_consumer: Consumer<Recorder>,
 }
 
 impl UnusedResourceCollector {
@@ -40,8 +51,12 @@ impl UnusedResourceCollector {
 	pub fn drain(&mut self) {
 		while self.unused_sound_consumer.pop().is_some() {}
 		while self.unused_parameter_consumer.pop().is_some() {}
+		while self.unused_automation_consumer.pop().is_some() {}
+		while self.unused_modulator_consumer.pop().is_some() {}
+		while self.unused_expression_consumer.pop().is_some() {}
 		while self.unused_sub_track_consumer.pop().is_some() {}
 		while self.unused_clock_consumer.pop().is_some() {}
+		while self.unused_recorder_consumer.pop().is_some() {}
 	}
 }
 
@@ -52,22 +67,38 @@ pub(crate) fn create_unused_resource_channels(
 		RingBuffer::new(settings.sound_capacity).split();
 	let (unused_parameter_producer, unused_parameter_consumer) =
 		RingBuffer::new(settings.parameter_capacity).split();
+	let (unused_automation_producer, unused_automation_consumer) =
+		RingBuffer::new(settings.parameter_capacity).split();
+	let (unused_modulator_producer, unused_modulator_consumer) =
+		RingBuffer::new(settings.modulator_capacity).split();
+	let (unused_expression_producer, unused_expression_consumer) =
+		RingBuffer::new(settings.expression_capacity).split();
 	let (unused_sub_track_producer, unused_sub_track_consumer) =
 		RingBuffer::new(settings.sub_track_capacity).split();
 	let (unused_clock_producer, unused_clock_consumer) =
 		RingBuffer::new(settings.clock_capacity).split();
+	let (unused_recorder_producer, unused_recorder_consumer) =
+		RingBuffer::new(settings.recorder_capacity).split();
 	(
 		UnusedResourceProducers {
 			sound: unused_sound_producer,
 			parameter: unused_parameter_producer,
+			automation: unused_automation_producer,
+			modulator: unused_modulator_producer,
+			expression: unused_expression_producer,
 			sub_track: unused_sub_track_producer,
 			clock: unused_clock_producer,
+			recorder: unused_recorder_producer,
 		},
 		UnusedResourceCollector {
 			unused_sound_consumer,
 			unused_parameter_consumer,
+			unused_automation_consumer,
+			unused_modulator_consumer,
+			unused_expression_consumer,
 			unused_sub_track_consumer,
 			unused_clock_consumer,
+			unused_recorder_consumer,
 		},
 	)
 }
@@ -77,13 +108,17 @@ pub(crate) struct Resources {
 	pub parameters: Parameters,
 	pub mixer: Mixer,
 	pub clocks: Clocks,
+	pub recorders: Recorders,
 }
 
 pub(crate) struct ResourceControllers {
 	pub sound_controller: Controller,
 	pub parameter_controller: Controller,
+	pub modulator_controller: Controller,
+	pub expression_controller: Controller,
 	pub sub_track_controller: Controller,
 	pub clock_controller: Controller,
+	pub recorder_controller: Controller,
 }
 
 pub(crate) fn create_resources(
@@ -96,29 +131,50 @@ pub(crate) fn create_resources(
 	let parameters = Parameters::new(
 		settings.parameter_capacity,
 		unused_resource_producers.parameter,
+		unused_resource_producers.automation,
+		Modulators::new(
+			settings.modulator_capacity,
+			unused_resource_producers.modulator,
+		),
+		Expressions::new(
+			settings.expression_capacity,
+			unused_resource_producers.expression,
+		),
 	);
 	let parameter_controller = parameters.controller();
+	let modulator_controller = parameters.modulator_controller();
+	let expression_controller = parameters.expression_controller();
 	let mixer = Mixer::new(
 		settings.sub_track_capacity,
 		unused_resource_producers.sub_track,
 		context,
 		settings.main_track_effects,
+		settings.main_track_metering,
 	);
 	let sub_track_controller = mixer.sub_track_controller();
 	let clocks = Clocks::new(settings.clock_capacity, unused_resource_producers.clock);
 	let clock_controller = clocks.controller();
+	let recorders = Recorders::new(
+		settings.recorder_capacity,
+		unused_resource_producers.recorder,
+	);
+	let recorder_controller = recorders.controller();
 	(
 		Resources {
 			sounds,
 			parameters,
 			mixer,
 			clocks,
+			recorders,
 		},
 		ResourceControllers {
 			sound_controller,
 			parameter_controller,
+			modulator_controller,
+			expression_controller,
 			sub_track_controller,
 			clock_controller,
+			recorder_controller,
 		},
 	)
 }
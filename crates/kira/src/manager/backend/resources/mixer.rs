@@ -6,7 +6,9 @@ use ringbuf::Producer;
 use crate::{
 	dsp::Frame,
 	manager::{backend::context::Context, command::MixerCommand},
-	track::{effect::Effect, SubTrackId, Track, TrackId, TrackSettings},
+	track::{
+		effect::Effect, MeteringSettings, SubTrackId, Track, TrackId, TrackSettings, TrackShared,
+	},
 	value::CachedValue,
 };
 
@@ -26,12 +28,14 @@ impl Mixer {
 		unused_sub_track_producer: Producer<Track>,
 		context: &Arc<Context>,
 		main_track_effects: Vec<Box<dyn Effect>>,
+		main_track_metering: Option<MeteringSettings>,
 	) -> Self {
 		Self {
 			main_track: Track::new(
 				{
 					let mut settings = TrackSettings::new();
 					settings.effects = main_track_effects;
+					settings.metering = main_track_metering;
 					settings
 				},
 				context,
@@ -43,10 +47,21 @@ impl Mixer {
 		}
 	}
 
+	pub fn main_track_shared(&self) -> Arc<TrackShared> {
+		self.main_track.shared()
+	}
+
 	pub fn sub_track_controller(&self) -> Controller {
 		self.sub_tracks.controller()
 	}
 
+	pub fn track(&self, id: TrackId) -> Option<&Track> {
+		match id {
+			TrackId::Main => Some(&self.main_track),
+			TrackId::Sub(id) => self.sub_tracks.get(id.0),
+		}
+	}
+
 	pub fn track_mut(&mut self, id: TrackId) -> Option<&mut Track> {
 		match id {
 			TrackId::Main => Some(&mut self.main_track),
@@ -76,6 +91,10 @@ impl Mixer {
 	}
 
 	pub fn on_start_processing(&mut self) {
+		self.main_track.on_start_processing();
+		for (_, track) in &mut self.sub_tracks {
+			track.on_start_processing();
+		}
 		let mut i = 0;
 		while i < self.sub_track_ids.len() && !self.unused_track_producer.is_full() {
 			let id = self.sub_track_ids[i];
@@ -1,7 +1,10 @@
 use atomic_arena::{Arena, Controller};
 use ringbuf::Producer;
 
-use crate::{manager::command::SoundCommand, sound::Sound};
+use crate::{
+	manager::{command::SoundCommand, event::Event},
+	sound::{Sound, SoundEvent, SoundId, SoundOutputs},
+};
 
 use super::{mixer::Mixer, Clocks, Parameters};
 
@@ -22,18 +25,28 @@ impl Sounds {
 		self.sounds.controller()
 	}
 
-	pub fn on_start_processing(&mut self) {
-		for (_, sound) in &mut self.sounds {
+	pub fn on_start_processing(&mut self, event_producer: &mut Producer<Event>) {
+		for (key, sound) in &mut self.sounds {
+			while let Some(event) = sound.pop_event() {
+				let id = SoundId(key);
+				let event = match event {
+					SoundEvent::Started => Event::SoundStarted(id),
+					SoundEvent::Looped => Event::SoundLooped(id),
+					SoundEvent::MarkerCrossed(marker) => Event::MarkerCrossed { sound: id, marker },
+				};
+				event_producer.push(event).ok();
+			}
 			sound.on_start_processing();
 		}
-		self.remove_unused_sounds();
+		self.remove_unused_sounds(event_producer);
 	}
 
-	fn remove_unused_sounds(&mut self) {
+	fn remove_unused_sounds(&mut self, event_producer: &mut Producer<Event>) {
 		if self.unused_sound_producer.is_full() {
 			return;
 		}
-		for (_, sound) in self.sounds.drain_filter(|sound| sound.finished()) {
+		for (key, sound) in self.sounds.drain_filter(|sound| sound.finished()) {
+			event_producer.push(Event::SoundStopped(SoundId(key))).ok();
 			if self.unused_sound_producer.push(sound).is_err() {
 				panic!("Unused sound producer is full")
 			}
@@ -59,10 +72,9 @@ impl Sounds {
 		clocks: &Clocks,
 		mixer: &mut Mixer,
 	) {
+		let mut outputs = SoundOutputs::new(mixer);
 		for (_, sound) in &mut self.sounds {
-			if let Some(track) = mixer.track_mut(sound.track()) {
-				track.add_input(sound.process(dt, parameters, clocks));
-			}
+			sound.process_to_tracks(dt, parameters, clocks, &mut outputs);
 		}
 	}
 }
@@ -4,11 +4,14 @@ use atomic_arena::Key;
 
 use crate::{
 	clock::{Clock, ClockId},
-	parameter::{Parameter, ParameterId},
+	modulator::{Modulator, ModulatorId},
+	parameter::{Automation, Parameter, ParameterId},
+	recorder::{Recorder, RecorderId},
 	sound::Sound,
 	track::{SubTrackId, Track, TrackId},
 	tween::Tween,
-	value::Value,
+	value::{ExpressionId, LiveExpression, Value},
+	StartTime,
 };
 
 pub(crate) enum SoundCommand {
@@ -22,10 +25,33 @@ pub(crate) enum ParameterCommand {
 		target: f64,
 		tween: Tween,
 	},
+	Automate {
+		id: ParameterId,
+		automation: Automation,
+	},
+	ClearAutomation(ParameterId),
 	Pause(ParameterId),
 	Resume(ParameterId),
 }
 
+pub(crate) enum ModulatorCommand {
+	Add(ModulatorId, Modulator),
+	SetRate {
+		id: ModulatorId,
+		target: f64,
+		tween: Tween,
+	},
+	SetDepth {
+		id: ModulatorId,
+		target: f64,
+		tween: Tween,
+	},
+}
+
+pub(crate) enum ExpressionCommand {
+	Add(ExpressionId, LiveExpression),
+}
+
 pub(crate) enum MixerCommand {
 	AddSubTrack(SubTrackId, Track),
 	SetTrackVolume(TrackId, Value),
@@ -35,16 +61,27 @@ pub(crate) enum MixerCommand {
 pub(crate) enum ClockCommand {
 	Add(ClockId, Clock),
 	SetInterval(ClockId, Value),
+	SetTempo(ClockId, Value),
+	SetExternalTime(ClockId, f64),
 	Start(ClockId),
 	Pause(ClockId),
 	Stop(ClockId),
 }
 
+pub(crate) enum RecorderCommand {
+	Add(RecorderId, Recorder),
+	Start(RecorderId, StartTime),
+	Stop(RecorderId, StartTime),
+}
+
 pub(crate) enum Command {
 	Sound(SoundCommand),
 	Parameter(ParameterCommand),
+	Modulator(ModulatorCommand),
+	Expression(ExpressionCommand),
 	Mixer(MixerCommand),
 	Clock(ClockCommand),
+	Recorder(RecorderCommand),
 	Pause(Tween),
 	Resume(Tween),
 }
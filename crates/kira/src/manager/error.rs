@@ -82,6 +82,78 @@ impl From<CommandError> for AddParameterError {
 	}
 }
 
+/// Errors that can occur when creating a modulator.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum AddModulatorError {
+	/// Could not add a modulator because the maximum number of modulators has been reached.
+	ModulatorLimitReached,
+	/// An error occured when sending a command to the audio thread.
+	CommandError(CommandError),
+}
+
+impl Display for AddModulatorError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			AddModulatorError::ModulatorLimitReached => f.write_str(
+				"Could not add a modulator because the maximum number of modulators has been reached.",
+			),
+			AddModulatorError::CommandError(error) => error.fmt(f),
+		}
+	}
+}
+
+impl Error for AddModulatorError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			AddModulatorError::CommandError(error) => Some(error),
+			_ => None,
+		}
+	}
+}
+
+impl From<CommandError> for AddModulatorError {
+	fn from(v: CommandError) -> Self {
+		Self::CommandError(v)
+	}
+}
+
+/// Errors that can occur when creating an expression.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum AddExpressionError {
+	/// Could not add an expression because the maximum number of expressions has been reached.
+	ExpressionLimitReached,
+	/// An error occured when sending a command to the audio thread.
+	CommandError(CommandError),
+}
+
+impl Display for AddExpressionError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			AddExpressionError::ExpressionLimitReached => f.write_str(
+				"Could not add an expression because the maximum number of expressions has been reached.",
+			),
+			AddExpressionError::CommandError(error) => error.fmt(f),
+		}
+	}
+}
+
+impl Error for AddExpressionError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			AddExpressionError::CommandError(error) => Some(error),
+			_ => None,
+		}
+	}
+}
+
+impl From<CommandError> for AddExpressionError {
+	fn from(v: CommandError) -> Self {
+		Self::CommandError(v)
+	}
+}
+
 /// Errors that can occur when creating a mixer sub-track.
 #[derive(Debug)]
 #[non_exhaustive]
@@ -151,3 +223,39 @@ impl From<CommandError> for AddClockError {
 		Self::CommandError(v)
 	}
 }
+
+/// Errors that can occur when creating a recorder.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum AddRecorderError {
+	/// Could not add a recorder because the maximum number of recorders has been reached.
+	RecorderLimitReached,
+	/// An error occured when sending a command to the audio thread.
+	CommandError(CommandError),
+}
+
+impl Display for AddRecorderError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			AddRecorderError::RecorderLimitReached => f.write_str(
+				"Could not add a recorder because the maximum number of recorders has been reached.",
+			),
+			AddRecorderError::CommandError(error) => error.fmt(f),
+		}
+	}
+}
+
+impl Error for AddRecorderError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			AddRecorderError::CommandError(error) => Some(error),
+			_ => None,
+		}
+	}
+}
+
+impl From<CommandError> for AddRecorderError {
+	fn from(v: CommandError) -> Self {
+		Self::CommandError(v)
+	}
+}
@@ -0,0 +1,41 @@
+//! Notifications sent from the audio thread to gameplay code.
+
+use crate::{clock::ClockId, parameter::ParameterId, sound::SoundId};
+
+/// Something that happened on the audio thread.
+///
+/// Events can be retrieved with [`AudioManager::pop_event`](super::AudioManager::pop_event).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Event {
+	/// A sound started producing audio.
+	SoundStarted(SoundId),
+	/// A sound reached its loop end and jumped back
+	/// to its loop start.
+	SoundLooped(SoundId),
+	/// A sound finished playing and was unloaded.
+	SoundStopped(SoundId),
+	/// The playback position of a sound crossed one of
+	/// its [`Marker`](crate::sound::Marker)s.
+	MarkerCrossed {
+		/// The sound that was playing.
+		sound: SoundId,
+		/// The ID of the marker.
+		marker: u32,
+	},
+	/// A parameter finished tweening to its target value.
+	TweenFinished(ParameterId),
+	/// A clock ticked.
+	///
+	/// If a clock ticks more than once during a single frame
+	/// (for example, when an external clock jumps ahead), only
+	/// the latest tick is reported.
+	ClockTicked {
+		/// The clock that ticked.
+		clock: ClockId,
+		/// The number of times the clock has ticked so far.
+		ticks: u64,
+	},
+	/// All audio finished fading out and was paused.
+	MainPlaybackPaused,
+}
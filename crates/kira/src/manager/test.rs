@@ -0,0 +1,122 @@
+use std::{sync::Arc, time::Duration};
+
+use crate::{
+	dsp::Frame,
+	sound::static_sound::{StaticSoundData, StaticSoundSettings},
+	test::create_manager,
+	tween::Tween,
+	LoopBehavior,
+};
+
+use super::{backend::MockBackend, event::Event, AudioManager};
+
+fn sound_data(num_frames: usize, settings: StaticSoundSettings) -> StaticSoundData {
+	StaticSoundData {
+		sample_rate: 1,
+		frames: Arc::new(vec![Frame::from_mono(1.0); num_frames]),
+		settings,
+		markers: Arc::new([]),
+	}
+}
+
+fn events(manager: &mut AudioManager<MockBackend>) -> Vec<Event> {
+	std::iter::from_fn(|| manager.pop_event()).collect()
+}
+
+#[test]
+fn reports_sound_events() {
+	let mut manager = create_manager(1);
+	let looping = manager
+		.play(sound_data(
+			2,
+			StaticSoundSettings::new().loop_behavior(LoopBehavior {
+				start_position: 0.0,
+			}),
+		))
+		.unwrap();
+	let one_shot = manager
+		.play(sound_data(2, StaticSoundSettings::new()))
+		.unwrap();
+	let backend = manager.backend_mut();
+	backend.on_start_processing();
+	for _ in 0..4 {
+		backend.process();
+	}
+	backend.on_start_processing();
+	let events = events(&mut manager);
+	// events from different sounds can be in any order
+	let events_for = |id| {
+		events
+			.iter()
+			.copied()
+			.filter(|event| match event {
+				Event::SoundStarted(event_id)
+				| Event::SoundLooped(event_id)
+				| Event::SoundStopped(event_id) => *event_id == id,
+				_ => false,
+			})
+			.collect::<Vec<_>>()
+	};
+	let looping = looping.id().unwrap();
+	let one_shot = one_shot.id().unwrap();
+	assert_eq!(events.len(), 4);
+	assert_eq!(
+		events_for(looping),
+		[Event::SoundStarted(looping), Event::SoundLooped(looping)]
+	);
+	assert_eq!(
+		events_for(one_shot),
+		[Event::SoundStarted(one_shot), Event::SoundStopped(one_shot)]
+	);
+}
+
+#[test]
+fn reports_clock_and_parameter_events() {
+	let mut manager = create_manager(1);
+	let mut clock = manager.add_clock(1.0).unwrap();
+	clock.start().unwrap();
+	let mut parameter = manager.add_parameter(0.0).unwrap();
+	parameter
+		.set(
+			1.0,
+			Tween {
+				duration: Duration::from_secs(2),
+				..Default::default()
+			},
+		)
+		.unwrap();
+	let backend = manager.backend_mut();
+	backend.on_start_processing();
+	for _ in 0..3 {
+		backend.process();
+	}
+	let events = events(&mut manager);
+	assert_eq!(
+		events,
+		[
+			Event::ClockTicked {
+				clock: clock.id(),
+				ticks: 1,
+			},
+			Event::ClockTicked {
+				clock: clock.id(),
+				ticks: 2,
+			},
+			Event::TweenFinished(parameter.id()),
+			Event::ClockTicked {
+				clock: clock.id(),
+				ticks: 3,
+			},
+		]
+	);
+}
+
+#[test]
+fn reports_main_playback_paused() {
+	let mut manager = create_manager(1);
+	manager.pause(Tween::default()).unwrap();
+	let backend = manager.backend_mut();
+	backend.on_start_processing();
+	backend.process();
+	assert_eq!(events(&mut manager), [Event::MainPlaybackPaused]);
+}
@@ -0,0 +1,197 @@
+//! Audio-thread sources of changing values, like LFOs, noise, and
+//! envelope followers.
+//!
+//! Modulators are created with
+//! [`AudioManager::add_modulator`](crate::manager::AudioManager::add_modulator)
+//! and can be used anywhere a [`Value`](crate::value::Value) is accepted.
+//! Unlike [parameters](crate::parameter), they don't need to be driven
+//! from gameplay code.
+
+mod envelope_follower;
+mod handle;
+mod lfo;
+mod modulators;
+mod noise;
+mod random;
+mod settings;
+
+pub use handle::*;
+pub use lfo::*;
+pub use settings::*;
+
+pub(crate) use modulators::Modulators;
+
+#[cfg(test)]
+mod test;
+
+use std::sync::{
+	atomic::{AtomicBool, AtomicU64, Ordering},
+	Arc,
+};
+
+use atomic_arena::Key;
+
+use crate::{
+	clock::{ClockId, Clocks},
+	manager::backend::resources::mixer::Mixer,
+	tween::{Tween, Tweenable},
+};
+
+use self::{envelope_follower::EnvelopeFollower, lfo::Lfo, noise::Noise};
+
+/// A unique identifier for a modulator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ModulatorId(pub(crate) Key);
+
+pub(crate) struct ModulatorShared {
+	value: AtomicU64,
+	removed: AtomicBool,
+}
+
+impl ModulatorShared {
+	pub fn new(value: f64) -> Self {
+		Self {
+			value: AtomicU64::new(value.to_bits()),
+			removed: AtomicBool::new(false),
+		}
+	}
+
+	pub fn value(&self) -> f64 {
+		f64::from_bits(self.value.load(Ordering::SeqCst))
+	}
+
+	pub fn is_marked_for_removal(&self) -> bool {
+		self.removed.load(Ordering::SeqCst)
+	}
+
+	pub fn mark_for_removal(&self) {
+		self.removed.store(true, Ordering::SeqCst);
+	}
+}
+
+enum ModulatorKind {
+	Lfo(Lfo),
+	Noise(Noise),
+	EnvelopeFollower(EnvelopeFollower),
+}
+
+pub(crate) struct Modulator {
+	kind: ModulatorKind,
+	rate: Tweenable,
+	depth: Tweenable,
+	offset: f64,
+	clock: Option<ClockId>,
+	last_clock_position: Option<f64>,
+	value: f64,
+	shared: Arc<ModulatorShared>,
+}
+
+impl Modulator {
+	pub fn new(settings: ModulatorSettings) -> Self {
+		let (kind, rate, depth, offset, clock) = match settings {
+			ModulatorSettings::Lfo(settings) => (
+				ModulatorKind::Lfo(Lfo::new(settings.waveform, settings.phase, settings.seed)),
+				settings.rate,
+				settings.depth,
+				settings.offset,
+				settings.clock,
+			),
+			ModulatorSettings::Noise(settings) => (
+				ModulatorKind::Noise(Noise::new(settings.seed)),
+				settings.rate,
+				settings.depth,
+				settings.offset,
+				settings.clock,
+			),
+			ModulatorSettings::EnvelopeFollower(settings) => (
+				ModulatorKind::EnvelopeFollower(EnvelopeFollower::new(
+					settings.track,
+					settings.attack,
+					settings.release,
+				)),
+				0.0,
+				settings.depth,
+				settings.offset,
+				None,
+			),
+		};
+		let mut modulator = Self {
+			kind,
+			rate: Tweenable::new(rate),
+			depth: Tweenable::new(depth),
+			offset,
+			clock,
+			last_clock_position: None,
+			value: offset,
+			shared: Arc::new(ModulatorShared::new(offset)),
+		};
+		modulator.value = modulator.calculate_value();
+		modulator
+			.shared
+			.value
+			.store(modulator.value.to_bits(), Ordering::SeqCst);
+		modulator
+	}
+
+	pub(crate) fn shared(&self) -> Arc<ModulatorShared> {
+		self.shared.clone()
+	}
+
+	pub fn value(&self) -> f64 {
+		self.value
+	}
+
+	pub fn set_rate(&mut self, target: f64, tween: Tween) {
+		self.rate.set(target, tween);
+	}
+
+	pub fn set_depth(&mut self, target: f64, tween: Tween) {
+		self.depth.set(target, tween);
+	}
+
+	pub(crate) fn on_start_processing(&self) {
+		self.shared
+			.value
+			.store(self.value.to_bits(), Ordering::SeqCst);
+	}
+
+	/// Returns how many cycles the modulator should advance by.
+	fn cycles_elapsed(&mut self, dt: f64, clocks: &Clocks) -> f64 {
+		let elapsed = match self.clock {
+			Some(id) => {
+				let clock_position = clocks.get(id).map(|clock| clock.fractional_position());
+				let elapsed = match (self.last_clock_position, clock_position) {
+					(Some(last), Some(current)) => (current - last).max(0.0),
+					_ => 0.0,
+				};
+				self.last_clock_position = clock_position;
+				elapsed
+			}
+			None => dt,
+		};
+		elapsed * self.rate.value()
+	}
+
+	fn calculate_value(&self) -> f64 {
+		let raw = match &self.kind {
+			ModulatorKind::Lfo(lfo) => lfo.value(),
+			ModulatorKind::Noise(noise) => noise.value(),
+			ModulatorKind::EnvelopeFollower(envelope_follower) => envelope_follower.value(),
+		};
+		self.offset + self.depth.value() * raw
+	}
+
+	pub fn update(&mut self, dt: f64, clocks: &Clocks, mixer: &Mixer) {
+		self.rate.update(dt, clocks);
+		self.depth.update(dt, clocks);
+		let cycles = self.cycles_elapsed(dt, clocks);
+		match &mut self.kind {
+			ModulatorKind::Lfo(lfo) => lfo.advance(cycles),
+			ModulatorKind::Noise(noise) => noise.advance(cycles),
+			ModulatorKind::EnvelopeFollower(envelope_follower) => {
+				envelope_follower.update(dt, mixer)
+			}
+		}
+		self.value = self.calculate_value();
+	}
+}
@@ -0,0 +1,41 @@
+use crate::{manager::backend::resources::mixer::Mixer, track::TrackId};
+
+pub(super) struct EnvelopeFollower {
+	track: TrackId,
+	attack: f64,
+	release: f64,
+	envelope: f64,
+}
+
+impl EnvelopeFollower {
+	pub fn new(track: TrackId, attack: f64, release: f64) -> Self {
+		Self {
+			track,
+			attack,
+			release,
+			envelope: 0.0,
+		}
+	}
+
+	/// Returns the current level of the track.
+	pub fn value(&self) -> f64 {
+		self.envelope
+	}
+
+	pub fn update(&mut self, dt: f64, mixer: &Mixer) {
+		let input = mixer
+			.track(self.track)
+			.map(|track| {
+				let output = track.output();
+				output.left.abs().max(output.right.abs()) as f64
+			})
+			.unwrap_or(0.0);
+		let time = if input > self.envelope {
+			self.attack
+		} else {
+			self.release
+		};
+		let coefficient = if time > 0.0 { (-dt / time).exp() } else { 0.0 };
+		self.envelope = input + (self.envelope - input) * coefficient;
+	}
+}
@@ -0,0 +1,67 @@
+use std::sync::Arc;
+
+use crate::{
+	error::CommandError,
+	manager::command::{producer::CommandProducer, Command, ModulatorCommand},
+	tween::Tween,
+};
+
+use super::{ModulatorId, ModulatorShared};
+
+/// Controls a modulator.
+///
+/// When a [`ModulatorHandle`] is dropped, the corresponding modulator
+/// will be removed.
+pub struct ModulatorHandle {
+	pub(crate) id: ModulatorId,
+	pub(crate) shared: Arc<ModulatorShared>,
+	pub(crate) command_producer: CommandProducer,
+}
+
+impl ModulatorHandle {
+	/// Returns the unique identifier for the modulator.
+	pub fn id(&self) -> ModulatorId {
+		self.id
+	}
+
+	/// Returns the current output value of the modulator.
+	pub fn value(&self) -> f64 {
+		self.shared.value()
+	}
+
+	/// Smoothly transitions the rate of the modulator to a new value
+	/// with the specified tween.
+	///
+	/// This has no effect on envelope followers.
+	pub fn set_rate(&mut self, rate: f64, tween: Tween) -> Result<(), CommandError> {
+		self.command_producer
+			.push(Command::Modulator(ModulatorCommand::SetRate {
+				id: self.id,
+				target: rate,
+				tween,
+			}))
+	}
+
+	/// Smoothly transitions the depth of the modulator to a new value
+	/// with the specified tween.
+	pub fn set_depth(&mut self, depth: f64, tween: Tween) -> Result<(), CommandError> {
+		self.command_producer
+			.push(Command::Modulator(ModulatorCommand::SetDepth {
+				id: self.id,
+				target: depth,
+				tween,
+			}))
+	}
+}
+
+impl Drop for ModulatorHandle {
+	fn drop(&mut self) {
+		self.shared.mark_for_removal();
+	}
+}
+
+impl From<&ModulatorHandle> for ModulatorId {
+	fn from(handle: &ModulatorHandle) -> Self {
+		handle.id()
+	}
+}
@@ -0,0 +1,71 @@
+use std::f64::consts::TAU;
+
+use super::random::Random;
+
+/// The shape of an LFO's oscillation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub enum Waveform {
+	/// A smooth oscillation.
+	#[default]
+	Sine,
+	/// Rises and falls linearly.
+	Triangle,
+	/// Alternates between the highest and lowest values.
+	Square,
+	/// Rises linearly and then jumps back to the lowest value.
+	Saw,
+	/// Holds a new random value for each cycle.
+	SampleAndHold,
+}
+
+pub(super) struct Lfo {
+	waveform: Waveform,
+	phase: f64,
+	random: Random,
+	held_value: f64,
+}
+
+impl Lfo {
+	pub fn new(waveform: Waveform, phase: f64, seed: u64) -> Self {
+		let mut random = Random::new(seed);
+		let held_value = random.next_bipolar();
+		Self {
+			waveform,
+			phase: phase.rem_euclid(1.0),
+			random,
+			held_value,
+		}
+	}
+
+	/// Returns the current value of the LFO (from -1.0 to 1.0).
+	pub fn value(&self) -> f64 {
+		match self.waveform {
+			Waveform::Sine => (self.phase * TAU).sin(),
+			Waveform::Triangle => 4.0 * ((self.phase + 0.75) % 1.0 - 0.5).abs() - 1.0,
+			Waveform::Square => {
+				if self.phase < 0.5 {
+					1.0
+				} else {
+					-1.0
+				}
+			}
+			Waveform::Saw => 2.0 * self.phase - 1.0,
+			Waveform::SampleAndHold => self.held_value,
+		}
+	}
+
+	pub fn advance(&mut self, cycles: f64) {
+		let phase = self.phase + cycles;
+		// the phase can move in either direction, since the rate
+		// can be negative
+		if !(0.0..1.0).contains(&phase) {
+			self.held_value = self.random.next_bipolar();
+		}
+		self.phase = phase.rem_euclid(1.0);
+		// rem_euclid can round up to 1.0 for tiny negative phases
+		if self.phase >= 1.0 {
+			self.phase = 0.0;
+		}
+	}
+}
@@ -0,0 +1,79 @@
+use atomic_arena::{Arena, Controller};
+use ringbuf::Producer;
+
+use crate::{
+	clock::Clocks,
+	manager::{backend::resources::mixer::Mixer, command::ModulatorCommand},
+	modulator::{Modulator, ModulatorId},
+};
+
+pub(crate) struct Modulators {
+	modulators: Arena<Modulator>,
+	unused_modulator_producer: Producer<Modulator>,
+}
+
+impl Modulators {
+	pub fn new(capacity: usize, unused_modulator_producer: Producer<Modulator>) -> Self {
+		Self {
+			modulators: Arena::new(capacity),
+			unused_modulator_producer,
+		}
+	}
+
+	pub fn controller(&self) -> Controller {
+		self.modulators.controller()
+	}
+
+	pub fn get(&self, id: ModulatorId) -> Option<&Modulator> {
+		self.modulators.get(id.0)
+	}
+
+	fn remove_unused_modulators(&mut self) {
+		if self.unused_modulator_producer.is_full() {
+			return;
+		}
+		for (_, modulator) in self
+			.modulators
+			.drain_filter(|modulator| modulator.shared().is_marked_for_removal())
+		{
+			if self.unused_modulator_producer.push(modulator).is_err() {
+				panic!("Unused modulator producer is full")
+			}
+			if self.unused_modulator_producer.is_full() {
+				return;
+			}
+		}
+	}
+
+	pub fn on_start_processing(&mut self) {
+		self.remove_unused_modulators();
+		for (_, modulator) in &self.modulators {
+			modulator.on_start_processing();
+		}
+	}
+
+	pub fn run_command(&mut self, command: ModulatorCommand) {
+		match command {
+			ModulatorCommand::Add(id, modulator) => self
+				.modulators
+				.insert_with_key(id.0, modulator)
+				.expect("Modulator arena is full"),
+			ModulatorCommand::SetRate { id, target, tween } => {
+				if let Some(modulator) = self.modulators.get_mut(id.0) {
+					modulator.set_rate(target, tween);
+				}
+			}
+			ModulatorCommand::SetDepth { id, target, tween } => {
+				if let Some(modulator) = self.modulators.get_mut(id.0) {
+					modulator.set_depth(target, tween);
+				}
+			}
+		}
+	}
+
+	pub fn update(&mut self, dt: f64, clocks: &Clocks, mixer: &Mixer) {
+		for (_, modulator) in &mut self.modulators {
+			modulator.update(dt, clocks, mixer);
+		}
+	}
+}
@@ -0,0 +1,46 @@
+use super::random::Random;
+
+pub(super) struct Noise {
+	random: Random,
+	previous: f64,
+	next: f64,
+	fraction: f64,
+}
+
+impl Noise {
+	pub fn new(seed: u64) -> Self {
+		let mut random = Random::new(seed);
+		let previous = random.next_bipolar();
+		let next = random.next_bipolar();
+		Self {
+			random,
+			previous,
+			next,
+			fraction: 0.0,
+		}
+	}
+
+	/// Returns the current value of the noise (from -1.0 to 1.0).
+	pub fn value(&self) -> f64 {
+		// smoothstep between random points so the noise
+		// doesn't have any sharp corners
+		let x = self.fraction * self.fraction * (3.0 - 2.0 * self.fraction);
+		self.previous + (self.next - self.previous) * x
+	}
+
+	pub fn advance(&mut self, cycles: f64) {
+		self.fraction += cycles;
+		while self.fraction >= 1.0 {
+			self.fraction -= 1.0;
+			self.previous = self.next;
+			self.next = self.random.next_bipolar();
+		}
+		// with a negative rate, step backwards through
+		// the random points
+		while self.fraction < 0.0 {
+			self.fraction += 1.0;
+			self.next = self.previous;
+			self.previous = self.random.next_bipolar();
+		}
+	}
+}
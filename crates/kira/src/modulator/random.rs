@@ -0,0 +1,24 @@
+/// A small, allocation-free pseudo-random number generator
+/// (xorshift64*) for use on the audio thread.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Random(u64);
+
+impl Random {
+	pub fn new(seed: u64) -> Self {
+		// xorshift gets stuck at 0, so make sure the state never is
+		Self(seed ^ 0x9E37_79B9_7F4A_7C15 | 1)
+	}
+
+	fn next_u64(&mut self) -> u64 {
+		self.0 ^= self.0 >> 12;
+		self.0 ^= self.0 << 25;
+		self.0 ^= self.0 >> 27;
+		self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+	}
+
+	/// Returns a random number from -1.0 to 1.0.
+	pub fn next_bipolar(&mut self) -> f64 {
+		let unipolar = (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+		unipolar * 2.0 - 1.0
+	}
+}
@@ -0,0 +1,246 @@
+use crate::{clock::ClockId, track::TrackId};
+
+use super::Waveform;
+
+/// Settings for a low-frequency oscillator.
+///
+/// The output of the LFO oscillates between
+/// `offset - depth` and `offset + depth`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub struct LfoSettings {
+	/// The shape of the oscillation.
+	pub waveform: Waveform,
+	/// The number of cycles per second, or per tick if
+	/// the LFO is synced to a clock.
+	pub rate: f64,
+	/// How far the output moves away from the offset.
+	pub depth: f64,
+	/// The center value of the output.
+	pub offset: f64,
+	/// The starting position in the cycle, from 0.0 to 1.0.
+	pub phase: f64,
+	/// The clock the LFO's rate is synced to, if any.
+	pub clock: Option<ClockId>,
+	/// The seed used for [`Waveform::SampleAndHold`].
+	pub seed: u64,
+}
+
+impl LfoSettings {
+	/// Creates a new [`LfoSettings`] with the default settings.
+	pub fn new() -> Self {
+		Self {
+			waveform: Waveform::Sine,
+			rate: 1.0,
+			depth: 1.0,
+			offset: 0.0,
+			phase: 0.0,
+			clock: None,
+			seed: 0,
+		}
+	}
+
+	/// Sets the shape of the oscillation.
+	pub fn waveform(self, waveform: Waveform) -> Self {
+		Self { waveform, ..self }
+	}
+
+	/// Sets the number of cycles per second, or per tick if
+	/// the LFO is synced to a clock.
+	pub fn rate(self, rate: f64) -> Self {
+		Self { rate, ..self }
+	}
+
+	/// Sets how far the output moves away from the offset.
+	pub fn depth(self, depth: f64) -> Self {
+		Self { depth, ..self }
+	}
+
+	/// Sets the center value of the output.
+	pub fn offset(self, offset: f64) -> Self {
+		Self { offset, ..self }
+	}
+
+	/// Sets the starting position in the cycle, from 0.0 to 1.0.
+	pub fn phase(self, phase: f64) -> Self {
+		Self { phase, ..self }
+	}
+
+	/// Syncs the LFO's rate to a clock, so the rate is measured
+	/// in cycles per tick instead of cycles per second.
+	pub fn clock(self, clock: impl Into<ClockId>) -> Self {
+		Self {
+			clock: Some(clock.into()),
+			..self
+		}
+	}
+
+	/// Sets the seed used for [`Waveform::SampleAndHold`].
+	pub fn seed(self, seed: u64) -> Self {
+		Self { seed, ..self }
+	}
+}
+
+impl Default for LfoSettings {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+/// Settings for smoothed random noise.
+///
+/// The output of the noise wanders between
+/// `offset - depth` and `offset + depth`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub struct NoiseSettings {
+	/// The number of new random points per second, or per tick
+	/// if the noise is synced to a clock.
+	pub rate: f64,
+	/// How far the output moves away from the offset.
+	pub depth: f64,
+	/// The center value of the output.
+	pub offset: f64,
+	/// The clock the noise's rate is synced to, if any.
+	pub clock: Option<ClockId>,
+	/// The seed for the random number generator.
+	pub seed: u64,
+}
+
+impl NoiseSettings {
+	/// Creates a new [`NoiseSettings`] with the default settings.
+	pub fn new() -> Self {
+		Self {
+			rate: 1.0,
+			depth: 1.0,
+			offset: 0.0,
+			clock: None,
+			seed: 0,
+		}
+	}
+
+	/// Sets the number of new random points per second, or per tick
+	/// if the noise is synced to a clock.
+	pub fn rate(self, rate: f64) -> Self {
+		Self { rate, ..self }
+	}
+
+	/// Sets how far the output moves away from the offset.
+	pub fn depth(self, depth: f64) -> Self {
+		Self { depth, ..self }
+	}
+
+	/// Sets the center value of the output.
+	pub fn offset(self, offset: f64) -> Self {
+		Self { offset, ..self }
+	}
+
+	/// Syncs the noise's rate to a clock, so the rate is measured
+	/// in points per tick instead of points per second.
+	pub fn clock(self, clock: impl Into<ClockId>) -> Self {
+		Self {
+			clock: Some(clock.into()),
+			..self
+		}
+	}
+
+	/// Sets the seed for the random number generator.
+	pub fn seed(self, seed: u64) -> Self {
+		Self { seed, ..self }
+	}
+}
+
+impl Default for NoiseSettings {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+/// Settings for an envelope follower, which measures the
+/// level of a mixer track's output.
+///
+/// The output of the envelope follower is
+/// `offset + depth * level`, where `level` is the peak
+/// amplitude of the track smoothed over time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub struct EnvelopeFollowerSettings {
+	/// The mixer track to measure.
+	pub track: TrackId,
+	/// How long it takes the envelope to rise towards
+	/// a louder level (in seconds).
+	pub attack: f64,
+	/// How long it takes the envelope to fall towards
+	/// a quieter level (in seconds).
+	pub release: f64,
+	/// The factor to multiply the level by.
+	pub depth: f64,
+	/// The value to add to the output.
+	pub offset: f64,
+}
+
+impl EnvelopeFollowerSettings {
+	/// Creates a new [`EnvelopeFollowerSettings`] that measures
+	/// the given track.
+	pub fn new(track: impl Into<TrackId>) -> Self {
+		Self {
+			track: track.into(),
+			attack: 0.01,
+			release: 0.1,
+			depth: 1.0,
+			offset: 0.0,
+		}
+	}
+
+	/// Sets how long it takes the envelope to rise towards
+	/// a louder level (in seconds).
+	pub fn attack(self, attack: f64) -> Self {
+		Self { attack, ..self }
+	}
+
+	/// Sets how long it takes the envelope to fall towards
+	/// a quieter level (in seconds).
+	pub fn release(self, release: f64) -> Self {
+		Self { release, ..self }
+	}
+
+	/// Sets the factor to multiply the level by.
+	pub fn depth(self, depth: f64) -> Self {
+		Self { depth, ..self }
+	}
+
+	/// Sets the value to add to the output.
+	pub fn offset(self, offset: f64) -> Self {
+		Self { offset, ..self }
+	}
+}
+
+/// Settings for a modulator.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub enum ModulatorSettings {
+	/// A low-frequency oscillator.
+	Lfo(LfoSettings),
+	/// Smoothed random noise.
+	Noise(NoiseSettings),
+	/// An envelope follower.
+	EnvelopeFollower(EnvelopeFollowerSettings),
+}
+
+impl From<LfoSettings> for ModulatorSettings {
+	fn from(settings: LfoSettings) -> Self {
+		Self::Lfo(settings)
+	}
+}
+
+impl From<NoiseSettings> for ModulatorSettings {
+	fn from(settings: NoiseSettings) -> Self {
+		Self::Noise(settings)
+	}
+}
+
+impl From<EnvelopeFollowerSettings> for ModulatorSettings {
+	fn from(settings: EnvelopeFollowerSettings) -> Self {
+		Self::EnvelopeFollower(settings)
+	}
+}
@@ -0,0 +1,122 @@
+use std::sync::Arc;
+
+use crate::{
+	dsp::Frame,
+	sound::static_sound::{StaticSoundData, StaticSoundSettings},
+	test::create_manager,
+	track::TrackId,
+	value::Value,
+};
+
+use super::{lfo::Lfo, noise::Noise, EnvelopeFollowerSettings, LfoSettings, Waveform};
+
+fn sample_lfo(waveform: Waveform) -> Vec<f64> {
+	let mut lfo = Lfo::new(waveform, 0.0, 0);
+	let mut values = vec![];
+	for _ in 0..4 {
+		values.push(lfo.value());
+		lfo.advance(0.25);
+	}
+	values
+}
+
+#[test]
+fn lfo_waveforms() {
+	let sine = sample_lfo(Waveform::Sine);
+	for (actual, expected) in sine.iter().zip([0.0, 1.0, 0.0, -1.0]) {
+		assert!((actual - expected).abs() < 1.0e-9);
+	}
+	assert_eq!(sample_lfo(Waveform::Triangle), vec![0.0, 1.0, 0.0, -1.0]);
+	assert_eq!(sample_lfo(Waveform::Square), vec![1.0, 1.0, -1.0, -1.0]);
+	assert_eq!(sample_lfo(Waveform::Saw), vec![-1.0, -0.5, 0.0, 0.5]);
+}
+
+#[test]
+fn sample_and_hold_changes_once_per_cycle() {
+	let mut lfo = Lfo::new(Waveform::SampleAndHold, 0.0, 1);
+	let first = lfo.value();
+	lfo.advance(0.5);
+	assert_eq!(lfo.value(), first);
+	lfo.advance(0.5);
+	assert_ne!(lfo.value(), first);
+}
+
+#[test]
+fn noise_stays_in_range() {
+	let mut noise = Noise::new(2);
+	for _ in 0..10_000 {
+		noise.advance(0.01);
+		assert!((-1.0..=1.0).contains(&noise.value()));
+	}
+}
+
+#[test]
+fn noise_with_a_negative_rate_stays_in_range() {
+	let mut noise = Noise::new(2);
+	for _ in 0..10_000 {
+		noise.advance(-0.03);
+		assert!((-1.0..=1.0).contains(&noise.value()));
+	}
+}
+
+#[test]
+fn envelope_follower_measures_track_level() {
+	let mut manager = create_manager(100);
+	let follower = manager
+		.add_modulator(
+			EnvelopeFollowerSettings::new(TrackId::Main)
+				.attack(0.0)
+				.release(0.0),
+		)
+		.unwrap();
+	manager
+		.play(StaticSoundData {
+			sample_rate: 100,
+			frames: Arc::new(vec![Frame::from_mono(0.5); 10]),
+			settings: StaticSoundSettings::new(),
+			markers: Arc::new([]),
+		})
+		.unwrap();
+	let backend = manager.backend_mut();
+	backend.on_start_processing();
+	for _ in 0..3 {
+		backend.process();
+	}
+	backend.on_start_processing();
+	assert!((follower.value() - 0.5).abs() < 1.0e-6);
+}
+
+#[test]
+fn lfo_phase_wraps_backwards() {
+	let mut lfo = Lfo::new(Waveform::Saw, 0.0, 0);
+	lfo.advance(-0.25);
+	assert_eq!(lfo.value(), 0.5);
+	lfo.advance(-2.5);
+	assert_eq!(lfo.value(), -0.5);
+}
+
+#[test]
+fn modulators_with_negative_rates_stay_in_range() {
+	let mut manager = create_manager(100);
+	let lfo = manager
+		.add_modulator(LfoSettings::new().waveform(Waveform::Saw).rate(-7.0))
+		.unwrap();
+	// the sound's volume is read from the modulator through a CachedValue
+	manager
+		.play(StaticSoundData {
+			sample_rate: 100,
+			frames: Arc::new(vec![Frame::from_mono(1.0); 100]),
+			settings: StaticSoundSettings::new().volume(Value::from(&lfo)),
+			markers: Arc::new([]),
+		})
+		.unwrap();
+	let max_amplitude = Frame::from_mono(1.0).panned(0.5).left;
+	let backend = manager.backend_mut();
+	backend.on_start_processing();
+	for _ in 0..50 {
+		let out = backend.process();
+		assert!(out.left.abs() <= max_amplitude + 1.0e-6);
+		backend.on_start_processing();
+		assert!((-1.0..=1.0).contains(&lfo.value()));
+	}
+}
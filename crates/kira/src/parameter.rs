@@ -1,8 +1,10 @@
 //! Tweenable values for controlling settings.
 
+mod automation;
 mod handle;
 mod parameters;
 
+pub use automation::*;
 pub use handle::*;
 pub use parameters::*;
 
@@ -12,6 +14,7 @@ use std::sync::{
 };
 
 use atomic_arena::Key;
+use ringbuf::Producer;
 
 use crate::{
 	clock::Clocks,
@@ -20,6 +23,9 @@ use crate::{
 
 type JustFinishedTween = bool;
 
+#[cfg(test)]
+mod test;
+
 /// A unique identifier for a parameter.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct ParameterId(pub(crate) Key);
@@ -56,9 +62,26 @@ impl ParameterShared {
 	}
 }
 
+struct AutomationState {
+	automation: Automation,
+	/// The keyframe in progress, or the number of keyframes
+	/// if the automation finished.
+	current_keyframe_index: usize,
+}
+
+impl AutomationState {
+	fn finished(&self) -> bool {
+		self.current_keyframe_index >= self.automation.keyframes.len()
+	}
+}
+
 pub(crate) struct Parameter {
 	tweenable: Tweenable,
 	paused: bool,
+	automation: Option<AutomationState>,
+	/// An automation that was replaced or cleared and is waiting
+	/// to be sent off the audio thread to be deallocated.
+	retired_automation: Option<Automation>,
 	shared: Arc<ParameterShared>,
 }
 
@@ -67,6 +90,8 @@ impl Parameter {
 		Self {
 			tweenable: Tweenable::new(initial_value),
 			paused: false,
+			automation: None,
+			retired_automation: None,
 			shared: Arc::new(ParameterShared::new(initial_value)),
 		}
 	}
@@ -89,10 +114,120 @@ impl Parameter {
 		self.shared.paused.store(false, Ordering::SeqCst);
 	}
 
+	/// Returns `true` if the current automation (if any) can be
+	/// replaced or cleared without deallocating anything.
+	///
+	/// Only one retired automation can wait to be sent off the
+	/// audio thread at a time, so commands that would retire
+	/// another one have to wait until there's room.
+	pub(crate) fn can_retire_automation(&self) -> bool {
+		self.automation.is_none() || self.retired_automation.is_none()
+	}
+
+	/// Sends automations the parameter is done with to the unused
+	/// resource collector. If the collector is full, they're held
+	/// onto until the next time this is called.
+	pub(crate) fn collect_retired_automation(
+		&mut self,
+		unused_automation_producer: &mut Producer<Automation>,
+	) {
+		self.send_retired_automation(unused_automation_producer);
+		// finished automations stay in place until there's room
+		// to retire them
+		if self.retired_automation.is_none()
+			&& self
+				.automation
+				.as_ref()
+				.is_some_and(AutomationState::finished)
+		{
+			self.retire_automation();
+			self.send_retired_automation(unused_automation_producer);
+		}
+	}
+
+	fn send_retired_automation(&mut self, unused_automation_producer: &mut Producer<Automation>) {
+		if let Some(automation) = self.retired_automation.take() {
+			if let Err(automation) = unused_automation_producer.push(automation) {
+				self.retired_automation = Some(automation);
+			}
+		}
+	}
+
+	/// Stops the current automation (if any) and holds onto it
+	/// until it can be deallocated on another thread.
+	///
+	/// This should only be called if [`can_retire_automation`](Self::can_retire_automation)
+	/// returns `true`, since the previously retired automation
+	/// would be deallocated otherwise.
+	fn retire_automation(&mut self) -> bool {
+		match self.automation.take() {
+			Some(AutomationState { automation, .. }) => {
+				debug_assert!(self.retired_automation.is_none());
+				self.retired_automation = Some(automation);
+				true
+			}
+			None => false,
+		}
+	}
+
 	pub fn set(&mut self, target: f64, tween: Tween) {
+		self.retire_automation();
 		self.tweenable.set(target, tween);
 	}
 
+	pub fn automate(&mut self, automation: Automation) {
+		self.retire_automation();
+		// an automation with no keyframes is finished right away,
+		// and it's retired like any other finished automation
+		self.automation = Some(AutomationState {
+			automation,
+			current_keyframe_index: 0,
+		});
+		self.start_current_keyframe();
+	}
+
+	pub fn clear_automation(&mut self) {
+		if self.retire_automation() {
+			// stop the keyframe that was in progress
+			self.tweenable = Tweenable::new(self.tweenable.value());
+		}
+	}
+
+	fn start_current_keyframe(&mut self) {
+		if let Some(AutomationState {
+			automation,
+			current_keyframe_index,
+		}) = &self.automation
+		{
+			let Some(keyframe) = automation.keyframes.get(*current_keyframe_index).copied() else {
+				return;
+			};
+			let target = keyframe.target.unwrap_or_else(|| self.tweenable.value());
+			self.tweenable.set(target, keyframe.tween);
+		}
+	}
+
+	/// Moves on to the next keyframe of the automation (if any).
+	///
+	/// Returns `false` if the automation finished.
+	fn advance_automation(&mut self) -> bool {
+		if let Some(state) = &mut self.automation {
+			if state.finished() {
+				return false;
+			}
+			state.current_keyframe_index += 1;
+			if state.finished() {
+				if !state.automation.looping {
+					return false;
+				}
+				state.current_keyframe_index = 0;
+			}
+			self.start_current_keyframe();
+			return true;
+		}
+		false
+	}
+
 	pub(crate) fn on_start_processing(&self) {
 		self.shared
 			.value
@@ -103,6 +238,29 @@ impl Parameter {
 		if self.paused {
 			return false;
 		}
-		self.tweenable.update(dt, clocks)
+		let mut leftover_time = match self.tweenable.update_with_leftover_time(dt, clocks) {
+			Some(leftover_time) => leftover_time,
+			None => return false,
+		};
+		// time left over after a keyframe finishes counts towards
+		// the next keyframe, so looping automations don't drift.
+		// a looping automation made of zero-length keyframes would
+		// never run out of time, so each keyframe gets at most one
+		// turn per update.
+		if let Some(state) = &self.automation {
+			for _ in 0..state.automation.keyframes.len() {
+				if !self.advance_automation() || leftover_time <= 0.0 {
+					break;
+				}
+				match self
+					.tweenable
+					.update_with_leftover_time(leftover_time, clocks)
+				{
+					Some(time) => leftover_time = time,
+					None => break,
+				}
+			}
+		}
+		true
 	}
 }
@@ -0,0 +1,75 @@
+use std::time::Duration;
+
+use crate::tween::Tween;
+
+/// One step of an [`Automation`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Keyframe {
+	/// The value to transition to, or `None` to hold the
+	/// current value for the duration of the tween.
+	pub target: Option<f64>,
+	/// The tween used to reach the target value.
+	pub tween: Tween,
+}
+
+/// A sequence of [`Keyframe`]s that a parameter moves through
+/// one after another.
+///
+/// Each keyframe starts when the previous one finishes. If a
+/// keyframe's tween has a [`StartTime::ClockTime`](crate::StartTime::ClockTime),
+/// the keyframe will wait for the clock to reach that time before
+/// it starts.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct Automation {
+	/// The steps of the automation.
+	pub keyframes: Vec<Keyframe>,
+	/// Whether the automation should start over from the first
+	/// keyframe after the last keyframe finishes.
+	pub looping: bool,
+}
+
+impl Automation {
+	/// Creates a new [`Automation`] with no keyframes.
+	pub fn new() -> Self {
+		Self {
+			keyframes: vec![],
+			looping: false,
+		}
+	}
+
+	/// Adds a keyframe that transitions to the target value with
+	/// the specified tween.
+	pub fn then(mut self, target: f64, tween: Tween) -> Self {
+		self.keyframes.push(Keyframe {
+			target: Some(target),
+			tween,
+		});
+		self
+	}
+
+	/// Adds a keyframe that holds the current value for the
+	/// specified duration.
+	pub fn hold(mut self, duration: Duration) -> Self {
+		self.keyframes.push(Keyframe {
+			target: None,
+			tween: Tween {
+				duration,
+				..Default::default()
+			},
+		});
+		self
+	}
+
+	/// Sets whether the automation should start over from the first
+	/// keyframe after the last keyframe finishes.
+	pub fn looping(self, looping: bool) -> Self {
+		Self { looping, ..self }
+	}
+}
+
+impl Default for Automation {
+	fn default() -> Self {
+		Self::new()
+	}
+}
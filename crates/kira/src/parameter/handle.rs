@@ -5,7 +5,7 @@ use crate::{
 	manager::command::{producer::CommandProducer, Command, ParameterCommand},
 };
 
-use super::{ParameterId, ParameterShared, Tween};
+use super::{Automation, ParameterId, ParameterShared, Tween};
 
 /// Controls a parameter.
 ///
@@ -48,6 +48,8 @@ impl ParameterHandle {
 
 	/// Smoothly transitions the parameter to a new value with the
 	/// specified tween.
+	///
+	/// This cancels any automation in progress.
 	pub fn set(&mut self, target: f64, tween: Tween) -> Result<(), CommandError> {
 		self.command_producer
 			.push(Command::Parameter(ParameterCommand::Set {
@@ -56,6 +58,61 @@ impl ParameterHandle {
 				tween,
 			}))
 	}
+
+	/// Moves the parameter through a sequence of keyframes.
+	///
+	/// This replaces any automation or tween in progress.
+	///
+	/// ## Example
+	/// ```
+	/// # use std::{error::Error, time::Duration};
+	/// #
+	/// # use kira::{
+	/// # 	manager::{backend::MockBackend, AudioManager},
+	/// # 	parameter::Automation,
+	/// # 	tween::{Easing, Tween},
+	/// # };
+	/// #
+	/// # let mut audio_manager = AudioManager::new(MockBackend::new(1), Default::default()).unwrap();
+	/// let mut parameter = audio_manager.add_parameter(0.0)?;
+	/// parameter.automate(
+	/// 	Automation::new()
+	/// 		.then(
+	/// 			0.8,
+	/// 			Tween {
+	/// 				duration: Duration::from_secs(2),
+	/// 				..Default::default()
+	/// 			},
+	/// 		)
+	/// 		.hold(Duration::from_secs(1))
+	/// 		.then(
+	/// 			0.0,
+	/// 			Tween {
+	/// 				duration: Duration::from_secs(4),
+	/// 				easing: Easing::OutPowi(2),
+	/// 				..Default::default()
+	/// 			},
+	/// 		),
+	/// )?;
+	/// #
+	/// # Result::<(), Box<dyn Error>>::Ok(())
+	/// ```
+	pub fn automate(&mut self, automation: Automation) -> Result<(), CommandError> {
+		self.command_producer
+			.push(Command::Parameter(ParameterCommand::Automate {
+				id: self.id,
+				automation,
+			}))
+	}
+
+	/// Stops any automation in progress, leaving the parameter
+	/// at its current value.
+	pub fn clear_automation(&mut self) -> Result<(), CommandError> {
+		self.command_producer
+			.push(Command::Parameter(ParameterCommand::ClearAutomation(
+				self.id,
+			)))
+	}
 }
 
 impl Drop for ParameterHandle {
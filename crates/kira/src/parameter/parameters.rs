@@ -3,24 +3,42 @@ use ringbuf::Producer;
 
 use crate::{
 	clock::Clocks,
-	manager::command::ParameterCommand,
-	parameter::{Parameter, ParameterId},
+	manager::{
+		backend::resources::mixer::Mixer,
+		command::{ExpressionCommand, ModulatorCommand, ParameterCommand},
+		event::Event,
+	},
+	modulator::{Modulator, ModulatorId, Modulators},
+	parameter::{Automation, Parameter, ParameterId},
+	value::{ExpressionId, Expressions, LiveExpression},
 };
 
-/// Contains the values of each parameter.
+/// Contains the values of each parameter, modulator, and expression.
 ///
 /// This is an opaque type that's only useful for passing to
 /// [`CachedValue::update`](crate::value::CachedValue::update).
 pub struct Parameters {
 	parameters: Arena<Parameter>,
 	unused_parameter_producer: Producer<Parameter>,
+	unused_automation_producer: Producer<Automation>,
+	modulators: Modulators,
+	expressions: Expressions,
 }
 
 impl Parameters {
-	pub(crate) fn new(capacity: usize, unused_parameter_producer: Producer<Parameter>) -> Self {
+	pub(crate) fn new(
+		capacity: usize,
+		unused_parameter_producer: Producer<Parameter>,
+		unused_automation_producer: Producer<Automation>,
+		modulators: Modulators,
+		expressions: Expressions,
+	) -> Self {
 		Self {
 			parameters: Arena::new(capacity),
 			unused_parameter_producer,
+			unused_automation_producer,
+			modulators,
+			expressions,
 		}
 	}
 
@@ -32,6 +50,22 @@ impl Parameters {
 		self.parameters.get(id.0)
 	}
 
+	pub(crate) fn modulator_controller(&self) -> Controller {
+		self.modulators.controller()
+	}
+
+	pub(crate) fn modulator(&self, id: ModulatorId) -> Option<&Modulator> {
+		self.modulators.get(id)
+	}
+
+	pub(crate) fn expression_controller(&self) -> Controller {
+		self.expressions.controller()
+	}
+
+	pub(crate) fn expression(&self, id: ExpressionId) -> Option<&LiveExpression> {
+		self.expressions.get(id)
+	}
+
 	fn remove_unused_parameters(&mut self) {
 		if self.unused_parameter_producer.is_full() {
 			return;
@@ -51,9 +85,28 @@ impl Parameters {
 
 	pub(crate) fn on_start_processing(&mut self) {
 		self.remove_unused_parameters();
-		for (_, parameter) in &self.parameters {
+		for (_, parameter) in &mut self.parameters {
 			parameter.on_start_processing();
+			// retry sending automations that didn't fit last time
+			parameter.collect_retired_automation(&mut self.unused_automation_producer);
+		}
+		self.modulators.on_start_processing();
+		self.expressions.on_start_processing();
+	}
+
+	/// Returns `false` if the command has to wait until the
+	/// parameter can let go of its current automation.
+	pub(crate) fn can_run_command(&mut self, command: &ParameterCommand) -> bool {
+		if let ParameterCommand::Set { id, .. }
+		| ParameterCommand::Automate { id, .. }
+		| ParameterCommand::ClearAutomation(id) = command
+		{
+			if let Some(parameter) = self.parameters.get_mut(id.0) {
+				parameter.collect_retired_automation(&mut self.unused_automation_producer);
+				return parameter.can_retire_automation();
+			}
 		}
+		true
 	}
 
 	pub(crate) fn run_command(&mut self, command: ParameterCommand) {
@@ -64,7 +117,20 @@ impl Parameters {
 				.expect("Parameter arena is full"),
 			ParameterCommand::Set { id, target, tween } => {
 				if let Some(parameter) = self.parameters.get_mut(id.0) {
-					parameter.set(target, tween)
+					parameter.set(target, tween);
+					parameter.collect_retired_automation(&mut self.unused_automation_producer);
+				}
+			}
+			ParameterCommand::Automate { id, automation } => {
+				if let Some(parameter) = self.parameters.get_mut(id.0) {
+					parameter.automate(automation);
+					parameter.collect_retired_automation(&mut self.unused_automation_producer);
+				}
+			}
+			ParameterCommand::ClearAutomation(id) => {
+				if let Some(parameter) = self.parameters.get_mut(id.0) {
+					parameter.clear_automation();
+					parameter.collect_retired_automation(&mut self.unused_automation_producer);
 				}
 			}
 			ParameterCommand::Pause(id) => {
@@ -80,9 +146,29 @@ impl Parameters {
 		}
 	}
 
-	pub(crate) fn update(&mut self, dt: f64, clocks: &Clocks) {
-		for (_, parameter) in &mut self.parameters {
-			parameter.update(dt, clocks);
+	pub(crate) fn run_modulator_command(&mut self, command: ModulatorCommand) {
+		self.modulators.run_command(command);
+	}
+
+	pub(crate) fn run_expression_command(&mut self, command: ExpressionCommand) {
+		self.expressions.run_command(command);
+	}
+
+	pub(crate) fn update(
+		&mut self,
+		dt: f64,
+		clocks: &Clocks,
+		mixer: &Mixer,
+		event_producer: &mut Producer<Event>,
+	) {
+		for (key, parameter) in &mut self.parameters {
+			if parameter.update(dt, clocks) {
+				event_producer
+					.push(Event::TweenFinished(ParameterId(key)))
+					.ok();
+				parameter.collect_retired_automation(&mut self.unused_automation_producer);
+			}
 		}
+		self.modulators.update(dt, clocks, mixer);
 	}
 }
@@ -0,0 +1,155 @@
+use std::time::Duration;
+
+use ringbuf::RingBuffer;
+
+use crate::{
+	clock::Clocks,
+	manager::{backend::MockBackend, AudioManager, AudioManagerSettings},
+	tween::Tween,
+};
+
+use super::{Automation, Parameter};
+
+fn tween(seconds: u64) -> Tween {
+	Tween {
+		duration: Duration::from_secs(seconds),
+		..Default::default()
+	}
+}
+
+fn tween_secs(seconds: f64) -> Tween {
+	Tween {
+		duration: Duration::from_secs_f64(seconds),
+		..Default::default()
+	}
+}
+
+#[test]
+fn automation() {
+	let (unused_clock_producer, _) = RingBuffer::new(1).split();
+	let clocks = Clocks::new(1, unused_clock_producer);
+	let mut parameter = Parameter::new(0.0);
+	parameter.automate(
+		Automation::new()
+			.then(1.0, tween(2))
+			.hold(Duration::from_secs(1))
+			.then(0.0, tween(1)),
+	);
+	let mut values = vec![];
+	for _ in 0..5 {
+		parameter.update(1.0, &clocks);
+		values.push(parameter.value());
+	}
+	assert_eq!(values, vec![0.5, 1.0, 1.0, 0.0, 0.0]);
+}
+
+#[test]
+fn looping_automation() {
+	let (unused_clock_producer, _) = RingBuffer::new(1).split();
+	let clocks = Clocks::new(1, unused_clock_producer);
+	let mut parameter = Parameter::new(0.0);
+	parameter.automate(
+		Automation::new()
+			.then(1.0, tween(1))
+			.then(0.0, tween(1))
+			.looping(true),
+	);
+	let mut values = vec![];
+	for _ in 0..4 {
+		parameter.update(1.0, &clocks);
+		values.push(parameter.value());
+	}
+	assert_eq!(values, vec![1.0, 0.0, 1.0, 0.0]);
+	parameter.clear_automation();
+	parameter.update(1.0, &clocks);
+	assert_eq!(parameter.value(), 0.0);
+}
+
+#[test]
+fn automation_carries_leftover_time_into_next_keyframe() {
+	let (unused_clock_producer, _) = RingBuffer::new(1).split();
+	let clocks = Clocks::new(1, unused_clock_producer);
+	let mut parameter = Parameter::new(0.0);
+	parameter.automate(
+		Automation::new()
+			.then(3.0, tween_secs(1.5))
+			.then(0.0, tween_secs(1.5))
+			.looping(true),
+	);
+	let mut values = vec![];
+	for _ in 0..6 {
+		parameter.update(1.0, &clocks);
+		values.push(parameter.value());
+	}
+	for (value, expected) in values.iter().zip([2.0, 2.0, 0.0, 2.0, 2.0, 0.0]) {
+		assert!((value - expected).abs() < 1.0e-9, "{:?}", values);
+	}
+}
+
+#[test]
+fn retires_replaced_and_finished_automations() {
+	let (unused_clock_producer, _) = RingBuffer::new(1).split();
+	let clocks = Clocks::new(1, unused_clock_producer);
+	let (mut unused_automation_producer, mut unused_automation_consumer) =
+		RingBuffer::new(1).split();
+	let mut parameter = Parameter::new(0.0);
+	parameter.automate(Automation::new().then(1.0, tween(1)));
+	parameter.collect_retired_automation(&mut unused_automation_producer);
+	assert!(unused_automation_consumer.pop().is_none());
+	parameter.set(0.0, tween(1));
+	parameter.collect_retired_automation(&mut unused_automation_producer);
+	assert!(unused_automation_consumer.pop().is_some());
+	parameter.automate(Automation::new().then(1.0, tween(1)));
+	parameter.update(1.0, &clocks);
+	parameter.collect_retired_automation(&mut unused_automation_producer);
+	assert!(unused_automation_consumer.pop().is_some());
+}
+
+#[test]
+fn holds_retired_automations_until_there_is_room() {
+	let (mut unused_automation_producer, mut unused_automation_consumer) =
+		RingBuffer::new(1).split();
+	unused_automation_producer.push(Automation::new()).unwrap();
+	let mut parameter = Parameter::new(0.0);
+	parameter.automate(Automation::new().then(1.0, tween(1)));
+	parameter.set(0.0, tween(1));
+	parameter.collect_retired_automation(&mut unused_automation_producer);
+	parameter.automate(Automation::new().then(1.0, tween(1)));
+	// the first automation is still waiting to be sent
+	assert!(!parameter.can_retire_automation());
+	unused_automation_consumer.pop().unwrap();
+	parameter.collect_retired_automation(&mut unused_automation_producer);
+	assert!(parameter.can_retire_automation());
+	assert!(unused_automation_consumer.pop().is_some());
+}
+
+#[test]
+fn replacing_an_automation_waits_for_room_to_retire_it() {
+	// the unused automation queue has room for one automation
+	let mut manager = AudioManager::new(
+		MockBackend::new(1),
+		AudioManagerSettings::new().parameter_capacity(1),
+	)
+	.unwrap();
+	let mut parameter = manager.add_parameter(0.0).unwrap();
+	let automation = || Automation::new().then(1.0, tween(10));
+	// the first automation fills the queue, and the second one
+	// waits in the parameter
+	for _ in 0..2 {
+		parameter.automate(automation()).unwrap();
+		parameter.clear_automation().unwrap();
+	}
+	parameter.automate(automation()).unwrap();
+	parameter.set(-1.0, Tween::default()).unwrap();
+	let backend = manager.backend_mut();
+	backend.on_start_processing();
+	backend.process();
+	backend.on_start_processing();
+	// the third automation keeps running until there's room for it
+	assert!(parameter.value() > 0.0);
+	backend.collect_unused_resources();
+	backend.on_start_processing();
+	backend.process();
+	backend.on_start_processing();
+	assert_eq!(parameter.value(), -1.0);
+}
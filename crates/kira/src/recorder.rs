@@ -0,0 +1,152 @@
+//! Captures the output of mixer tracks.
+//!
+//! Recorders are created with
+//! [`AudioManager::add_recorder`](crate::manager::AudioManager::add_recorder).
+//! While recording, every [`Frame`] a track outputs is sent to the
+//! [`RecorderHandle`], where it can be read from gameplay code (for
+//! example, to write it to a file or feed it to a video encoder).
+
+mod handle;
+mod recorders;
+mod settings;
+
+pub use handle::*;
+pub use settings::*;
+
+pub(crate) use recorders::Recorders;
+
+#[cfg(test)]
+mod test;
+
+use std::sync::{
+	atomic::{AtomicBool, AtomicU64, Ordering},
+	Arc,
+};
+
+use atomic_arena::Key;
+use ringbuf::Producer;
+
+use crate::{
+	clock::{ClockTime, Clocks},
+	dsp::Frame,
+	manager::backend::resources::mixer::Mixer,
+	track::TrackId,
+	StartTime,
+};
+
+/// A unique identifier for a recorder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RecorderId(pub(crate) Key);
+
+pub(crate) struct RecorderShared {
+	recording: AtomicBool,
+	dropped_frames: AtomicU64,
+	removed: AtomicBool,
+}
+
+impl RecorderShared {
+	pub fn new() -> Self {
+		Self {
+			recording: AtomicBool::new(false),
+			dropped_frames: AtomicU64::new(0),
+			removed: AtomicBool::new(false),
+		}
+	}
+
+	pub fn recording(&self) -> bool {
+		self.recording.load(Ordering::SeqCst)
+	}
+
+	pub fn dropped_frames(&self) -> u64 {
+		self.dropped_frames.load(Ordering::SeqCst)
+	}
+
+	pub fn is_marked_for_removal(&self) -> bool {
+		self.removed.load(Ordering::SeqCst)
+	}
+
+	pub fn mark_for_removal(&self) {
+		self.removed.store(true, Ordering::SeqCst);
+	}
+}
+
+fn start_time_reached(start_time: StartTime, clocks: &Clocks) -> bool {
+	match start_time {
+		StartTime::Immediate => true,
+		StartTime::ClockTime(ClockTime { clock, ticks }) => match clocks.get(clock) {
+			Some(clock) => clock.ticking() && clock.ticks() >= ticks,
+			None => false,
+		},
+	}
+}
+
+pub(crate) struct Recorder {
+	shared: Arc<RecorderShared>,
+	track: TrackId,
+	overflow_policy: OverflowPolicy,
+	frame_producer: Producer<Frame>,
+	recording: bool,
+	start_time: Option<StartTime>,
+	stop_time: Option<StartTime>,
+}
+
+impl Recorder {
+	pub fn new(settings: RecorderSettings, frame_producer: Producer<Frame>) -> Self {
+		Self {
+			shared: Arc::new(RecorderShared::new()),
+			track: settings.track,
+			overflow_policy: settings.overflow_policy,
+			frame_producer,
+			recording: false,
+			start_time: None,
+			stop_time: None,
+		}
+	}
+
+	pub fn shared(&self) -> Arc<RecorderShared> {
+		self.shared.clone()
+	}
+
+	pub fn start(&mut self, start_time: StartTime) {
+		self.start_time = Some(start_time);
+	}
+
+	pub fn stop(&mut self, stop_time: StartTime) {
+		self.stop_time = Some(stop_time);
+	}
+
+	pub fn on_start_processing(&self) {
+		self.shared
+			.recording
+			.store(self.recording, Ordering::SeqCst);
+	}
+
+	pub fn record(&mut self, mixer: &Mixer, clocks: &Clocks) {
+		if let Some(start_time) = self.start_time {
+			if start_time_reached(start_time, clocks) {
+				self.start_time = None;
+				self.recording = true;
+			}
+		}
+		if let Some(stop_time) = self.stop_time {
+			if start_time_reached(stop_time, clocks) {
+				self.stop_time = None;
+				self.recording = false;
+			}
+		}
+		if !self.recording {
+			return;
+		}
+		let frame = match mixer.track(self.track) {
+			Some(track) => track.output(),
+			None => return,
+		};
+		// never wait for the gameplay thread to make room
+		if self.frame_producer.push(frame).is_err() {
+			self.shared.dropped_frames.fetch_add(1, Ordering::SeqCst);
+			if let OverflowPolicy::StopRecording = self.overflow_policy {
+				self.recording = false;
+			}
+		}
+	}
+}
@@ -0,0 +1,84 @@
+use std::sync::Arc;
+
+use ringbuf::Consumer;
+
+use crate::{
+	dsp::Frame,
+	error::CommandError,
+	manager::command::{producer::CommandProducer, Command, RecorderCommand},
+	StartTime,
+};
+
+use super::{RecorderId, RecorderShared};
+
+/// Controls a recorder and reads the audio it captures.
+///
+/// When a [`RecorderHandle`] is dropped, the corresponding recorder
+/// will be removed.
+pub struct RecorderHandle {
+	pub(crate) id: RecorderId,
+	pub(crate) shared: Arc<RecorderShared>,
+	pub(crate) command_producer: CommandProducer,
+	pub(crate) frame_consumer: Consumer<Frame>,
+}
+
+impl RecorderHandle {
+	/// Returns the unique identifier for the recorder.
+	pub fn id(&self) -> RecorderId {
+		self.id
+	}
+
+	/// Returns `true` if the recorder is currently capturing audio.
+	pub fn recording(&self) -> bool {
+		self.shared.recording()
+	}
+
+	/// Returns the total number of frames that were discarded
+	/// because the recorder's buffer was full.
+	pub fn dropped_frames(&self) -> u64 {
+		self.shared.dropped_frames()
+	}
+
+	/// Starts capturing audio at the given time.
+	pub fn start(&mut self, start_time: impl Into<StartTime>) -> Result<(), CommandError> {
+		self.command_producer
+			.push(Command::Recorder(RecorderCommand::Start(
+				self.id,
+				start_time.into(),
+			)))
+	}
+
+	/// Stops capturing audio at the given time.
+	pub fn stop(&mut self, stop_time: impl Into<StartTime>) -> Result<(), CommandError> {
+		self.command_producer
+			.push(Command::Recorder(RecorderCommand::Stop(
+				self.id,
+				stop_time.into(),
+			)))
+	}
+
+	/// Returns the number of captured frames that are waiting
+	/// to be read.
+	pub fn available_frames(&self) -> usize {
+		self.frame_consumer.len()
+	}
+
+	/// Removes and returns the oldest captured frame, or `None`
+	/// if there are no frames waiting to be read.
+	pub fn pop(&mut self) -> Option<Frame> {
+		self.frame_consumer.pop()
+	}
+
+	/// Moves as many captured frames as will fit into `frames`,
+	/// oldest first, and returns the number of frames that were
+	/// written.
+	pub fn pop_frames(&mut self, frames: &mut [Frame]) -> usize {
+		self.frame_consumer.pop_slice(frames)
+	}
+}
+
+impl Drop for RecorderHandle {
+	fn drop(&mut self) {
+		self.shared.mark_for_removal();
+	}
+}
@@ -0,0 +1,75 @@
+use atomic_arena::{Arena, Controller};
+use ringbuf::Producer;
+
+use crate::{
+	clock::Clocks,
+	manager::{backend::resources::mixer::Mixer, command::RecorderCommand},
+	recorder::Recorder,
+};
+
+pub(crate) struct Recorders {
+	recorders: Arena<Recorder>,
+	unused_recorder_producer: Producer<Recorder>,
+}
+
+impl Recorders {
+	pub fn new(capacity: usize, unused_recorder_producer: Producer<Recorder>) -> Self {
+		Self {
+			recorders: Arena::new(capacity),
+			unused_recorder_producer,
+		}
+	}
+
+	pub fn controller(&self) -> Controller {
+		self.recorders.controller()
+	}
+
+	fn remove_unused_recorders(&mut self) {
+		if self.unused_recorder_producer.is_full() {
+			return;
+		}
+		for (_, recorder) in self
+			.recorders
+			.drain_filter(|recorder| recorder.shared().is_marked_for_removal())
+		{
+			if self.unused_recorder_producer.push(recorder).is_err() {
+				panic!("Unused recorder producer is full")
+			}
+			if self.unused_recorder_producer.is_full() {
+				return;
+			}
+		}
+	}
+
+	pub fn on_start_processing(&mut self) {
+		self.remove_unused_recorders();
+		for (_, recorder) in &self.recorders {
+			recorder.on_start_processing();
+		}
+	}
+
+	pub fn run_command(&mut self, command: RecorderCommand) {
+		match command {
+			RecorderCommand::Add(id, recorder) => self
+				.recorders
+				.insert_with_key(id.0, recorder)
+				.expect("Recorder arena is full"),
+			RecorderCommand::Start(id, start_time) => {
+				if let Some(recorder) = self.recorders.get_mut(id.0) {
+					recorder.start(start_time);
+				}
+			}
+			RecorderCommand::Stop(id, stop_time) => {
+				if let Some(recorder) = self.recorders.get_mut(id.0) {
+					recorder.stop(stop_time);
+				}
+			}
+		}
+	}
+
+	pub fn record(&mut self, mixer: &Mixer, clocks: &Clocks) {
+		for (_, recorder) in &mut self.recorders {
+			recorder.record(mixer, clocks);
+		}
+	}
+}
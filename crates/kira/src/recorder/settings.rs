@@ -0,0 +1,73 @@
+use crate::track::TrackId;
+
+/// What a recorder should do when its buffer is full
+/// because frames aren't being read quickly enough.
+///
+/// Either way, the audio thread never waits for the
+/// buffer to be emptied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub enum OverflowPolicy {
+	/// Discard new frames until there's room in the buffer,
+	/// then continue recording.
+	#[default]
+	DropNewFrames,
+	/// Stop recording. Recording can be started again with
+	/// [`RecorderHandle::start`](super::RecorderHandle::start).
+	StopRecording,
+}
+
+/// Settings for a recorder.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub struct RecorderSettings {
+	/// The mixer track to record.
+	pub track: TrackId,
+	/// The number of frames that can be waiting to be read
+	/// from the recorder at a time.
+	pub buffer_capacity: usize,
+	/// What the recorder should do when its buffer is full.
+	pub overflow_policy: OverflowPolicy,
+}
+
+impl RecorderSettings {
+	/// Creates a new [`RecorderSettings`] with the default settings.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Sets the mixer track to record.
+	pub fn track(self, track: impl Into<TrackId>) -> Self {
+		Self {
+			track: track.into(),
+			..self
+		}
+	}
+
+	/// Sets the number of frames that can be waiting to be read
+	/// from the recorder at a time.
+	pub fn buffer_capacity(self, buffer_capacity: usize) -> Self {
+		Self {
+			buffer_capacity,
+			..self
+		}
+	}
+
+	/// Sets what the recorder should do when its buffer is full.
+	pub fn overflow_policy(self, overflow_policy: OverflowPolicy) -> Self {
+		Self {
+			overflow_policy,
+			..self
+		}
+	}
+}
+
+impl Default for RecorderSettings {
+	fn default() -> Self {
+		Self {
+			track: TrackId::Main,
+			buffer_capacity: 65536,
+			overflow_policy: OverflowPolicy::default(),
+		}
+	}
+}
@@ -0,0 +1,115 @@
+use std::sync::Arc;
+
+use crate::{
+	clock::ClockTime,
+	dsp::Frame,
+	manager::{backend::MockBackend, AudioManager},
+	sound::static_sound::{StaticSoundData, StaticSoundSettings},
+	test::create_manager,
+	StartTime,
+};
+
+use super::{OverflowPolicy, RecorderSettings};
+
+/// Creates a manager that plays a sound counting up from 1.
+fn create_manager_with_sound() -> AudioManager<MockBackend> {
+	let mut manager = create_manager(100);
+	manager
+		.play(StaticSoundData {
+			sample_rate: 100,
+			frames: Arc::new((1..=100).map(|i| Frame::from_mono(i as f32)).collect()),
+			settings: StaticSoundSettings::new(),
+			markers: Arc::new([]),
+		})
+		.unwrap();
+	manager
+}
+
+fn assert_frame_eq(actual: Option<Frame>, expected: f32) {
+	let actual = actual.expect("expected a frame");
+	assert!((actual.left - expected).abs() < 1.0e-4);
+	assert!((actual.right - expected).abs() < 1.0e-4);
+}
+
+#[test]
+fn records_main_track() {
+	let mut manager = create_manager_with_sound();
+	let mut recorder = manager.add_recorder(RecorderSettings::new()).unwrap();
+	recorder.start(StartTime::Immediate).unwrap();
+	let backend = manager.backend_mut();
+	backend.on_start_processing();
+	for _ in 0..3 {
+		backend.process();
+	}
+	backend.on_start_processing();
+	assert!(recorder.recording());
+	assert_eq!(recorder.available_frames(), 3);
+	let mut frames = [Frame::ZERO; 3];
+	assert_eq!(recorder.pop_frames(&mut frames), 3);
+	for (i, frame) in frames.iter().enumerate() {
+		assert_frame_eq(Some(*frame), i as f32 + 1.0);
+	}
+	recorder.stop(StartTime::Immediate).unwrap();
+	let backend = manager.backend_mut();
+	backend.on_start_processing();
+	backend.process();
+	backend.on_start_processing();
+	assert!(!recorder.recording());
+	assert_eq!(recorder.pop(), None);
+}
+
+#[test]
+fn waits_for_start_time() {
+	let mut manager = create_manager_with_sound();
+	let mut clock = manager.add_clock(0.02).unwrap();
+	let mut recorder = manager.add_recorder(RecorderSettings::new()).unwrap();
+	recorder
+		.start(ClockTime {
+			clock: clock.id(),
+			ticks: 1,
+		})
+		.unwrap();
+	clock.start().unwrap();
+	let backend = manager.backend_mut();
+	backend.on_start_processing();
+	for _ in 0..5 {
+		backend.process();
+	}
+	// the clock ticks on the second frame
+	assert_frame_eq(recorder.pop(), 2.0);
+	assert_eq!(recorder.available_frames(), 3);
+}
+
+#[test]
+fn overflow_policies() {
+	let mut manager = create_manager_with_sound();
+	let mut dropping = manager
+		.add_recorder(RecorderSettings::new().buffer_capacity(2))
+		.unwrap();
+	let mut stopping = manager
+		.add_recorder(
+			RecorderSettings::new()
+				.buffer_capacity(2)
+				.overflow_policy(OverflowPolicy::StopRecording),
+		)
+		.unwrap();
+	dropping.start(StartTime::Immediate).unwrap();
+	stopping.start(StartTime::Immediate).unwrap();
+	let backend = manager.backend_mut();
+	backend.on_start_processing();
+	for _ in 0..4 {
+		backend.process();
+	}
+	backend.on_start_processing();
+	assert_eq!(dropping.dropped_frames(), 2);
+	assert!(dropping.recording());
+	assert_eq!(stopping.dropped_frames(), 1);
+	assert!(!stopping.recording());
+	// after making room, the dropping recorder picks up where
+	// the audio currently is
+	dropping.pop();
+	let backend = manager.backend_mut();
+	backend.process();
+	assert_frame_eq(dropping.pop(), 2.0);
+	assert_frame_eq(dropping.pop(), 5.0);
+}
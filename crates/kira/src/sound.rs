@@ -8,7 +8,65 @@ take a look at the documentation for
 
 pub mod static_sound;
 
-use crate::{clock::Clocks, dsp::Frame, parameter::Parameters, track::TrackId};
+use atomic_arena::Key;
+
+use crate::{
+	clock::Clocks, dsp::Frame, manager::backend::resources::mixer::Mixer, parameter::Parameters,
+	track::TrackId,
+};
+
+/// A unique identifier for a playing [`Sound`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SoundId(pub(crate) Key);
+
+/// Something that happened to a [`Sound`] that gameplay
+/// code may want to know about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SoundEvent {
+	/// The sound started producing audio.
+	Started,
+	/// The sound reached its loop end and jumped back
+	/// to its loop start.
+	Looped,
+	/// The playback position crossed the [`Marker`] with
+	/// the given ID.
+	MarkerCrossed(u32),
+}
+
+/// A named point in time in a sound.
+///
+/// When the playback position of a sound crosses a marker,
+/// an [`Event::MarkerCrossed`](crate::manager::event::Event::MarkerCrossed)
+/// is emitted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Marker {
+	/// A number identifying the marker in events.
+	pub id: u32,
+	/// The position of the marker (in seconds).
+	pub position: f64,
+	/// The name of the marker, if it has one.
+	pub name: Option<String>,
+}
+
+impl Marker {
+	/// Creates a new unnamed [`Marker`].
+	pub fn new(id: u32, position: f64) -> Self {
+		Self {
+			id,
+			position,
+			name: None,
+		}
+	}
+
+	/// Sets the name of the marker.
+	pub fn name(self, name: impl Into<String>) -> Self {
+		Self {
+			name: Some(name.into()),
+			..self
+		}
+	}
+}
 
 /// Represents a source of audio that is loaded, but not yet playing.
 pub trait SoundData {
@@ -23,6 +81,22 @@ pub trait SoundData {
 	/// and a handle to control it.
 	#[allow(clippy::type_complexity)]
 	fn into_sound(self) -> Result<(Box<dyn Sound>, Self::Handle), Self::Error>;
+
+	/// Like [`into_sound`](SoundData::into_sound), but also receives
+	/// the ID that identifies the sound in
+	/// [`Event`](crate::manager::event::Event)s, so the handle can
+	/// provide a way to get it. This is what
+	/// [`AudioManager::play`](crate::manager::AudioManager::play) calls.
+	///
+	/// By default, this ignores the ID and calls `into_sound`.
+	#[allow(clippy::type_complexity)]
+	fn into_sound_with_id(self, id: SoundId) -> Result<(Box<dyn Sound>, Self::Handle), Self::Error>
+	where
+		Self: Sized,
+	{
+		let _ = id;
+		self.into_sound()
+	}
 }
 
 /// Represents an actively playing sound.
@@ -39,6 +113,54 @@ pub trait Sound: Send {
 	/// Produces the next [`Frame`] of audio.
 	fn process(&mut self, dt: f64, parameters: &Parameters, clocks: &Clocks) -> Frame;
 
+	/// Produces the next [`Frame`] of audio and sends it to
+	/// one or more mixer tracks.
+	///
+	/// By default, this sends the result of [`process`](Sound::process)
+	/// to the sound's [`track`](Sound::track). Sounds that route
+	/// different parts of their audio to different tracks can
+	/// override this.
+	fn process_to_tracks(
+		&mut self,
+		dt: f64,
+		parameters: &Parameters,
+		clocks: &Clocks,
+		outputs: &mut SoundOutputs,
+	) {
+		let track = self.track();
+		outputs.send(track, self.process(dt, parameters, clocks));
+	}
+
+	/// Returns the oldest event that happened to the sound
+	/// and hasn't been reported yet.
+	///
+	/// This is called repeatedly whenever a new batch of audio
+	/// samples is requested until it returns `None`. Sounds that
+	/// don't report events don't need to implement this.
+	fn pop_event(&mut self) -> Option<SoundEvent> {
+		None
+	}
+
 	/// Returns `true` if the sound is finished and can be unloaded.
 	fn finished(&self) -> bool;
 }
+
+/// Sends audio from a [`Sound`] to mixer tracks.
+pub struct SoundOutputs<'a> {
+	mixer: &'a mut Mixer,
+}
+
+impl<'a> SoundOutputs<'a> {
+	pub(crate) fn new(mixer: &'a mut Mixer) -> Self {
+		Self { mixer }
+	}
+
+	/// Adds a frame of audio to the input of a mixer track.
+	///
+	/// Audio sent to a track that doesn't exist is discarded.
+	pub fn send(&mut self, track: TrackId, frame: Frame) {
+		if let Some(track) = self.mixer.track_mut(track) {
+			track.add_input(frame);
+		}
+	}
+}
@@ -15,7 +15,7 @@ use crate::{tween::Tween, value::Value};
 #[cfg(test)]
 mod test;
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 enum Command {
 	SetVolume(Value),
 	SetPlaybackRate(Value),
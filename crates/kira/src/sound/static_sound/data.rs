@@ -3,8 +3,8 @@ use std::{sync::Arc, time::Duration};
 use ringbuf::RingBuffer;
 
 use crate::{
-	dsp::{interpolate_frame, Frame},
-	sound::{Sound, SoundData},
+	dsp::{resample, Frame},
+	sound::{Marker, Sound, SoundData, SoundId},
 };
 
 use super::{handle::StaticSoundHandle, sound::StaticSound, StaticSoundSettings};
@@ -23,6 +23,9 @@ pub struct StaticSoundData {
 	pub frames: Arc<Vec<Frame>>,
 	/// Settings for the sound.
 	pub settings: StaticSoundSettings,
+	/// Points in time that emit events when the playback
+	/// position crosses them.
+	pub markers: Arc<[Marker]>,
 }
 
 impl StaticSoundData {
@@ -31,43 +34,100 @@ impl StaticSoundData {
 		Duration::from_secs_f64(self.frames.len() as f64 / self.sample_rate as f64)
 	}
 
+	/// Returns a copy of the audio converted to a different
+	/// sample rate.
+	///
+	/// Sounds that have the same sample rate as the audio
+	/// backend don't need to be resampled on the fly, which
+	/// saves CPU time and improves quality. This uses a
+	/// high-quality resampler (see [`resample`]), so it can
+	/// take a while for long sounds.
+	pub fn resampled(&self, sample_rate: u32) -> Self {
+		Self {
+			sample_rate,
+			frames: Arc::new(resample(&self.frames, self.sample_rate, sample_rate)),
+			settings: self.settings,
+			markers: self.markers.clone(),
+		}
+	}
+
+	/// Sets the points in time that emit events when the
+	/// playback position crosses them.
+	pub fn markers(self, markers: Vec<Marker>) -> Self {
+		Self {
+			markers: markers.into(),
+			..self
+		}
+	}
+
+	/// Adds a point in time that emits an event when the
+	/// playback position crosses it.
+	pub fn with_marker(self, marker: Marker) -> Self {
+		let mut markers = self.markers.to_vec();
+		markers.push(marker);
+		self.markers(markers)
+	}
+
 	fn frame_at_index(&self, index: usize) -> Frame {
 		self.frames.get(index).copied().unwrap_or(Frame::ZERO)
 	}
 
 	/// Gets the [`Frame`] at an arbitrary time in seconds.
+	///
+	/// Frames between samples are estimated using the sound's
+	/// [`Interpolation`](crate::dsp::Interpolation) setting.
 	pub fn frame_at_position(&self, position: f64) -> Frame {
 		let sample_position = self.sample_rate as f64 * position;
 		let fraction = (sample_position % 1.0) as f32;
 		let current_sample_index = sample_position as usize;
-		let previous = if current_sample_index == 0 {
-			Frame::ZERO
-		} else {
-			self.frame_at_index(current_sample_index - 1)
-		};
-		let current = self.frame_at_index(current_sample_index);
-		let next_1 = self.frame_at_index(current_sample_index + 1);
-		let next_2 = self.frame_at_index(current_sample_index + 2);
-		interpolate_frame(previous, current, next_1, next_2, fraction)
+		let mut frames = [Frame::ZERO; 8];
+		let taps = self.settings.interpolation.taps();
+		for (i, frame) in frames
+			.iter_mut()
+			.enumerate()
+			.take(taps.end)
+			.skip(taps.start)
+		{
+			// frames[3] is the current frame
+			if let Some(index) = (current_sample_index + i).checked_sub(3) {
+				*frame = self.frame_at_index(index);
+			}
+		}
+		self.settings.interpolation.interpolate(frames, fraction)
 	}
 }
 
-impl SoundData for StaticSoundData {
-	type Error = ();
-
-	type Handle = StaticSoundHandle;
-
-	#[allow(clippy::type_complexity)]
-	fn into_sound(self) -> Result<(Box<dyn Sound>, Self::Handle), Self::Error> {
+impl StaticSoundData {
+	fn split(self, id: Option<SoundId>) -> (Box<dyn Sound>, StaticSoundHandle) {
 		let (command_producer, command_consumer) = RingBuffer::new(COMMAND_BUFFER_CAPACITY).split();
 		let sound = StaticSound::new(self, command_consumer);
 		let shared = sound.shared();
-		Ok((
+		(
 			Box::new(sound),
 			StaticSoundHandle {
+				id,
 				command_producer,
 				shared,
 			},
-		))
+		)
+	}
+}
+
+impl SoundData for StaticSoundData {
+	type Error = ();
+
+	type Handle = StaticSoundHandle;
+
+	#[allow(clippy::type_complexity)]
+	fn into_sound(self) -> Result<(Box<dyn Sound>, Self::Handle), Self::Error> {
+		Ok(self.split(None))
+	}
+
+	#[allow(clippy::type_complexity)]
+	fn into_sound_with_id(
+		self,
+		id: SoundId,
+	) -> Result<(Box<dyn Sound>, Self::Handle), Self::Error> {
+		Ok(self.split(Some(id)))
 	}
 }
@@ -2,7 +2,7 @@ use std::{error::Error, fmt::Display, sync::Arc};
 
 use ringbuf::Producer;
 
-use crate::{tween::Tween, value::Value};
+use crate::{sound::SoundId, tween::Tween, value::Value};
 
 use super::{sound::Shared, Command, PlaybackState};
 
@@ -21,11 +21,19 @@ impl Error for CommandQueueFull {}
 
 /// Controls a static sound.
 pub struct StaticSoundHandle {
+	pub(super) id: Option<SoundId>,
 	pub(super) command_producer: Producer<Command>,
 	pub(super) shared: Arc<Shared>,
 }
 
 impl StaticSoundHandle {
+	/// Returns the unique identifier for the sound, or `None`
+	/// if the sound wasn't started by an
+	/// [`AudioManager`](crate::manager::AudioManager).
+	pub fn id(&self) -> Option<SoundId> {
+		self.id
+	}
+
 	/// Returns the current playback state of the sound.
 	pub fn state(&self) -> PlaybackState {
 		self.shared.state()
@@ -52,6 +60,7 @@ impl StaticSoundHandle {
 	/// # 	sample_rate: 1,
 	/// # 	frames: Arc::new(vec![]),
 	/// # 	settings: Default::default(),
+	/// # 	markers: Arc::new([]),
 	/// # })?;
 	/// #
 	/// // set the volume to a fixed value
@@ -89,6 +98,7 @@ impl StaticSoundHandle {
 	/// # 	sample_rate: 1,
 	/// # 	frames: Arc::new(vec![]),
 	/// # 	settings: Default::default(),
+	/// # 	markers: Arc::new([]),
 	/// # })?;
 	/// #
 	/// // set the playback rate to a fixed value
@@ -126,6 +136,7 @@ impl StaticSoundHandle {
 	/// # 	sample_rate: 1,
 	/// # 	frames: Arc::new(vec![]),
 	/// # 	settings: Default::default(),
+	/// # 	markers: Arc::new([]),
 	/// # })?;
 	/// #
 	/// // set the panning to a fixed value
@@ -1,4 +1,6 @@
-use crate::{track::TrackId, tween::Tween, value::Value, LoopBehavior, StartTime};
+use crate::{
+	dsp::Interpolation, track::TrackId, tween::Tween, value::Value, LoopBehavior, StartTime,
+};
 
 /// Settings for a static sound.
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -30,6 +32,9 @@ pub struct StaticSoundSettings {
 	pub track: TrackId,
 	/// An optional fade-in from silence.
 	pub fade_in_tween: Option<Tween>,
+	/// How audio between frames is estimated when the sound
+	/// doesn't play at its original sample rate.
+	pub interpolation: Interpolation,
 }
 
 impl StaticSoundSettings {
@@ -45,6 +50,7 @@ impl StaticSoundSettings {
 			loop_behavior: None,
 			track: TrackId::Main,
 			fade_in_tween: None,
+			interpolation: Interpolation::default(),
 		}
 	}
 
@@ -121,6 +127,15 @@ impl StaticSoundSettings {
 			..self
 		}
 	}
+
+	/// Sets how audio between frames is estimated when the sound
+	/// doesn't play at its original sample rate.
+	pub fn interpolation(self, interpolation: Interpolation) -> Self {
+		Self {
+			interpolation,
+			..self
+		}
+	}
 }
 
 impl Default for StaticSoundSettings {
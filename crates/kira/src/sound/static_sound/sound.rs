@@ -1,4 +1,5 @@
 use std::{
+	collections::VecDeque,
 	ops::Range,
 	sync::{
 		atomic::{AtomicU64, AtomicU8, Ordering},
@@ -12,7 +13,7 @@ use crate::{
 	clock::{ClockTime, Clocks},
 	dsp::Frame,
 	parameter::Parameters,
-	sound::Sound,
+	sound::{Marker, Sound, SoundEvent},
 	track::TrackId,
 	tween::{Tween, Tweenable},
 	value::CachedValue,
@@ -21,6 +22,10 @@ use crate::{
 
 use super::{data::StaticSoundData, Command};
 
+/// How many loop events can be queued up in addition to
+/// marker events before new events are discarded.
+const LOOP_EVENT_CAPACITY: usize = 16;
+
 /// The playback state of a sound.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum PlaybackState {
@@ -70,12 +75,29 @@ pub(super) struct StaticSound {
 	playback_rate: CachedValue,
 	panning: CachedValue,
 	volume_fade: Tweenable,
+	started: bool,
+	reported_start: bool,
+	/// Loop and marker events that haven't been reported yet,
+	/// in the order they happened.
+	unreported_events: VecDeque<SoundEvent>,
 	shared: Arc<Shared>,
 }
 
 impl StaticSound {
-	pub fn new(data: StaticSoundData, command_consumer: Consumer<Command>) -> Self {
+	pub fn new(mut data: StaticSoundData, command_consumer: Consumer<Command>) -> Self {
+		// the markers are shared with other copies of the sound data,
+		// so they're only copied if they need to be sorted
+		if !data
+			.markers
+			.windows(2)
+			.all(|pair| pair[0].position <= pair[1].position)
+		{
+			let mut markers = data.markers.to_vec();
+			markers.sort_by(|a, b| a.position.total_cmp(&b.position));
+			data.markers = markers.into();
+		}
 		let settings = data.settings;
+		let unreported_events = VecDeque::with_capacity(data.markers.len() + LOOP_EVENT_CAPACITY);
 		let position = if settings.reverse {
 			data.duration().as_secs_f64() - settings.start_position
 		} else {
@@ -97,6 +119,9 @@ impl StaticSound {
 			} else {
 				Tweenable::new(1.0)
 			},
+			started: false,
+			reported_start: false,
+			unreported_events,
 			shared: Arc::new(Shared {
 				state: AtomicU8::new(PlaybackState::Playing as u8),
 				position: AtomicU64::new(position.to_bits()),
@@ -136,16 +161,43 @@ impl StaticSound {
 		}
 	}
 
-	fn increment_playback_position(&mut self, amount: f64) {
+	/// Queues up the loop event and the markers that were crossed
+	/// when the playback position moved from `previous_position`
+	/// past the end of the loop and wrapped around to the other end.
+	fn queue_events_across_loop(&mut self, previous_position: f64, reverse: bool) {
+		let loop_start = match self.data.settings.loop_behavior {
+			Some(LoopBehavior { start_position }) => start_position,
+			None => return,
+		};
+		let loop_end = self.data.duration().as_secs_f64();
+		let markers = &self.data.markers;
+		let queue = &mut self.unreported_events;
+		if reverse {
+			queue_markers(markers, queue, previous_position, loop_start);
+			push_event(queue, SoundEvent::Looped);
+			queue_markers(markers, queue, loop_end, self.position);
+		} else {
+			queue_markers(markers, queue, previous_position, loop_end);
+			push_event(queue, SoundEvent::Looped);
+			queue_markers(markers, queue, loop_start, self.position);
+		}
+	}
+
+	/// Moves the playback position, looping or stopping the sound
+	/// if it goes past the end. Returns `true` if the sound looped.
+	fn increment_playback_position(&mut self, amount: f64) -> bool {
 		self.position += amount;
 		if let Some(LoopBehavior { start_position }) = self.data.settings.loop_behavior {
+			let position = self.position;
 			self.position = wrap(
 				self.position,
 				start_position..self.data.duration().as_secs_f64(),
 			);
+			return self.position != position;
 		} else if self.position < 0.0 || self.position > self.data.duration().as_secs_f64() {
 			self.set_state(PlaybackState::Stopped);
 		}
+		false
 	}
 }
 
@@ -166,9 +218,11 @@ impl Sound for StaticSound {
 				Command::Pause(tween) => self.pause(tween),
 				Command::Resume(tween) => self.resume(tween),
 				Command::Stop(tween) => self.stop(tween),
-				Command::SeekBy(amount) => self.increment_playback_position(amount),
+				Command::SeekBy(amount) => {
+					self.increment_playback_position(amount);
+				}
 				Command::SeekTo(position) => {
-					self.increment_playback_position(position - self.position)
+					self.increment_playback_position(position - self.position);
 				}
 			}
 		}
@@ -185,6 +239,7 @@ impl Sound for StaticSound {
 		if matches!(self.start_time, StartTime::ClockTime(..)) {
 			return Frame::ZERO;
 		}
+		self.started = true;
 		if self.volume_fade.update(dt, clocks) {
 			match self.state {
 				PlaybackState::Pausing => self.set_state(PlaybackState::Paused),
@@ -199,16 +254,65 @@ impl Sound for StaticSound {
 		self.playback_rate.update(parameters);
 		self.panning.update(parameters);
 		let out = self.data.frame_at_position(self.position);
-		self.increment_playback_position(self.playback_rate() * dt);
+		let previous_position = self.position;
+		let amount = self.playback_rate() * dt;
+		if self.increment_playback_position(amount) {
+			self.queue_events_across_loop(previous_position, amount < 0.0);
+		} else {
+			queue_markers(
+				&self.data.markers,
+				&mut self.unreported_events,
+				previous_position,
+				self.position,
+			);
+		}
 		(out * self.volume_fade.value() as f32 * self.volume.get() as f32)
 			.panned(self.panning.get() as f32)
 	}
 
+	fn pop_event(&mut self) -> Option<SoundEvent> {
+		if self.started && !self.reported_start {
+			self.reported_start = true;
+			return Some(SoundEvent::Started);
+		}
+		self.unreported_events.pop_front()
+	}
+
 	fn finished(&self) -> bool {
 		self.state == PlaybackState::Stopped
 	}
 }
 
+/// Adds an event to the queue. Events that don't fit in the
+/// queue are discarded so the queue never allocates.
+fn push_event(queue: &mut VecDeque<SoundEvent>, event: SoundEvent) {
+	if queue.len() < queue.capacity() {
+		queue.push_back(event);
+	}
+}
+
+/// Queues up events for the markers between `from` and `to`,
+/// including a marker at `from` but not a marker at `to`.
+///
+/// `markers` must be sorted by position.
+fn queue_markers(markers: &[Marker], queue: &mut VecDeque<SoundEvent>, from: f64, to: f64) {
+	let mut push = |marker: &Marker| push_event(queue, SoundEvent::MarkerCrossed(marker.id));
+	if to >= from {
+		let start = markers.partition_point(|marker| marker.position < from);
+		markers[start..]
+			.iter()
+			.take_while(|marker| marker.position < to)
+			.for_each(&mut push);
+	} else {
+		let end = markers.partition_point(|marker| marker.position <= from);
+		markers[..end]
+			.iter()
+			.rev()
+			.take_while(|marker| marker.position > to)
+			.for_each(&mut push);
+	}
+}
+
 fn wrap(mut x: f64, range: Range<f64>) -> f64 {
 	let length = range.end - range.start;
 	while x < range.start {
@@ -1,8 +1,18 @@
 use std::{sync::Arc, time::Duration};
 
-use crate::dsp::Frame;
+use ringbuf::RingBuffer;
 
-use super::data::StaticSoundData;
+use crate::{
+	clock::Clocks,
+	dsp::{Frame, Interpolation},
+	modulator::Modulators,
+	parameter::Parameters,
+	sound::{Marker, Sound, SoundEvent},
+	value::Expressions,
+	LoopBehavior,
+};
+
+use super::{data::StaticSoundData, sound::StaticSound, StaticSoundSettings};
 
 #[test]
 fn duration() {
@@ -10,6 +20,7 @@ fn duration() {
 		sample_rate: 1,
 		frames: Arc::new(vec![Frame::from_mono(0.0); 4]),
 		settings: Default::default(),
+		markers: Arc::new([]),
 	};
 	assert_eq!(static_sound.duration(), Duration::from_secs(4));
 }
@@ -25,6 +36,7 @@ fn frame_at_position() {
 			Frame::from_mono(3.0),
 		]),
 		settings: Default::default(),
+		markers: Arc::new([]),
 	};
 	assert_eq!(static_sound.frame_at_position(-1.0), Frame::from_mono(0.0));
 	assert_eq!(static_sound.frame_at_position(0.0), Frame::from_mono(0.0));
@@ -33,3 +45,193 @@ fn frame_at_position() {
 	assert_eq!(static_sound.frame_at_position(3.0), Frame::from_mono(3.0));
 	assert_eq!(static_sound.frame_at_position(4.0), Frame::from_mono(0.0));
 }
+
+fn sine(frequency: f64, sample_rate: u32, len: usize) -> Vec<Frame> {
+	(0..len)
+		.map(|i| {
+			let sample =
+				(2.0 * std::f64::consts::PI * frequency * i as f64 / sample_rate as f64).sin();
+			Frame::from_mono(sample as f32)
+		})
+		.collect()
+}
+
+#[test]
+fn resampled() {
+	let static_sound = StaticSoundData {
+		sample_rate: 44_100,
+		frames: Arc::new(sine(1000.0, 44_100, 4410)),
+		settings: Default::default(),
+		markers: Arc::new([]),
+	};
+	let resampled = static_sound.resampled(48_000);
+	assert_eq!(resampled.sample_rate, 48_000);
+	assert_eq!(resampled.frames.len(), 4800);
+	// away from the edges, the result should match a sine wave
+	// generated at the new sample rate
+	let expected = sine(1000.0, 48_000, 4800);
+	for (actual, expected) in resampled
+		.frames
+		.iter()
+		.zip(expected.iter())
+		.skip(100)
+		.take(4600)
+	{
+		assert!((actual.left - expected.left).abs() < 1.0e-3);
+	}
+}
+
+#[test]
+fn resampling_removes_frequencies_above_nyquist() {
+	// 15kHz is above the Nyquist frequency of a 22.05kHz sample rate
+	let static_sound = StaticSoundData {
+		sample_rate: 44_100,
+		frames: Arc::new(sine(15_000.0, 44_100, 4410)),
+		settings: Default::default(),
+		markers: Arc::new([]),
+	};
+	let resampled = static_sound.resampled(22_050);
+	for frame in resampled.frames.iter().skip(100).take(2000) {
+		assert!(frame.left.abs() < 0.01);
+	}
+}
+
+#[test]
+fn interpolation_modes() {
+	let frames = Arc::new(
+		(0..8)
+			.map(|i| Frame::from_mono(i as f32))
+			.collect::<Vec<_>>(),
+	);
+	let data = |interpolation| StaticSoundData {
+		sample_rate: 1,
+		frames: frames.clone(),
+		settings: StaticSoundSettings::new().interpolation(interpolation),
+		markers: Arc::new([]),
+	};
+	let nearest = data(Interpolation::Nearest);
+	assert_eq!(nearest.frame_at_position(3.25), Frame::from_mono(3.0));
+	assert_eq!(nearest.frame_at_position(3.75), Frame::from_mono(4.0));
+	assert_eq!(
+		data(Interpolation::Linear).frame_at_position(3.25),
+		Frame::from_mono(3.25)
+	);
+	// a straight line should be reconstructed (almost) exactly
+	// by the higher quality interpolators
+	for interpolation in [Interpolation::Cubic, Interpolation::Sinc] {
+		let frame = data(interpolation).frame_at_position(3.5);
+		assert!((frame.left - 3.5).abs() < 0.01);
+	}
+}
+
+#[test]
+fn interpolation_only_reads_its_taps() {
+	for interpolation in [
+		Interpolation::Nearest,
+		Interpolation::Linear,
+		Interpolation::Cubic,
+		Interpolation::Sinc,
+	] {
+		let taps = interpolation.taps();
+		let frames: [Frame; 8] = std::array::from_fn(|i| Frame::from_mono(i as f32));
+		let mut tapped_frames = [Frame::ZERO; 8];
+		tapped_frames[taps.clone()].copy_from_slice(&frames[taps]);
+		assert_eq!(
+			interpolation.interpolate(frames, 0.3),
+			interpolation.interpolate(tapped_frames, 0.3)
+		);
+	}
+}
+
+/// Plays a sound for the given number of steps, advancing the
+/// playback position by `dt` seconds each time, and returns the
+/// events it reported.
+fn reported_events(data: StaticSoundData, steps: usize, dt: f64) -> Vec<SoundEvent> {
+	let (_, command_consumer) = RingBuffer::new(1).split();
+	let mut sound = StaticSound::new(data, command_consumer);
+	let (unused_parameter_producer, _) = RingBuffer::new(1).split();
+	let (unused_automation_producer, _) = RingBuffer::new(1).split();
+	let (unused_modulator_producer, _) = RingBuffer::new(1).split();
+	let (unused_expression_producer, _) = RingBuffer::new(1).split();
+	let parameters = Parameters::new(
+		1,
+		unused_parameter_producer,
+		unused_automation_producer,
+		Modulators::new(1, unused_modulator_producer),
+		Expressions::new(1, unused_expression_producer),
+	);
+	let (unused_clock_producer, _) = RingBuffer::new(1).split();
+	let clocks = Clocks::new(1, unused_clock_producer);
+	let mut events = vec![];
+	for _ in 0..steps {
+		sound.process(dt, &parameters, &clocks);
+		events.extend(std::iter::from_fn(|| sound.pop_event()));
+	}
+	events
+}
+
+/// Plays a sound for the given number of seconds, one second at
+/// a time, and returns the IDs of the markers it crossed.
+fn crossed_markers(data: StaticSoundData, duration: usize) -> Vec<u32> {
+	reported_events(data, duration, 1.0)
+		.into_iter()
+		.filter_map(|event| match event {
+			SoundEvent::MarkerCrossed(id) => Some(id),
+			_ => None,
+		})
+		.collect()
+}
+
+#[test]
+fn markers() {
+	let data = |settings: StaticSoundSettings| {
+		StaticSoundData {
+			sample_rate: 1,
+			frames: Arc::new(vec![Frame::from_mono(0.0); 4]),
+			settings,
+			markers: Arc::new([]),
+		}
+		.with_marker(Marker::new(2, 2.5))
+		.with_marker(Marker::new(1, 0.5))
+	};
+	assert_eq!(crossed_markers(data(StaticSoundSettings::new()), 5), [1, 2]);
+	// markers are crossed again when the sound loops
+	assert_eq!(
+		crossed_markers(
+			data(StaticSoundSettings::new().loop_behavior(LoopBehavior {
+				start_position: 0.0
+			})),
+			6
+		),
+		[1, 2, 1]
+	);
+	// markers are crossed in reverse order when the sound
+	// plays backwards
+	assert_eq!(
+		crossed_markers(data(StaticSoundSettings::new().reverse(true)), 5),
+		[2, 1]
+	);
+}
+
+#[test]
+fn events_are_reported_in_order() {
+	let data = StaticSoundData {
+		sample_rate: 1,
+		frames: Arc::new(vec![Frame::from_mono(0.0); 4]),
+		settings: StaticSoundSettings::new().loop_behavior(LoopBehavior {
+			start_position: 0.0,
+		}),
+		markers: Arc::new([Marker::new(1, 0.5), Marker::new(2, 3.5)]),
+	};
+	// move past the loop end and wrap around in one step
+	assert_eq!(
+		reported_events(data, 1, 5.0),
+		[
+			SoundEvent::Started,
+			SoundEvent::MarkerCrossed(1),
+			SoundEvent::MarkerCrossed(2),
+			SoundEvent::Looped,
+			SoundEvent::MarkerCrossed(1),
+		]
+	);
+}
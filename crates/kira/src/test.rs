@@ -0,0 +1,11 @@
+use crate::manager::{backend::MockBackend, AudioManager, AudioManagerSettings};
+
+/// Creates an [`AudioManager`] with a [`MockBackend`] running
+/// at the given sample rate.
+pub(crate) fn create_manager(sample_rate: u32) -> AudioManager<MockBackend> {
+	AudioManager::new(
+		MockBackend::new(sample_rate),
+		AudioManagerSettings::default(),
+	)
+	.unwrap()
+}
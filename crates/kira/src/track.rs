@@ -2,13 +2,18 @@
 
 pub mod effect;
 mod handle;
+mod meter;
 mod routes;
 mod settings;
 
 pub use handle::*;
+pub use meter::{ChannelLevels, MeteringSettings, TrackLevels};
 pub use routes::*;
 pub use settings::*;
 
+#[cfg(test)]
+mod test;
+
 use std::sync::{
 	atomic::{AtomicBool, Ordering},
 	Arc,
@@ -23,7 +28,10 @@ use crate::{
 	value::{CachedValue, Value},
 };
 
-use self::effect::Effect;
+use self::{
+	effect::Effect,
+	meter::{Meter, TrackLevelsShared},
+};
 
 /// A unique identifier for a mixer sub-track.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -52,12 +60,24 @@ impl From<&TrackHandle> for TrackId {
 
 pub(crate) struct TrackShared {
 	removed: AtomicBool,
+	metering: bool,
+	levels: TrackLevelsShared,
 }
 
 impl TrackShared {
-	pub fn new() -> Self {
+	pub fn new(metering: bool) -> Self {
 		Self {
 			removed: AtomicBool::new(false),
+			metering,
+			levels: TrackLevelsShared::default(),
+		}
+	}
+
+	pub fn levels(&self) -> Option<TrackLevels> {
+		if self.metering {
+			Some(self.levels.load())
+		} else {
+			None
 		}
 	}
 
@@ -77,6 +97,8 @@ pub(crate) struct Track {
 	routes: Vec<(TrackId, CachedValue)>,
 	effects: Vec<Box<dyn Effect>>,
 	input: Frame,
+	output: Frame,
+	meter: Option<Meter>,
 }
 
 impl Track {
@@ -85,12 +107,14 @@ impl Track {
 			effect.init(context.sample_rate());
 		}
 		Self {
-			shared: Arc::new(TrackShared::new()),
+			shared: Arc::new(TrackShared::new(settings.metering.is_some())),
 			volume: CachedValue::new(.., settings.volume, 1.0),
 			panning: CachedValue::new(0.0..=1.0, settings.panning, 0.5),
 			routes: settings.routes.into_vec(),
 			effects: settings.effects,
 			input: Frame::ZERO,
+			output: Frame::ZERO,
+			meter: settings.metering.map(Meter::new),
 		}
 	}
 
@@ -110,10 +134,20 @@ impl Track {
 		self.panning.set(panning);
 	}
 
+	pub fn output(&self) -> Frame {
+		self.output
+	}
+
 	pub fn add_input(&mut self, input: Frame) {
 		self.input += input;
 	}
 
+	pub fn on_start_processing(&mut self) {
+		if let Some(meter) = &mut self.meter {
+			meter.publish(&self.shared.levels);
+		}
+	}
+
 	pub fn process(&mut self, dt: f64, parameters: &Parameters) -> Frame {
 		self.volume.update(parameters);
 		self.panning.update(parameters);
@@ -126,6 +160,10 @@ impl Track {
 		}
 		output *= self.volume.get() as f32;
 		output = output.panned(self.panning.get() as f32);
+		if let Some(meter) = &mut self.meter {
+			meter.process(output, dt);
+		}
+		self.output = output;
 		output
 	}
 }
@@ -1,5 +1,6 @@
 //! Modifies audio signals.
 
+pub mod analyzer;
 pub mod delay;
 pub mod distortion;
 pub mod filter;
@@ -0,0 +1,246 @@
+//! Measures the frequency content of audio.
+
+use std::{
+	f32::consts::PI,
+	sync::{
+		atomic::{AtomicU32, AtomicUsize, Ordering},
+		Arc,
+	},
+};
+
+use crate::{
+	dsp::{fft, Frame},
+	parameter::Parameters,
+	track::Effect,
+};
+
+/// A function that's applied to a window of audio before
+/// it's analyzed.
+///
+/// Windowing functions taper off the beginning and end of the
+/// window, which reduces "spectral leakage" (energy from one
+/// frequency showing up in neighboring bins).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum WindowFunction {
+	/// Leaves the window unchanged.
+	Rectangular,
+	/// A good general purpose window.
+	Hann,
+	/// Similar to [`WindowFunction::Hann`], but doesn't fully
+	/// taper off to zero at the edges.
+	Hamming,
+	/// Has less spectral leakage than [`WindowFunction::Hann`],
+	/// but blurs together nearby frequencies more.
+	Blackman,
+}
+
+impl WindowFunction {
+	fn coefficient(self, index: usize, len: usize) -> f32 {
+		if len < 2 {
+			return 1.0;
+		}
+		let x = 2.0 * PI * index as f32 / (len - 1) as f32;
+		match self {
+			WindowFunction::Rectangular => 1.0,
+			WindowFunction::Hann => 0.5 - 0.5 * x.cos(),
+			WindowFunction::Hamming => 0.54 - 0.46 * x.cos(),
+			WindowFunction::Blackman => 0.42 - 0.5 * x.cos() + 0.08 * (2.0 * x).cos(),
+		}
+	}
+}
+
+/// Settings for an [`Analyzer`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub struct AnalyzerSettings {
+	/// The number of frames that are analyzed at a time.
+	///
+	/// This must be a power of two. Larger windows give more
+	/// precise frequency information, but respond to changes
+	/// more slowly.
+	pub window_size: usize,
+	/// The function that's applied to each window before
+	/// it's analyzed.
+	pub window_function: WindowFunction,
+	/// How much the previous spectrum is blended with the
+	/// newly measured one. `0.0` means no smoothing, and
+	/// values closer to `1.0` make the spectrum change
+	/// more slowly.
+	pub smoothing: f32,
+}
+
+impl AnalyzerSettings {
+	/// Creates a new `AnalyzerSettings` with the default settings.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Sets the number of frames that are analyzed at a time.
+	pub fn window_size(self, window_size: usize) -> Self {
+		Self {
+			window_size,
+			..self
+		}
+	}
+
+	/// Sets the function that's applied to each window before
+	/// it's analyzed.
+	pub fn window_function(self, window_function: WindowFunction) -> Self {
+		Self {
+			window_function,
+			..self
+		}
+	}
+
+	/// Sets how much the previous spectrum is blended with the
+	/// newly measured one.
+	pub fn smoothing(self, smoothing: f32) -> Self {
+		Self { smoothing, ..self }
+	}
+}
+
+impl Default for AnalyzerSettings {
+	fn default() -> Self {
+		Self {
+			window_size: 1024,
+			window_function: WindowFunction::Hann,
+			smoothing: 0.5,
+		}
+	}
+}
+
+struct AnalyzerShared {
+	sample_rate: AtomicU32,
+	/// The most recent samples (mixed down to mono), stored
+	/// as a circular buffer of `f32` bits.
+	///
+	/// The buffer holds two windows, so the audio thread can keep
+	/// writing while the handle reads the latest window without
+	/// overwriting it.
+	samples: Vec<AtomicU32>,
+	/// The total number of samples that have been written.
+	num_samples_written: AtomicUsize,
+}
+
+impl AnalyzerShared {
+	fn new(window_size: usize) -> Self {
+		Self {
+			sample_rate: AtomicU32::new(0),
+			samples: (0..window_size * 2).map(|_| AtomicU32::new(0)).collect(),
+			num_samples_written: AtomicUsize::new(0),
+		}
+	}
+}
+
+/// An effect that sends the audio passing through it to an
+/// [`AnalyzerHandle`] without changing it.
+pub struct Analyzer {
+	shared: Arc<AnalyzerShared>,
+}
+
+impl Analyzer {
+	/// Creates a new analyzer and a handle to read its output.
+	///
+	/// # Panics
+	///
+	/// Panics if the window size isn't a power of two.
+	pub fn new(settings: AnalyzerSettings) -> (Self, AnalyzerHandle) {
+		assert!(
+			settings.window_size.is_power_of_two(),
+			"the analyzer window size must be a power of two"
+		);
+		let shared = Arc::new(AnalyzerShared::new(settings.window_size));
+		(
+			Self {
+				shared: shared.clone(),
+			},
+			AnalyzerHandle {
+				shared,
+				window_function: settings.window_function,
+				smoothing: settings.smoothing.clamp(0.0, 1.0),
+				real: vec![0.0; settings.window_size],
+				imag: vec![0.0; settings.window_size],
+				spectrum: vec![0.0; settings.window_size / 2],
+			},
+		)
+	}
+}
+
+impl Effect for Analyzer {
+	fn init(&mut self, sample_rate: u32) {
+		self.shared.sample_rate.store(sample_rate, Ordering::SeqCst);
+	}
+
+	fn process(&mut self, input: Frame, _dt: f64, _parameters: &Parameters) -> Frame {
+		// the oldest samples are overwritten, so the handle always
+		// analyzes the latest audio no matter how often it's read
+		let samples = &self.shared.samples;
+		let position = self.shared.num_samples_written.load(Ordering::Relaxed);
+		samples[position % samples.len()].store(
+			((input.left + input.right) * 0.5).to_bits(),
+			Ordering::Relaxed,
+		);
+		self.shared
+			.num_samples_written
+			.store(position.wrapping_add(1), Ordering::Release);
+		input
+	}
+}
+
+/// Reads the frequency content of the audio passing
+/// through an [`Analyzer`].
+pub struct AnalyzerHandle {
+	shared: Arc<AnalyzerShared>,
+	window_function: WindowFunction,
+	smoothing: f32,
+	real: Vec<f32>,
+	imag: Vec<f32>,
+	spectrum: Vec<f32>,
+}
+
+impl AnalyzerHandle {
+	/// Returns the sample rate of the audio being analyzed,
+	/// or `None` if the analyzer hasn't been added to a
+	/// mixer track yet.
+	pub fn sample_rate(&self) -> Option<u32> {
+		match self.shared.sample_rate.load(Ordering::SeqCst) {
+			0 => None,
+			sample_rate => Some(sample_rate),
+		}
+	}
+
+	/// Returns the center frequency (in hertz) of the bin with the
+	/// given index, or `None` if the analyzer hasn't been added to
+	/// a mixer track yet.
+	pub fn bin_frequency(&self, index: usize) -> Option<f64> {
+		self.sample_rate()
+			.map(|sample_rate| index as f64 * sample_rate as f64 / self.real.len() as f64)
+	}
+
+	/// Analyzes the most recent window of audio and returns the
+	/// magnitude of each frequency bin.
+	///
+	/// There are half as many bins as frames in the window. The
+	/// first bin represents 0 Hz, and each bin after that is
+	/// `sample_rate / window_size` Hz higher than the previous one.
+	pub fn spectrum(&mut self) -> &[f32] {
+		let samples = &self.shared.samples;
+		let end = self.shared.num_samples_written.load(Ordering::Acquire);
+		let len = self.real.len();
+		let start = end.wrapping_sub(len);
+		for i in 0..len {
+			let sample = f32::from_bits(
+				samples[start.wrapping_add(i) % samples.len()].load(Ordering::Relaxed),
+			);
+			self.real[i] = sample * self.window_function.coefficient(i, len);
+			self.imag[i] = 0.0;
+		}
+		fft(&mut self.real, &mut self.imag);
+		for (i, bin) in self.spectrum.iter_mut().enumerate() {
+			let magnitude = (self.real[i].powi(2) + self.imag[i].powi(2)).sqrt() / len as f32;
+			*bin = *bin * self.smoothing + magnitude * (1.0 - self.smoothing);
+		}
+		&self.spectrum
+	}
+}
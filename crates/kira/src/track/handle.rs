@@ -6,7 +6,7 @@ use crate::{
 	value::Value,
 };
 
-use super::{TrackId, TrackShared};
+use super::{TrackId, TrackLevels, TrackShared};
 
 /// Controls a mixer track.
 ///
@@ -14,7 +14,7 @@ use super::{TrackId, TrackShared};
 /// track will be removed.
 pub struct TrackHandle {
 	pub(crate) id: TrackId,
-	pub(crate) shared: Option<Arc<TrackShared>>,
+	pub(crate) shared: Arc<TrackShared>,
 	pub(crate) command_producer: CommandProducer,
 }
 
@@ -24,6 +24,13 @@ impl TrackHandle {
 		self.id
 	}
 
+	/// Returns the most recently measured levels of the mixer
+	/// track's output, or `None` if metering isn't enabled for
+	/// the track.
+	pub fn levels(&self) -> Option<TrackLevels> {
+		self.shared.levels()
+	}
+
 	/// Sets the (post-effects) volume of the mixer track.
 	pub fn set_volume(&mut self, volume: impl Into<Value>) -> Result<(), CommandError> {
 		self.command_producer
@@ -46,8 +53,9 @@ impl TrackHandle {
 
 impl Drop for TrackHandle {
 	fn drop(&mut self) {
-		if let Some(shared) = &self.shared {
-			shared.mark_for_removal();
+		// the main track can't be removed
+		if let TrackId::Sub(..) = self.id {
+			self.shared.mark_for_removal();
 		}
 	}
 }
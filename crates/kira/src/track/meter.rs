@@ -0,0 +1,196 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use crate::dsp::Frame;
+
+/// Settings for measuring the levels of a mixer track.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub struct MeteringSettings {
+	/// The amount of time the RMS level is averaged over (in seconds).
+	pub rms_window: f64,
+	/// How long the held peak level stays before it's
+	/// reset (in seconds).
+	pub peak_hold_time: f64,
+}
+
+impl MeteringSettings {
+	/// Creates a new [`MeteringSettings`] with the default settings.
+	pub fn new() -> Self {
+		Self {
+			rms_window: 0.3,
+			peak_hold_time: 1.5,
+		}
+	}
+
+	/// Sets the amount of time the RMS level is averaged over (in seconds).
+	pub fn rms_window(self, rms_window: f64) -> Self {
+		Self { rms_window, ..self }
+	}
+
+	/// Sets how long the held peak level stays before it's
+	/// reset (in seconds).
+	pub fn peak_hold_time(self, peak_hold_time: f64) -> Self {
+		Self {
+			peak_hold_time,
+			..self
+		}
+	}
+}
+
+impl Default for MeteringSettings {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+/// The measured levels of one channel of a mixer track.
+///
+/// All levels are linear amplitudes, where `1.0` is full scale.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ChannelLevels {
+	/// The highest absolute sample value since the last time
+	/// the levels were updated.
+	pub peak: f32,
+	/// The root mean square of the signal, which is closer
+	/// to the perceived loudness than the peak level.
+	pub rms: f32,
+	/// The highest peak level over the peak hold time.
+	pub peak_hold: f32,
+}
+
+/// The measured levels of a mixer track.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct TrackLevels {
+	/// The levels of the left channel.
+	pub left: ChannelLevels,
+	/// The levels of the right channel.
+	pub right: ChannelLevels,
+}
+
+#[derive(Default)]
+struct ChannelLevelsShared {
+	peak: AtomicU32,
+	rms: AtomicU32,
+	peak_hold: AtomicU32,
+}
+
+impl ChannelLevelsShared {
+	fn load(&self) -> ChannelLevels {
+		ChannelLevels {
+			peak: f32::from_bits(self.peak.load(Ordering::SeqCst)),
+			rms: f32::from_bits(self.rms.load(Ordering::SeqCst)),
+			peak_hold: f32::from_bits(self.peak_hold.load(Ordering::SeqCst)),
+		}
+	}
+
+	fn store(&self, levels: ChannelLevels) {
+		self.peak.store(levels.peak.to_bits(), Ordering::SeqCst);
+		self.rms.store(levels.rms.to_bits(), Ordering::SeqCst);
+		self.peak_hold
+			.store(levels.peak_hold.to_bits(), Ordering::SeqCst);
+	}
+}
+
+#[derive(Default)]
+pub(crate) struct TrackLevelsShared {
+	left: ChannelLevelsShared,
+	right: ChannelLevelsShared,
+}
+
+impl TrackLevelsShared {
+	pub fn load(&self) -> TrackLevels {
+		TrackLevels {
+			left: self.left.load(),
+			right: self.right.load(),
+		}
+	}
+}
+
+struct ChannelMeter {
+	peak: f32,
+	mean_square: f64,
+	peak_hold: f32,
+	peak_hold_timer: f64,
+}
+
+impl ChannelMeter {
+	fn new() -> Self {
+		Self {
+			peak: 0.0,
+			mean_square: 0.0,
+			peak_hold: 0.0,
+			peak_hold_timer: 0.0,
+		}
+	}
+
+	fn process(&mut self, sample: f32, dt: f64, rms_coefficient: f64, peak_hold_time: f64) {
+		let level = sample.abs();
+		self.peak = self.peak.max(level);
+		// exponential moving average of the squared signal
+		self.mean_square = (sample as f64).powi(2)
+			+ (self.mean_square - (sample as f64).powi(2)) * rms_coefficient;
+		self.peak_hold_timer -= dt;
+		if level >= self.peak_hold || self.peak_hold_timer <= 0.0 {
+			self.peak_hold = level;
+			self.peak_hold_timer = peak_hold_time;
+		}
+	}
+
+	fn levels(&self) -> ChannelLevels {
+		ChannelLevels {
+			peak: self.peak,
+			rms: self.mean_square.sqrt() as f32,
+			peak_hold: self.peak_hold,
+		}
+	}
+}
+
+/// Measures the levels of a track's output.
+pub(crate) struct Meter {
+	settings: MeteringSettings,
+	/// The `dt` the RMS smoothing coefficient was calculated for.
+	rms_coefficient_dt: f64,
+	/// How much of the previous mean square is kept each sample.
+	rms_coefficient: f64,
+	left: ChannelMeter,
+	right: ChannelMeter,
+}
+
+impl Meter {
+	pub fn new(settings: MeteringSettings) -> Self {
+		Self {
+			settings,
+			rms_coefficient_dt: 0.0,
+			rms_coefficient: 0.0,
+			left: ChannelMeter::new(),
+			right: ChannelMeter::new(),
+		}
+	}
+
+	pub fn process(&mut self, frame: Frame, dt: f64) {
+		// dt only changes with the sample rate, so the coefficient
+		// is recalculated rarely
+		if dt != self.rms_coefficient_dt {
+			self.rms_coefficient_dt = dt;
+			self.rms_coefficient = if self.settings.rms_window > 0.0 {
+				(-dt / self.settings.rms_window).exp()
+			} else {
+				0.0
+			};
+		}
+		let peak_hold_time = self.settings.peak_hold_time;
+		self.left
+			.process(frame.left, dt, self.rms_coefficient, peak_hold_time);
+		self.right
+			.process(frame.right, dt, self.rms_coefficient, peak_hold_time);
+	}
+
+	/// Sends the current levels to the gameplay thread and starts
+	/// measuring a new peak.
+	pub fn publish(&mut self, shared: &TrackLevelsShared) {
+		shared.left.store(self.left.levels());
+		shared.right.store(self.right.levels());
+		self.left.peak = 0.0;
+		self.right.peak = 0.0;
+	}
+}
@@ -51,8 +51,8 @@ impl TrackRoutes {
 
 	pub(crate) fn into_vec(self) -> Vec<(TrackId, CachedValue)> {
 		self.0
-			.iter()
-			.map(|(id, value)| (*id, CachedValue::new(.., *value, 0.0)))
+			.into_iter()
+			.map(|(id, value)| (id, CachedValue::new(.., value, 0.0)))
 			.collect()
 	}
 }
@@ -1,6 +1,6 @@
 use crate::value::Value;
 
-use super::{routes::TrackRoutes, Effect};
+use super::{routes::TrackRoutes, Effect, MeteringSettings};
 
 /// Settings for a mixer track.
 #[non_exhaustive]
@@ -16,6 +16,9 @@ pub struct TrackSettings {
 	/// The effects that should be applied to the input audio
 	/// for this track.
 	pub effects: Vec<Box<dyn Effect>>,
+	/// Whether the levels of the track should be measured,
+	/// and if so, how.
+	pub metering: Option<MeteringSettings>,
 }
 
 impl TrackSettings {
@@ -26,6 +29,7 @@ impl TrackSettings {
 			panning: Value::Fixed(0.5),
 			routes: TrackRoutes::new(),
 			effects: vec![],
+			metering: None,
 		}
 	}
 
@@ -57,6 +61,15 @@ impl TrackSettings {
 		self.effects.push(Box::new(effect));
 		self
 	}
+
+	/// Enables measuring the levels of the track. The levels can
+	/// be read with [`TrackHandle::levels`](super::TrackHandle::levels).
+	pub fn metering(self, metering: impl Into<Option<MeteringSettings>>) -> Self {
+		Self {
+			metering: metering.into(),
+			..self
+		}
+	}
 }
 
 impl Default for TrackSettings {
@@ -0,0 +1,116 @@
+use std::f32::consts::PI;
+
+use ringbuf::RingBuffer;
+
+use crate::{dsp::Frame, modulator::Modulators, parameter::Parameters, value::Expressions};
+
+use super::{
+	effect::analyzer::{Analyzer, AnalyzerSettings, WindowFunction},
+	meter::{Meter, TrackLevelsShared},
+	Effect, MeteringSettings,
+};
+
+fn create_parameters() -> Parameters {
+	let (unused_parameter_producer, _) = RingBuffer::new(1).split();
+	let (unused_automation_producer, _) = RingBuffer::new(1).split();
+	let (unused_modulator_producer, _) = RingBuffer::new(1).split();
+	let (unused_expression_producer, _) = RingBuffer::new(1).split();
+	Parameters::new(
+		1,
+		unused_parameter_producer,
+		unused_automation_producer,
+		Modulators::new(1, unused_modulator_producer),
+		Expressions::new(1, unused_expression_producer),
+	)
+}
+
+#[test]
+fn meter_measures_peak_and_rms() {
+	let mut meter = Meter::new(MeteringSettings::new().rms_window(0.01));
+	let shared = TrackLevelsShared::default();
+	for i in 0..1000 {
+		let sample = if i % 2 == 0 { 0.5 } else { -0.5 };
+		meter.process(Frame::new(sample, 0.25), 0.001);
+	}
+	meter.publish(&shared);
+	let levels = shared.load();
+	assert_eq!(levels.left.peak, 0.5);
+	assert_eq!(levels.right.peak, 0.25);
+	assert!((levels.left.rms - 0.5).abs() < 1.0e-4);
+	assert!((levels.right.rms - 0.25).abs() < 1.0e-4);
+	assert_eq!(levels.left.peak_hold, 0.5);
+}
+
+#[test]
+fn meter_holds_peaks() {
+	let mut meter = Meter::new(MeteringSettings::new().peak_hold_time(1.0));
+	let shared = TrackLevelsShared::default();
+	meter.process(Frame::from_mono(1.0), 0.1);
+	meter.publish(&shared);
+	// the peak resets every time the levels are published,
+	// but the held peak stays until the hold time has passed
+	for _ in 0..5 {
+		meter.process(Frame::from_mono(0.1), 0.1);
+	}
+	meter.publish(&shared);
+	let levels = shared.load();
+	assert_eq!(levels.left.peak, 0.1);
+	assert_eq!(levels.left.peak_hold, 1.0);
+	for _ in 0..6 {
+		meter.process(Frame::from_mono(0.1), 0.1);
+	}
+	meter.publish(&shared);
+	assert_eq!(shared.load().left.peak_hold, 0.1);
+}
+
+#[test]
+fn analyzer_finds_frequency_of_sine_wave() {
+	let (mut analyzer, mut handle) = Analyzer::new(
+		AnalyzerSettings::new()
+			.window_size(64)
+			.window_function(WindowFunction::Rectangular)
+			.smoothing(0.0),
+	);
+	analyzer.init(64);
+	assert_eq!(handle.sample_rate(), Some(64));
+	let parameters = create_parameters();
+	for i in 0..64 {
+		let sample = (2.0 * PI * 8.0 * i as f32 / 64.0).sin();
+		let output = analyzer.process(Frame::from_mono(sample), 1.0 / 64.0, &parameters);
+		assert_eq!(output, Frame::from_mono(sample));
+	}
+	let spectrum = handle.spectrum().to_vec();
+	assert_eq!(spectrum.len(), 32);
+	let loudest_bin = spectrum
+		.iter()
+		.enumerate()
+		.max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+		.unwrap()
+		.0;
+	assert_eq!(loudest_bin, 8);
+	assert_eq!(handle.bin_frequency(loudest_bin), Some(8.0));
+	assert!((spectrum[8] - 0.5).abs() < 1.0e-4);
+	assert!(spectrum[3] < 1.0e-4);
+}
+
+#[test]
+fn analyzer_reads_latest_audio() {
+	let (mut analyzer, mut handle) = Analyzer::new(
+		AnalyzerSettings::new()
+			.window_size(64)
+			.window_function(WindowFunction::Rectangular)
+			.smoothing(0.0),
+	);
+	analyzer.init(64);
+	let parameters = create_parameters();
+	// the handle isn't read while many windows of audio pass through
+	for frequency in [3.0, 8.0] {
+		for i in 0..64 * 10 {
+			let sample = (2.0 * PI * frequency * i as f32 / 64.0).sin();
+			analyzer.process(Frame::from_mono(sample), 1.0 / 64.0, &parameters);
+		}
+	}
+	let spectrum = handle.spectrum();
+	assert!((spectrum[8] - 0.5).abs() < 1.0e-4);
+	assert!(spectrum[3] < 1.0e-4);
+}
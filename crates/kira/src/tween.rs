@@ -4,9 +4,15 @@ mod tweenable;
 
 pub use tweenable::*;
 
-use std::time::Duration;
+#[cfg(test)]
+mod test;
 
-use crate::start_time::StartTime;
+use std::{
+	f64::consts::{PI, TAU},
+	time::Duration,
+};
+
+use crate::{clock::ClockDuration, start_time::StartTime};
 
 /// Curves the motion of a [`Tween`].
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -46,38 +52,207 @@ pub enum Easing {
 	/// for float intensity values at the cost of being more
 	/// CPU intensive.
 	InOutPowf(f64),
+	/// Starts slow and speeds up, following a sine curve.
+	InSine,
+	/// Starts fast and slows down, following a sine curve.
+	OutSine,
+	/// Starts slow, speeds up, and then slows back down,
+	/// following a sine curve.
+	InOutSine,
+	/// Starts very slow and speeds up exponentially.
+	InExpo,
+	/// Starts very fast and slows down exponentially.
+	OutExpo,
+	/// Starts very slow, speeds up exponentially, and then
+	/// slows back down.
+	InOutExpo,
+	/// Starts slow and speeds up, following a quarter circle.
+	InCirc,
+	/// Starts fast and slows down, following a quarter circle.
+	OutCirc,
+	/// Starts slow, speeds up, and then slows back down,
+	/// following two quarter circles.
+	InOutCirc,
+	/// Moves slightly backwards before moving towards the
+	/// target value.
+	InBack,
+	/// Overshoots the target value slightly before settling
+	/// on it.
+	OutBack,
+	/// Moves slightly backwards at the start and overshoots
+	/// the target value at the end.
+	InOutBack,
+	/// Oscillates with increasing intensity before moving
+	/// to the target value.
+	InElastic,
+	/// Overshoots the target value and oscillates around it
+	/// like a spring before settling.
+	OutElastic,
+	/// Oscillates at both the start and the end of the motion.
+	InOutElastic,
+	/// Bounces away from the starting value with increasing
+	/// height before moving to the target value.
+	InBounce,
+	/// Bounces against the target value like a ball hitting
+	/// the floor.
+	OutBounce,
+	/// Bounces at both the start and the end of the motion.
+	InOutBounce,
+	/// Follows a cubic Bézier curve from `(0, 0)` to `(1, 1)`
+	/// with the control points `(x1, y1)` and `(x2, y2)`.
+	///
+	/// This works the same way as the CSS `cubic-bezier`
+	/// timing function. `x1` and `x2` should be between
+	/// `0.0` and `1.0`.
+	CubicBezier(f64, f64, f64, f64),
 }
 
 impl Easing {
-	fn apply(&self, mut x: f64) -> f64 {
+	fn apply(&self, x: f64) -> f64 {
 		match self {
 			Easing::Linear => x,
 			Easing::InPowi(power) => x.powi(*power),
-			Easing::OutPowi(power) => 1.0 - Self::InPowi(*power).apply(1.0 - x),
-			Easing::InOutPowi(power) => {
-				x *= 2.0;
-				if x < 1.0 {
-					0.5 * Self::InPowi(*power).apply(x)
-				} else {
-					x = 2.0 - x;
-					0.5 * (1.0 - Self::InPowi(*power).apply(x)) + 0.5
-				}
-			}
+			Easing::OutPowi(power) => out(x, |x| x.powi(*power)),
+			Easing::InOutPowi(power) => in_out(x, |x| x.powi(*power)),
 			Easing::InPowf(power) => x.powf(*power),
-			Easing::OutPowf(power) => 1.0 - Self::InPowf(*power).apply(1.0 - x),
-			Easing::InOutPowf(power) => {
-				x *= 2.0;
-				if x < 1.0 {
-					0.5 * Self::InPowf(*power).apply(x)
-				} else {
-					x = 2.0 - x;
-					0.5 * (1.0 - Self::InPowf(*power).apply(x)) + 0.5
-				}
-			}
+			Easing::OutPowf(power) => out(x, |x| x.powf(*power)),
+			Easing::InOutPowf(power) => in_out(x, |x| x.powf(*power)),
+			Easing::InSine => in_sine(x),
+			Easing::OutSine => out(x, in_sine),
+			Easing::InOutSine => in_out(x, in_sine),
+			Easing::InExpo => in_expo(x),
+			Easing::OutExpo => out(x, in_expo),
+			Easing::InOutExpo => in_out(x, in_expo),
+			Easing::InCirc => in_circ(x),
+			Easing::OutCirc => out(x, in_circ),
+			Easing::InOutCirc => in_out(x, in_circ),
+			Easing::InBack => in_back(x),
+			Easing::OutBack => out(x, in_back),
+			Easing::InOutBack => in_out(x, in_back),
+			Easing::InElastic => in_elastic(x),
+			Easing::OutElastic => out(x, in_elastic),
+			Easing::InOutElastic => in_out(x, in_elastic),
+			Easing::InBounce => out(x, out_bounce),
+			Easing::OutBounce => out_bounce(x),
+			Easing::InOutBounce => in_out(x, |x| out(x, out_bounce)),
+			Easing::CubicBezier(x1, y1, x2, y2) => cubic_bezier(x, *x1, *y1, *x2, *y2),
 		}
 	}
 }
 
+/// Turns an "in" easing function into an "out" easing function.
+fn out(x: f64, f: impl Fn(f64) -> f64) -> f64 {
+	1.0 - f(1.0 - x)
+}
+
+/// Turns an "in" easing function into an "in-out" easing function.
+fn in_out(mut x: f64, f: impl Fn(f64) -> f64) -> f64 {
+	x *= 2.0;
+	if x < 1.0 {
+		0.5 * f(x)
+	} else {
+		x = 2.0 - x;
+		0.5 * (1.0 - f(x)) + 0.5
+	}
+}
+
+fn in_sine(x: f64) -> f64 {
+	1.0 - (x * PI / 2.0).cos()
+}
+
+fn in_expo(x: f64) -> f64 {
+	if x <= 0.0 {
+		0.0
+	} else {
+		2.0f64.powf(10.0 * x - 10.0)
+	}
+}
+
+fn in_circ(x: f64) -> f64 {
+	1.0 - (1.0 - x * x).max(0.0).sqrt()
+}
+
+fn in_back(x: f64) -> f64 {
+	const C1: f64 = 1.70158;
+	const C3: f64 = C1 + 1.0;
+	C3 * x * x * x - C1 * x * x
+}
+
+fn in_elastic(x: f64) -> f64 {
+	if x <= 0.0 {
+		0.0
+	} else if x >= 1.0 {
+		1.0
+	} else {
+		-(2.0f64.powf(10.0 * x - 10.0)) * ((x * 10.0 - 10.75) * TAU / 3.0).sin()
+	}
+}
+
+fn out_bounce(x: f64) -> f64 {
+	const N1: f64 = 7.5625;
+	const D1: f64 = 2.75;
+	if x < 1.0 / D1 {
+		N1 * x * x
+	} else if x < 2.0 / D1 {
+		let x = x - 1.5 / D1;
+		N1 * x * x + 0.75
+	} else if x < 2.5 / D1 {
+		let x = x - 2.25 / D1;
+		N1 * x * x + 0.9375
+	} else {
+		let x = x - 2.625 / D1;
+		N1 * x * x + 0.984375
+	}
+}
+
+/// Evaluates a cubic Bézier curve from (0, 0) to (1, 1) with
+/// the control points (x1, y1) and (x2, y2) at the given x position.
+fn cubic_bezier(x: f64, x1: f64, y1: f64, x2: f64, y2: f64) -> f64 {
+	const NEWTON_ITERATIONS: usize = 8;
+	const BISECTION_ITERATIONS: usize = 32;
+	const EPSILON: f64 = 1.0e-7;
+
+	let x = x.clamp(0.0, 1.0);
+	// the coefficients of the polynomial form of the curve
+	let (cx, cy) = (3.0 * x1, 3.0 * y1);
+	let (bx, by) = (3.0 * (x2 - x1) - cx, 3.0 * (y2 - y1) - cy);
+	let (ax, ay) = (1.0 - cx - bx, 1.0 - cy - by);
+	let curve_x = |t: f64| ((ax * t + bx) * t + cx) * t;
+	let curve_y = |t: f64| ((ay * t + by) * t + cy) * t;
+	let slope_x = |t: f64| (3.0 * ax * t + 2.0 * bx) * t + cx;
+
+	// find the t value for the given x value. newton's method
+	// is fast, but it doesn't always converge, so fall back to
+	// bisection if needed
+	let mut t = x;
+	for _ in 0..NEWTON_ITERATIONS {
+		let error = curve_x(t) - x;
+		if error.abs() < EPSILON {
+			return curve_y(t);
+		}
+		let slope = slope_x(t);
+		if slope.abs() < EPSILON {
+			break;
+		}
+		t -= error / slope;
+	}
+	let (mut lower, mut upper) = (0.0, 1.0);
+	t = x;
+	for _ in 0..BISECTION_ITERATIONS {
+		let error = curve_x(t) - x;
+		if error.abs() < EPSILON {
+			break;
+		}
+		if error > 0.0 {
+			upper = t;
+		} else {
+			lower = t;
+		}
+		t = (lower + upper) / 2.0;
+	}
+	curve_y(t)
+}
+
 impl Default for Easing {
 	fn default() -> Self {
 		Self::Linear
@@ -91,13 +266,19 @@ pub struct Tween {
 	pub start_time: StartTime,
 	/// The duration of the motion.
 	pub duration: Duration,
+	/// The duration of the motion in ticks of a clock.
+	///
+	/// If this is set, `duration` is ignored, and the motion
+	/// will speed up or slow down along with the clock. The
+	/// motion will not progress while the clock is paused.
+	pub clock_duration: Option<ClockDuration>,
 	/// The curve of the motion.
 	pub easing: Easing,
 }
 
 impl Tween {
-	pub(super) fn value(&self, time: f64) -> f64 {
-		self.easing.apply(time / self.duration.as_secs_f64())
+	pub(super) fn value(&self, progress: f64) -> f64 {
+		self.easing.apply(progress)
 	}
 }
 
@@ -106,6 +287,7 @@ impl Default for Tween {
 		Self {
 			start_time: StartTime::default(),
 			duration: Duration::from_millis(10),
+			clock_duration: None,
 			easing: Easing::Linear,
 		}
 	}
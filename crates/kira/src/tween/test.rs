@@ -0,0 +1,144 @@
+use std::time::Duration;
+
+use ringbuf::RingBuffer;
+
+use crate::{
+	clock::{Clock, ClockDuration, ClockId, Clocks},
+	manager::command::ClockCommand,
+	modulator::Modulators,
+	parameter::Parameters,
+	value::{Expressions, Value},
+};
+
+use super::{Easing, Tween, Tweenable};
+
+const EASINGS: &[Easing] = &[
+	Easing::Linear,
+	Easing::InPowi(2),
+	Easing::OutPowi(2),
+	Easing::InOutPowi(2),
+	Easing::InPowf(2.5),
+	Easing::OutPowf(2.5),
+	Easing::InOutPowf(2.5),
+	Easing::InSine,
+	Easing::OutSine,
+	Easing::InOutSine,
+	Easing::InExpo,
+	Easing::OutExpo,
+	Easing::InOutExpo,
+	Easing::InCirc,
+	Easing::OutCirc,
+	Easing::InOutCirc,
+	Easing::InBack,
+	Easing::OutBack,
+	Easing::InOutBack,
+	Easing::InElastic,
+	Easing::OutElastic,
+	Easing::InOutElastic,
+	Easing::InBounce,
+	Easing::OutBounce,
+	Easing::InOutBounce,
+	Easing::CubicBezier(0.25, 0.1, 0.25, 1.0),
+];
+
+#[test]
+fn easings_start_at_0_and_end_at_1() {
+	for easing in EASINGS {
+		assert!(easing.apply(0.0).abs() < 1.0e-3, "{:?}", easing);
+		assert!((easing.apply(1.0) - 1.0).abs() < 1.0e-3, "{:?}", easing);
+	}
+}
+
+#[test]
+fn in_out_easings_are_symmetrical() {
+	for easing in [
+		Easing::InOutSine,
+		Easing::InOutCirc,
+		Easing::InOutBack,
+		Easing::InOutBounce,
+	] {
+		for i in 0..=10 {
+			let x = i as f64 / 10.0;
+			assert!(
+				(easing.apply(x) + easing.apply(1.0 - x) - 1.0).abs() < 1.0e-9,
+				"{:?}",
+				easing
+			);
+		}
+	}
+}
+
+#[test]
+fn cubic_bezier() {
+	// a bezier curve with control points on the diagonal is linear
+	let linear = Easing::CubicBezier(1.0 / 3.0, 1.0 / 3.0, 2.0 / 3.0, 2.0 / 3.0);
+	for i in 0..=10 {
+		let x = i as f64 / 10.0;
+		assert!((linear.apply(x) - x).abs() < 1.0e-6);
+	}
+	// css "ease-in-out"
+	let ease_in_out = Easing::CubicBezier(0.42, 0.0, 0.58, 1.0);
+	assert!((ease_in_out.apply(0.5) - 0.5).abs() < 1.0e-6);
+	assert!(ease_in_out.apply(0.25) < 0.25);
+	assert!(ease_in_out.apply(0.75) > 0.75);
+}
+
+#[test]
+fn tween_with_clock_duration() {
+	let (unused_clock_producer, _) = RingBuffer::new(1).split();
+	let mut clocks = Clocks::new(1, unused_clock_producer);
+	let id = ClockId(clocks.controller().try_reserve().unwrap());
+	clocks.run_command(ClockCommand::Add(id, Clock::new(Value::Fixed(1.0))));
+	clocks.run_command(ClockCommand::Start(id));
+	let (unused_parameter_producer, _) = RingBuffer::new(1).split();
+	let (unused_automation_producer, _) = RingBuffer::new(1).split();
+	let (unused_modulator_producer, _) = RingBuffer::new(1).split();
+	let (unused_expression_producer, _) = RingBuffer::new(1).split();
+	let parameters = Parameters::new(
+		1,
+		unused_parameter_producer,
+		unused_automation_producer,
+		Modulators::new(1, unused_modulator_producer),
+		Expressions::new(1, unused_expression_producer),
+	);
+	let mut tweenable = Tweenable::new(0.0);
+	tweenable.set(
+		1.0,
+		Tween {
+			clock_duration: Some(ClockDuration {
+				clock: id,
+				ticks: 4.0,
+			}),
+			..Default::default()
+		},
+	);
+	let (mut event_producer, _) = RingBuffer::new(1).split();
+	let mut update = |clocks: &mut Clocks, dt: f64| {
+		clocks.update(dt, &parameters, &mut event_producer);
+		tweenable.update(dt, clocks);
+		tweenable.value()
+	};
+	update(&mut clocks, 0.0);
+	assert_eq!(update(&mut clocks, 1.0), 0.25);
+	// doubling the clock speed doubles the speed of the tween
+	clocks.run_command(ClockCommand::SetInterval(id, Value::Fixed(0.5)));
+	assert_eq!(update(&mut clocks, 0.5), 0.5);
+	assert_eq!(update(&mut clocks, 0.5), 0.75);
+	assert_eq!(update(&mut clocks, 0.5), 1.0);
+}
+
+#[test]
+fn zero_length_tweens_finish_immediately() {
+	let (unused_clock_producer, _) = RingBuffer::new(1).split();
+	let clocks = Clocks::new(1, unused_clock_producer);
+	let mut tweenable = Tweenable::new(0.0);
+	tweenable.set(
+		1.0,
+		Tween {
+			duration: Duration::ZERO,
+			..Default::default()
+		},
+	);
+	assert!(tweenable.update(0.0, &clocks));
+	assert_eq!(tweenable.value(), 1.0);
+}
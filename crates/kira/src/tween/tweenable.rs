@@ -1,5 +1,5 @@
 use crate::{
-	clock::{ClockTime, Clocks},
+	clock::{ClockDuration, ClockTime, Clocks},
 	StartTime,
 };
 
@@ -13,6 +13,7 @@ enum State {
 	Tweening {
 		values: (f64, f64),
 		time: f64,
+		last_clock_position: Option<f64>,
 		tween: Tween,
 		waiting_to_start: bool,
 	},
@@ -50,6 +51,7 @@ impl Tweenable {
 		self.state = State::Tweening {
 			values: (self.value, target),
 			time: 0.0,
+			last_clock_position: None,
 			tween,
 			waiting_to_start: matches!(tween.start_time, StartTime::ClockTime(..)),
 		}
@@ -58,9 +60,16 @@ impl Tweenable {
 	/// Updates the [`Tweenable`] and returns `true` if it just finished
 	/// a tween that was in progress.
 	pub fn update(&mut self, dt: f64, clocks: &Clocks) -> JustFinishedTween {
+		self.update_with_leftover_time(dt, clocks).is_some()
+	}
+
+	/// Updates the [`Tweenable`]. If it just finished a tween, returns
+	/// how much of `dt` (in seconds) was left over after the tween ended.
+	pub(crate) fn update_with_leftover_time(&mut self, dt: f64, clocks: &Clocks) -> Option<f64> {
 		if let State::Tweening {
 			values,
 			time,
+			last_clock_position,
 			tween,
 			waiting_to_start,
 		} = &mut self.state
@@ -79,17 +88,51 @@ impl Tweenable {
 				}
 			}
 			if *waiting_to_start {
-				return false;
+				return None;
 			}
-			*time += dt;
-			if *time >= tween.duration.as_secs_f64() {
+			let mut leftover_time = 0.0;
+			let progress = match tween.clock_duration {
+				Some(ClockDuration { clock, ticks }) => match clocks.get(clock) {
+					Some(clock) => {
+						// measure how far the clock has moved since the
+						// last update, so the tween stretches if the
+						// clock's interval changes
+						let clock_position = clock.fractional_position();
+						if let Some(last_clock_position) = *last_clock_position {
+							*time += (clock_position - last_clock_position).max(0.0);
+						}
+						*last_clock_position = Some(clock_position);
+						if ticks > 0.0 {
+							*time / ticks
+						} else {
+							1.0
+						}
+					}
+					// if the clock doesn't exist, the tween can never
+					// finish, so just skip to the end
+					None => 1.0,
+				},
+				None => {
+					*time += dt;
+					let duration = tween.duration.as_secs_f64();
+					// compare before dividing so tweens with a duration
+					// of zero finish right away instead of producing NaN
+					if *time >= duration {
+						leftover_time = *time - duration;
+						1.0
+					} else {
+						*time / duration
+					}
+				}
+			};
+			if progress >= 1.0 {
 				self.value = values.1;
 				self.state = State::Idle;
-				return true;
+				return Some(leftover_time);
 			} else {
-				self.value = values.0 + (values.1 - values.0) * tween.value(*time);
+				self.value = values.0 + (values.1 - values.0) * tween.value(progress);
 			}
 		}
-		false
+		None
 	}
 }
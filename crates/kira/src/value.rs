@@ -2,12 +2,20 @@
 //! throughout Kira.
 
 mod cached;
+mod expression;
 mod mapping;
 
 pub use cached::*;
+pub use expression::*;
 pub use mapping::*;
 
-use crate::parameter::{ParameterHandle, ParameterId};
+use crate::{
+	modulator::{ModulatorHandle, ModulatorId},
+	parameter::{ParameterHandle, ParameterId, Parameters},
+};
+
+#[cfg(test)]
+mod test;
 
 /// The possible values for a setting.
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -23,6 +31,42 @@ pub enum Value {
 		/// The mapping of parameter values to setting values.
 		mapping: Mapping,
 	},
+	/// The setting is linked to a modulator with the
+	/// given mapping.
+	Modulator {
+		/// The modulator the setting is linked to.
+		id: ModulatorId,
+		/// The mapping of modulator values to setting values.
+		mapping: Mapping,
+	},
+	/// The setting is linked to an expression that combines
+	/// other values, with the given mapping.
+	Expression {
+		/// The expression the setting is linked to.
+		id: ExpressionId,
+		/// The mapping of expression values to setting values.
+		mapping: Mapping,
+	},
+}
+
+impl Value {
+	/// Returns the current raw value, or `None` if the value
+	/// depends on a parameter that no longer exists.
+	pub(crate) fn raw_value(&self, parameters: &Parameters) -> Option<f64> {
+		match self {
+			Value::Fixed(value) => Some(*value),
+			Value::Parameter { id, mapping } => parameters
+				.get(*id)
+				.map(|parameter| mapping.map(parameter.value())),
+			Value::Modulator { id, mapping } => parameters
+				.modulator(*id)
+				.map(|modulator| mapping.map(modulator.value())),
+			Value::Expression { id, mapping } => parameters
+				.expression(*id)
+				.and_then(|expression| expression.evaluate(parameters))
+				.map(|value| mapping.map(value)),
+		}
+	}
 }
 
 impl From<f64> for Value {
@@ -48,3 +92,39 @@ impl From<&ParameterHandle> for Value {
 		}
 	}
 }
+
+impl From<ModulatorId> for Value {
+	fn from(id: ModulatorId) -> Self {
+		Self::Modulator {
+			id,
+			mapping: Default::default(),
+		}
+	}
+}
+
+impl From<&ModulatorHandle> for Value {
+	fn from(handle: &ModulatorHandle) -> Self {
+		Self::Modulator {
+			id: handle.id(),
+			mapping: Default::default(),
+		}
+	}
+}
+
+impl From<ExpressionId> for Value {
+	fn from(id: ExpressionId) -> Self {
+		Self::Expression {
+			id,
+			mapping: Default::default(),
+		}
+	}
+}
+
+impl From<&ExpressionHandle> for Value {
+	fn from(handle: &ExpressionHandle) -> Self {
+		Self::Expression {
+			id: handle.id(),
+			mapping: Default::default(),
+		}
+	}
+}
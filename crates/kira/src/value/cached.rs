@@ -79,11 +79,11 @@ impl CachedValue {
 	pub fn new(valid_range: impl Into<ValidRange>, value: Value, default: f64) -> Self {
 		Self {
 			valid_range: valid_range.into(),
-			value,
 			raw_value: match value {
 				Value::Fixed(value) => value,
-				Value::Parameter { .. } => default,
+				_ => default,
 			},
+			value,
 		}
 	}
 
@@ -95,17 +95,18 @@ impl CachedValue {
 	/// Sets the value.
 	pub fn set(&mut self, value: Value) {
 		self.value = value;
-		if let Value::Fixed(raw_value) = self.value {
-			self.raw_value = self.valid_range.clamp(raw_value);
+		if let Value::Fixed(raw_value) = &self.value {
+			self.raw_value = self.valid_range.clamp(*raw_value);
 		}
 	}
 
 	/// Updates the [`CachedValue`] with the current values of parameters.
 	pub fn update(&mut self, parameters: &Parameters) {
-		if let Value::Parameter { id, mapping } = self.value {
-			if let Some(parameter) = parameters.get(id) {
-				self.raw_value = self.valid_range.clamp(mapping.map(parameter.value()));
-			}
+		if let Value::Fixed(..) = &self.value {
+			return;
+		}
+		if let Some(raw_value) = self.value.raw_value(parameters) {
+			self.raw_value = self.valid_range.clamp(raw_value);
 		}
 	}
 }
@@ -0,0 +1,145 @@
+mod expressions;
+mod handle;
+
+pub use handle::*;
+
+pub(crate) use expressions::Expressions;
+
+use std::sync::{
+	atomic::{AtomicBool, Ordering},
+	Arc,
+};
+
+use atomic_arena::Key;
+
+use crate::parameter::Parameters;
+
+use super::{Mapping, Value};
+
+/// A unique identifier for an [`Expression`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ExpressionId(pub(crate) Key);
+
+pub(crate) struct ExpressionShared {
+	removed: AtomicBool,
+}
+
+impl ExpressionShared {
+	pub fn new() -> Self {
+		Self {
+			removed: AtomicBool::new(false),
+		}
+	}
+
+	pub fn is_marked_for_removal(&self) -> bool {
+		self.removed.load(Ordering::SeqCst)
+	}
+
+	pub fn mark_for_removal(&self) {
+		self.removed.store(true, Ordering::SeqCst);
+	}
+}
+
+/// A combination of multiple [`Value`]s.
+///
+/// Expressions are created with
+/// [`AudioManager::add_expression`](crate::manager::AudioManager::add_expression)
+/// and evaluated on the audio thread, so settings that use them
+/// stay in sync with the parameters they depend on.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Expression {
+	/// The sum of the values.
+	Sum(Vec<Value>),
+	/// The product of the values.
+	Product(Vec<Value>),
+	/// The smallest of the values.
+	Min(Vec<Value>),
+	/// The largest of the values.
+	Max(Vec<Value>),
+	/// A value transformed by a mapping.
+	Mapped {
+		/// The value to transform.
+		value: Value,
+		/// The mapping of input values to output values.
+		mapping: Mapping,
+	},
+}
+
+impl Expression {
+	/// Creates an [`Expression`] that is the sum of the given values.
+	pub fn sum(values: impl IntoIterator<Item = Value>) -> Self {
+		Self::Sum(values.into_iter().collect())
+	}
+
+	/// Creates an [`Expression`] that is the product of the given values.
+	pub fn product(values: impl IntoIterator<Item = Value>) -> Self {
+		Self::Product(values.into_iter().collect())
+	}
+
+	/// Creates an [`Expression`] that is the smallest of the given values.
+	pub fn min(values: impl IntoIterator<Item = Value>) -> Self {
+		Self::Min(values.into_iter().collect())
+	}
+
+	/// Creates an [`Expression`] that is the largest of the given values.
+	pub fn max(values: impl IntoIterator<Item = Value>) -> Self {
+		Self::Max(values.into_iter().collect())
+	}
+
+	/// Creates an [`Expression`] that transforms a value with
+	/// the given mapping.
+	pub fn mapped(value: impl Into<Value>, mapping: Mapping) -> Self {
+		Self::Mapped {
+			value: value.into(),
+			mapping,
+		}
+	}
+
+	pub(crate) fn evaluate(&self, parameters: &Parameters) -> Option<f64> {
+		match self {
+			Expression::Sum(values) => values
+				.iter()
+				.try_fold(0.0, |sum, value| Some(sum + value.raw_value(parameters)?)),
+			Expression::Product(values) => values.iter().try_fold(1.0, |product, value| {
+				Some(product * value.raw_value(parameters)?)
+			}),
+			Expression::Min(values) => reduce(values, parameters, |a: f64, b: f64| a.min(b)),
+			Expression::Max(values) => reduce(values, parameters, |a: f64, b: f64| a.max(b)),
+			Expression::Mapped { value, mapping } => {
+				value.raw_value(parameters).map(|value| mapping.map(value))
+			}
+		}
+	}
+}
+
+/// An [`Expression`] that lives on the audio thread.
+pub(crate) struct LiveExpression {
+	expression: Expression,
+	shared: Arc<ExpressionShared>,
+}
+
+impl LiveExpression {
+	pub fn new(expression: Expression) -> Self {
+		Self {
+			expression,
+			shared: Arc::new(ExpressionShared::new()),
+		}
+	}
+
+	pub fn shared(&self) -> Arc<ExpressionShared> {
+		self.shared.clone()
+	}
+
+	pub fn evaluate(&self, parameters: &Parameters) -> Option<f64> {
+		self.expression.evaluate(parameters)
+	}
+}
+
+fn reduce(values: &[Value], parameters: &Parameters, f: impl Fn(f64, f64) -> f64) -> Option<f64> {
+	let (first, rest) = values.split_first()?;
+	rest.iter()
+		.try_fold(first.raw_value(parameters)?, |acc, value| {
+			Some(f(acc, value.raw_value(parameters)?))
+		})
+}
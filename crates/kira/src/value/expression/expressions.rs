@@ -0,0 +1,54 @@
+use atomic_arena::{Arena, Controller};
+use ringbuf::Producer;
+
+use crate::manager::command::ExpressionCommand;
+
+use super::{ExpressionId, LiveExpression};
+
+pub(crate) struct Expressions {
+	expressions: Arena<LiveExpression>,
+	unused_expression_producer: Producer<LiveExpression>,
+}
+
+impl Expressions {
+	pub fn new(capacity: usize, unused_expression_producer: Producer<LiveExpression>) -> Self {
+		Self {
+			expressions: Arena::new(capacity),
+			unused_expression_producer,
+		}
+	}
+
+	pub fn controller(&self) -> Controller {
+		self.expressions.controller()
+	}
+
+	pub fn get(&self, id: ExpressionId) -> Option<&LiveExpression> {
+		self.expressions.get(id.0)
+	}
+
+	pub fn on_start_processing(&mut self) {
+		if self.unused_expression_producer.is_full() {
+			return;
+		}
+		for (_, expression) in self
+			.expressions
+			.drain_filter(|expression| expression.shared().is_marked_for_removal())
+		{
+			if self.unused_expression_producer.push(expression).is_err() {
+				panic!("Unused expression producer is full")
+			}
+			if self.unused_expression_producer.is_full() {
+				return;
+			}
+		}
+	}
+
+	pub fn run_command(&mut self, command: ExpressionCommand) {
+		match command {
+			ExpressionCommand::Add(id, expression) => self
+				.expressions
+				.insert_with_key(id.0, expression)
+				.expect("Expression arena is full"),
+		}
+	}
+}
@@ -0,0 +1,32 @@
+use std::sync::Arc;
+
+use super::{ExpressionId, ExpressionShared};
+
+/// Refers to an [`Expression`](super::Expression) that was added
+/// to an [`AudioManager`](crate::manager::AudioManager).
+///
+/// When an [`ExpressionHandle`] is dropped, the corresponding
+/// expression will be removed.
+pub struct ExpressionHandle {
+	pub(crate) id: ExpressionId,
+	pub(crate) shared: Arc<ExpressionShared>,
+}
+
+impl ExpressionHandle {
+	/// Returns the unique identifier for the expression.
+	pub fn id(&self) -> ExpressionId {
+		self.id
+	}
+}
+
+impl Drop for ExpressionHandle {
+	fn drop(&mut self) {
+		self.shared.mark_for_removal();
+	}
+}
+
+impl From<&ExpressionHandle> for ExpressionId {
+	fn from(handle: &ExpressionHandle) -> Self {
+		handle.id()
+	}
+}
@@ -0,0 +1,96 @@
+use ringbuf::RingBuffer;
+
+use crate::{
+	manager::command::{ExpressionCommand, ParameterCommand},
+	modulator::Modulators,
+	parameter::{Parameter, ParameterId, Parameters},
+};
+
+use super::{CachedValue, Expression, ExpressionId, Expressions, LiveExpression, Mapping, Value};
+
+fn create_parameters(values: &[f64]) -> (Parameters, Vec<ParameterId>) {
+	let (unused_parameter_producer, _) = RingBuffer::new(1).split();
+	let (unused_automation_producer, _) = RingBuffer::new(1).split();
+	let (unused_modulator_producer, _) = RingBuffer::new(1).split();
+	let (unused_expression_producer, _) = RingBuffer::new(1).split();
+	let mut parameters = Parameters::new(
+		values.len(),
+		unused_parameter_producer,
+		unused_automation_producer,
+		Modulators::new(1, unused_modulator_producer),
+		Expressions::new(8, unused_expression_producer),
+	);
+	let mut ids = vec![];
+	for value in values {
+		let id = ParameterId(parameters.controller().try_reserve().unwrap());
+		parameters.run_command(ParameterCommand::Add(id, Parameter::new(*value)));
+		ids.push(id);
+	}
+	(parameters, ids)
+}
+
+fn add_expression(parameters: &mut Parameters, expression: Expression) -> Value {
+	let id = ExpressionId(parameters.expression_controller().try_reserve().unwrap());
+	parameters.run_expression_command(ExpressionCommand::Add(id, LiveExpression::new(expression)));
+	id.into()
+}
+
+#[test]
+fn expressions() {
+	let (mut parameters, ids) = create_parameters(&[2.0, 3.0, 0.25]);
+	let values = || ids.iter().map(|id| Value::from(*id));
+	let sum = add_expression(&mut parameters, Expression::sum(values()));
+	assert_eq!(sum.raw_value(&parameters), Some(5.25));
+	let product = add_expression(&mut parameters, Expression::product(values()));
+	assert_eq!(product.raw_value(&parameters), Some(1.5));
+	let min = add_expression(&mut parameters, Expression::min(values()));
+	assert_eq!(min.raw_value(&parameters), Some(0.25));
+	let max = add_expression(&mut parameters, Expression::max(values()));
+	assert_eq!(max.raw_value(&parameters), Some(3.0));
+	let empty = add_expression(&mut parameters, Expression::min([]));
+	assert_eq!(empty.raw_value(&parameters), None);
+	// expressions can be nested
+	let mapped = add_expression(
+		&mut parameters,
+		Expression::mapped(
+			sum,
+			Mapping {
+				input_range: (0.0, 1.0),
+				output_range: (1.0, 0.0),
+				..Default::default()
+			},
+		),
+	);
+	assert_eq!(mapped.raw_value(&parameters), Some(-4.25));
+}
+
+#[test]
+fn cached_value_evaluates_expressions() {
+	let (mut parameters, ids) = create_parameters(&[0.5, 0.5, 0.25]);
+	let product = add_expression(
+		&mut parameters,
+		Expression::product([
+			ids[0].into(),
+			ids[1].into(),
+			Value::Parameter {
+				id: ids[2],
+				mapping: Mapping {
+					input_range: (0.0, 1.0),
+					output_range: (1.0, 0.0),
+					..Default::default()
+				},
+			},
+		]),
+	);
+	let mut cached_value = CachedValue::new(0.0..=1.0, product, 1.0);
+	assert_eq!(cached_value.get(), 1.0);
+	cached_value.update(&parameters);
+	assert_eq!(cached_value.get(), 0.1875);
+	let sum = add_expression(
+		&mut parameters,
+		Expression::sum([Value::Fixed(2.0), ids[0].into()]),
+	);
+	cached_value.set(sum);
+	cached_value.update(&parameters);
+	assert_eq!(cached_value.get(), 1.0);
+}